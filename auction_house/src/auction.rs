@@ -1,25 +1,38 @@
 use ark_ec::ProjectiveCurve;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use digest::Digest;
+use primitive_types::U256;
 use rand::{CryptoRng, Rng};
 use std::{
     collections::{HashMap, HashSet},
+    io::{Read, Write},
     marker::PhantomData,
     time::{Duration, Instant},
 };
 
-use crate::{AuctionError, Error};
+use crate::{sealed_bid::SealedBidScheme, AuctionError, Error};
 use rsa::{hash_to_prime::HashToPrime, hog::RsaGroupParams, poe::PoEParams};
-use timed_commitments::{
-    basic_tc::TimeParams,
-    lazy_tc::{Comm as TCComm, LazyTC, Opening as TCOpening},
-    PedComm, PedersenParams,
-};
+use timed_commitments::{basic_tc::TimeParams, lazy_tc::LazyTC, PedComm, PedersenParams};
+
+// Phase boundaries either in wall-clock time since the auction was created, or in block numbers
+// as seen by the contract
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PhaseTiming {
+    WallClock {
+        t_bid_collection: Duration,
+        t_bid_self_open: Duration,
+    },
+    BlockBased {
+        collection_blocks: u64,
+        self_open_blocks: u64,
+        start_block: u64,
+    },
+}
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct AuctionParams<G: ProjectiveCurve, RsaP: RsaGroupParams> {
-    pub t_bid_collection: Duration,
-    pub t_bid_self_open: Duration,
+    pub timing: PhaseTiming,
     pub time_pp: TimeParams<RsaP>,
     pub ped_pp: PedersenParams<G>,
 }
@@ -30,36 +43,186 @@ pub struct Auction<
     RsaP: RsaGroupParams,
     H: Digest,
     H2P: HashToPrime,
+    S: SealedBidScheme<G, RsaP> = LazyTC<G, PoEP, RsaP, H, H2P>,
 > {
     t_start: Instant,
-    pub bid_comms_i: HashMap<usize, TCComm<G, RsaP>>, // index -> commitment
-    bid_comms_set: HashSet<TCComm<G, RsaP>>,          // commitments
-    pub bid_openings: HashMap<usize, Option<u32>>,    // index -> bid
+    block: u64,
+    pub bid_comms_i: HashMap<usize, S::Comm>, // index -> commitment
+    bid_comms_set: HashSet<S::Comm>,          // commitments
+    pub bid_openings: HashMap<usize, Option<u32>>, // index -> bid
+    bid_force_opened: HashSet<usize>,         // indices of force opened bids
     _poe_params: PhantomData<PoEP>,
     _hash: PhantomData<H>,
     _hash_to_prime: PhantomData<H2P>,
+    _scheme: PhantomData<S>,
+}
+
+impl<G: ProjectiveCurve, RsaP: RsaGroupParams> AuctionParams<G, RsaP> {
+    // Mirrors `getAuctionPhase` in AuctionHouse.sol with instants in place of block numbers
+    pub fn phase_at(
+        &self,
+        t_start: Instant,
+        now: Instant,
+        block: u64,
+        bids_to_open: usize,
+    ) -> AuctionPhase {
+        self.timing.phase_at(t_start, now, block, bids_to_open)
+    }
 }
 
+impl PhaseTiming {
+    pub fn phase_at(
+        &self,
+        t_start: Instant,
+        now: Instant,
+        block: u64,
+        bids_to_open: usize,
+    ) -> AuctionPhase {
+        let (in_collection, in_self_open) = match *self {
+            PhaseTiming::WallClock {
+                t_bid_collection,
+                t_bid_self_open,
+            } => {
+                let t_auction = now.saturating_duration_since(t_start);
+                (
+                    t_auction < t_bid_collection,
+                    t_auction < t_bid_collection + t_bid_self_open,
+                )
+            }
+            PhaseTiming::BlockBased {
+                collection_blocks,
+                self_open_blocks,
+                start_block,
+            } => {
+                let blocks = block.saturating_sub(start_block);
+                (
+                    blocks < collection_blocks,
+                    blocks < collection_blocks + self_open_blocks,
+                )
+            }
+        };
+        if in_collection {
+            AuctionPhase::BidCollection
+        } else if bids_to_open == 0 {
+            AuctionPhase::Complete
+        } else if in_self_open {
+            AuctionPhase::BidSelfOpening
+        } else {
+            AuctionPhase::BidForceOpening
+        }
+    }
+
+    // Tag followed by the phase boundaries, durations in milliseconds
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        match self {
+            PhaseTiming::WallClock {
+                t_bid_collection,
+                t_bid_self_open,
+            } => {
+                0u8.serialize(&mut writer)?;
+                (t_bid_collection.as_millis() as u64).serialize(&mut writer)?;
+                (t_bid_self_open.as_millis() as u64).serialize(&mut writer)?;
+            }
+            PhaseTiming::BlockBased {
+                collection_blocks,
+                self_open_blocks,
+                start_block,
+            } => {
+                1u8.serialize(&mut writer)?;
+                collection_blocks.serialize(&mut writer)?;
+                self_open_blocks.serialize(&mut writer)?;
+                start_block.serialize(&mut writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, Error> {
+        match u8::deserialize(&mut reader)? {
+            0 => Ok(PhaseTiming::WallClock {
+                t_bid_collection: Duration::from_millis(u64::deserialize(&mut reader)?),
+                t_bid_self_open: Duration::from_millis(u64::deserialize(&mut reader)?),
+            }),
+            1 => Ok(PhaseTiming::BlockBased {
+                collection_blocks: u64::deserialize(&mut reader)?,
+                self_open_blocks: u64::deserialize(&mut reader)?,
+                start_block: u64::deserialize(&mut reader)?,
+            }),
+            _ => Err(Box::new(AuctionError::InvalidPhase)),
+        }
+    }
+}
+
+// Borrowed view of a bid, `Forfeited` is a force opened commitment that did not open to a valid bid
 #[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BidState<'a, C> {
+    Sealed(&'a C),
+    SelfOpened(u32),
+    ForceOpened(u32),
+    Forfeited,
+}
+
+// Inverse of the encoding in `client_create_bid`, `None` for bytes that are not a bid
+fn decode_bid(bytes: &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+// Discriminants match the `AuctionPhase` enum in AuctionHouse.sol, as returned by `getAuctionPhase`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum AuctionPhase {
-    BidCollection,
-    BidSelfOpening,
-    BidForceOpening,
-    Complete,
+    BidCollection = 0,
+    BidSelfOpening = 1,
+    BidForceOpening = 2,
+    Complete = 3,
 }
 
-impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
-    Auction<G, PoEP, RsaP, H, H2P>
+impl TryFrom<u8> for AuctionPhase {
+    type Error = Error;
+
+    fn try_from(phase: u8) -> Result<Self, Error> {
+        match phase {
+            0 => Ok(AuctionPhase::BidCollection),
+            1 => Ok(AuctionPhase::BidSelfOpening),
+            2 => Ok(AuctionPhase::BidForceOpening),
+            3 => Ok(AuctionPhase::Complete),
+            _ => Err(Box::new(AuctionError::InvalidPhase)),
+        }
+    }
+}
+
+impl From<AuctionPhase> for u8 {
+    fn from(phase: AuctionPhase) -> u8 {
+        phase as u8
+    }
+}
+
+impl From<AuctionPhase> for U256 {
+    fn from(phase: AuctionPhase) -> U256 {
+        U256::from(phase as u8)
+    }
+}
+
+impl<
+        G: ProjectiveCurve,
+        PoEP: PoEParams,
+        RsaP: RsaGroupParams,
+        H: Digest,
+        H2P: HashToPrime,
+        S: SealedBidScheme<G, RsaP>,
+    > Auction<G, PoEP, RsaP, H, H2P, S>
 {
     pub fn new(_pp: &AuctionParams<G, RsaP>) -> Self {
         Self {
             t_start: Instant::now(),
+            block: 0,
             bid_comms_i: HashMap::new(),
             bid_comms_set: HashSet::new(),
             bid_openings: HashMap::new(),
+            bid_force_opened: HashSet::new(),
             _poe_params: PhantomData,
             _hash: PhantomData,
             _hash_to_prime: PhantomData,
+            _scheme: PhantomData,
         }
     }
 
@@ -70,70 +233,170 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
     ) -> AuctionPhase {
         match desired_phase {
             Some(phase) => phase,
-            _ => {
-                let t_auction = self.t_start.elapsed();
-                if t_auction < pp.t_bid_collection {
-                    AuctionPhase::BidCollection
-                } else if self.bid_comms_i.len() == self.bid_openings.len() {
-                    AuctionPhase::Complete
-                } else if t_auction < pp.t_bid_collection + pp.t_bid_self_open {
-                    AuctionPhase::BidSelfOpening
-                } else {
-                    AuctionPhase::BidForceOpening
-                }
-            }
+            _ => pp.phase_at(
+                self.t_start,
+                Instant::now(),
+                self.block,
+                self.bid_comms_i.len() - self.bid_openings.len(),
+            ),
         }
     }
 
+    // Phase under `timing` at `now` and `block`, ignoring the block last set on the auction
+    pub fn phase_at(&self, timing: &PhaseTiming, now: Instant, block: u64) -> AuctionPhase {
+        timing.phase_at(
+            self.t_start,
+            now,
+            block,
+            self.bid_comms_i.len() - self.bid_openings.len(),
+        )
+    }
+
+    // Latest block number seen by the contract, used for `PhaseTiming::BlockBased` phases
+    pub fn set_block(&mut self, block: u64) {
+        self.block = block;
+    }
+
+    pub fn block(&self) -> u64 {
+        self.block
+    }
+
+    // Wall-clock start is persisted as the time elapsed so far, so time spent while the auction
+    // is not loaded does not count towards `PhaseTiming::WallClock` deadlines
+    pub fn write_state<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        (self.t_start.elapsed().as_millis() as u64).serialize(&mut writer)?;
+        self.block.serialize(&mut writer)?;
+        let mut indices = self.bid_comms_i.keys().copied().collect::<Vec<_>>();
+        indices.sort_unstable();
+        (indices.len() as u64).serialize(&mut writer)?;
+        for i in indices {
+            (i as u64).serialize(&mut writer)?;
+            S::write_comm(&self.bid_comms_i[&i], &mut writer)?;
+        }
+        let mut openings = self.bid_openings.iter().collect::<Vec<_>>();
+        openings.sort_unstable();
+        (openings.len() as u64).serialize(&mut writer)?;
+        for (i, bid) in openings {
+            (*i as u64).serialize(&mut writer)?;
+            bid.is_some().serialize(&mut writer)?;
+            bid.unwrap_or(0).serialize(&mut writer)?;
+        }
+        let mut force_opened = self
+            .bid_force_opened
+            .iter()
+            .map(|i| *i as u64)
+            .collect::<Vec<_>>();
+        force_opened.sort_unstable();
+        force_opened.serialize(&mut writer)?;
+        Ok(())
+    }
+
+    pub fn read_state<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let elapsed = Duration::from_millis(u64::deserialize(&mut reader)?);
+        let mut auction = Self {
+            t_start: Instant::now()
+                .checked_sub(elapsed)
+                .unwrap_or_else(Instant::now),
+            block: u64::deserialize(&mut reader)?,
+            bid_comms_i: HashMap::new(),
+            bid_comms_set: HashSet::new(),
+            bid_openings: HashMap::new(),
+            bid_force_opened: HashSet::new(),
+            _poe_params: PhantomData,
+            _hash: PhantomData,
+            _hash_to_prime: PhantomData,
+            _scheme: PhantomData,
+        };
+        for _ in 0..u64::deserialize(&mut reader)? {
+            let i = u64::deserialize(&mut reader)? as usize;
+            let comm = S::read_comm(&mut reader)?;
+            auction.bid_comms_set.insert(comm.clone());
+            auction.bid_comms_i.insert(i, comm);
+        }
+        for _ in 0..u64::deserialize(&mut reader)? {
+            let i = u64::deserialize(&mut reader)? as usize;
+            let opened = bool::deserialize(&mut reader)?;
+            let bid = u32::deserialize(&mut reader)?;
+            auction
+                .bid_openings
+                .insert(i, if opened { Some(bid) } else { None });
+        }
+        for i in Vec::<u64>::deserialize(&mut reader)? {
+            auction.bid_force_opened.insert(i as usize);
+        }
+        Ok(auction)
+    }
+
     pub fn client_create_bid<R: CryptoRng + Rng>(
         rng: &mut R,
         pp: &AuctionParams<G, RsaP>,
         bid: u32,
-    ) -> Result<(TCComm<G, RsaP>, TCOpening<G, RsaP, H2P>), Error> {
-        LazyTC::<G, PoEP, RsaP, H, H2P>::commit(rng, &pp.time_pp, &pp.ped_pp, &bid.to_le_bytes())
+    ) -> Result<(S::Comm, S::Opening), Error> {
+        S::commit(rng, &pp.time_pp, &pp.ped_pp, &bid.to_le_bytes())
     }
 
     pub fn force_open_bid(
         &self,
         pp: &AuctionParams<G, RsaP>,
         bid_index: usize,
-    ) -> Result<(Option<u32>, TCOpening<G, RsaP, H2P>), Error> {
-        let (bid_bytes, opening) = LazyTC::<G, PoEP, RsaP, H, H2P>::force_open(
+    ) -> Result<(Option<u32>, S::Opening), Error> {
+        let (bid_bytes, opening) = S::force_open(
             &pp.time_pp,
             &pp.ped_pp,
             self.bid_comms_i
                 .get(&bid_index)
                 .ok_or(Box::new(AuctionError::InvalidBid))?,
         )?;
-        //TODO: Not robust. Will be enforced to be true with range proof
-        Ok((
-            bid_bytes.map(|bytes| u32::from_le_bytes(bytes[..4].try_into().unwrap())),
-            opening,
-        ))
+        Ok((bid_bytes.and_then(|bytes| decode_bid(&bytes)), opening))
     }
 
-    pub fn accept_bid(
-        &mut self,
-        pp: &AuctionParams<G, RsaP>,
-        bid_comm: &TCComm<G, RsaP>,
-    ) -> Result<usize, Error> {
+    // Checks that `accept_bid` would accept the commitment, without accepting it
+    pub fn check_bid(&self, pp: &AuctionParams<G, RsaP>, bid_comm: &S::Comm) -> Result<(), Error> {
         if self.phase(pp, Some(AuctionPhase::BidCollection)) != AuctionPhase::BidCollection {
             Err(Box::new(AuctionError::InvalidPhase))
         } else if self.bid_comms_set.contains(bid_comm) {
             Err(Box::new(AuctionError::InvalidBid))
         } else {
-            self.bid_comms_i
-                .insert(self.bid_comms_set.len(), bid_comm.clone());
-            self.bid_comms_set.insert(bid_comm.clone());
-            Ok(self.bid_comms_set.len() - 1)
+            Ok(())
         }
     }
 
+    pub fn accept_bid(
+        &mut self,
+        pp: &AuctionParams<G, RsaP>,
+        bid_comm: &S::Comm,
+    ) -> Result<usize, Error> {
+        self.check_bid(pp, bid_comm)?;
+        self.bid_comms_i
+            .insert(self.bid_comms_set.len(), bid_comm.clone());
+        self.bid_comms_set.insert(bid_comm.clone());
+        Ok(self.bid_comms_set.len() - 1)
+    }
+
+    // Swaps the commitment of an unopened bid, keeping its index. Returns the replaced commitment.
+    pub fn replace_bid(
+        &mut self,
+        pp: &AuctionParams<G, RsaP>,
+        bid_index: usize,
+        bid_comm: &S::Comm,
+    ) -> Result<S::Comm, Error> {
+        self.check_bid(pp, bid_comm)?;
+        if self.bid_openings.contains_key(&bid_index) {
+            return Err(Box::new(AuctionError::InvalidBid));
+        }
+        let prior_comm = self
+            .bid_comms_i
+            .insert(bid_index, bid_comm.clone())
+            .ok_or(Box::new(AuctionError::InvalidBid))?;
+        self.bid_comms_set.insert(bid_comm.clone());
+        Ok(prior_comm)
+    }
+
     pub fn accept_self_opening(
         &mut self,
         pp: &AuctionParams<G, RsaP>,
         bid: u32,
-        bid_opening: &TCOpening<G, RsaP, H2P>,
+        bid_opening: &S::Opening,
         bid_index: usize,
     ) -> Result<(), Error> {
         if self.phase(pp, Some(AuctionPhase::BidSelfOpening)) != AuctionPhase::BidSelfOpening {
@@ -158,8 +421,12 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
                 .bid_comms_i
                 .get(&bid_index)
                 .ok_or(Box::new(AuctionError::InvalidBid))?;
-            if PedComm::<G>::ver_open(&pp.ped_pp, &comm.ped_comm, &bid.to_le_bytes(), bid_opening)?
-            {
+            if PedComm::<G>::ver_open(
+                &pp.ped_pp,
+                &S::ped_comm(comm),
+                &bid.to_le_bytes(),
+                bid_opening,
+            )? {
                 self.bid_openings.insert(bid_index, Some(bid));
                 Ok(())
             } else {
@@ -172,22 +439,35 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         &mut self,
         pp: &AuctionParams<G, RsaP>,
         bid: Option<u32>,
-        bid_opening: &TCOpening<G, RsaP, H2P>,
+        bid_opening: &S::Opening,
         bid_index: usize,
     ) -> Result<(), Error> {
         if self.phase(pp, Some(AuctionPhase::BidForceOpening)) != AuctionPhase::BidForceOpening {
             Err(Box::new(AuctionError::InvalidPhase))
         } else {
             self.accept_opening(pp, bid, bid_opening, bid_index)?;
+            self.bid_force_opened.insert(bid_index);
             Ok(())
         }
     }
 
+    pub fn bid_state(&self, bid_index: usize) -> Option<BidState<'_, S::Comm>> {
+        let comm = self.bid_comms_i.get(&bid_index)?;
+        Some(match self.bid_openings.get(&bid_index) {
+            None => BidState::Sealed(comm),
+            Some(bid) if self.bid_force_opened.contains(&bid_index) => match bid {
+                Some(bid) => BidState::ForceOpened(*bid),
+                None => BidState::Forfeited,
+            },
+            Some(bid) => BidState::SelfOpened(bid.unwrap()),
+        })
+    }
+
     fn accept_opening(
         &mut self,
         pp: &AuctionParams<G, RsaP>,
         bid: Option<u32>,
-        bid_opening: &TCOpening<G, RsaP, H2P>,
+        bid_opening: &S::Opening,
         bid_index: usize,
     ) -> Result<(), Error> {
         if self.bid_openings.contains_key(&bid_index) {
@@ -198,19 +478,35 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
             .bid_comms_i
             .get(&bid_index)
             .ok_or(Box::new(AuctionError::InvalidBid))?;
-        if LazyTC::<G, PoEP, RsaP, H, H2P>::ver_open(
+        let valid = S::ver_open(
             &pp.time_pp,
             &pp.ped_pp,
             comm,
             &bid.map(|b| b.to_le_bytes().to_vec()),
             bid_opening,
-        )? {
+        )? || (bid.is_none() && Self::opens_to_non_bid(pp, comm, bid_opening)?);
+        if valid {
             self.bid_openings.insert(bid_index, bid);
             Ok(())
         } else {
             Err(Box::new(AuctionError::InvalidBid))
         }
     }
+
+    // Whether the opening reveals a committed message that does not decode to a bid, such as a
+    // corrupt commitment whose Pedersen layer still passed the range proofs
+    fn opens_to_non_bid(
+        pp: &AuctionParams<G, RsaP>,
+        comm: &S::Comm,
+        bid_opening: &S::Opening,
+    ) -> Result<bool, Error> {
+        match S::opened_message(bid_opening) {
+            Some(m) if decode_bid(&m).is_none() => {
+                S::ver_open(&pp.time_pp, &pp.ped_pp, comm, &Some(m), bid_opening)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -277,6 +573,150 @@ mod tests {
         PocklingtonHash<TestPocklingtonParams, Keccak256>,
     >;
 
+    #[test]
+    fn phase_at_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+        let auction_pp = AuctionParams {
+            timing: PhaseTiming::WallClock {
+                t_bid_collection: Duration::from_secs(2),
+                t_bid_self_open: Duration::from_secs(3),
+            },
+            time_pp: time_pp,
+            ped_pp: ped_pp,
+        };
+        let t_start = Instant::now();
+        let self_open_start = t_start + Duration::from_secs(2);
+        let force_open_start = self_open_start + Duration::from_secs(3);
+        let eps = Duration::from_nanos(1);
+
+        assert_eq!(
+            auction_pp.phase_at(t_start, t_start, 0, 2),
+            AuctionPhase::BidCollection
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, t_start, 0, 0),
+            AuctionPhase::BidCollection
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, self_open_start - eps, 0, 2),
+            AuctionPhase::BidCollection
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, self_open_start, 0, 2),
+            AuctionPhase::BidSelfOpening
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, force_open_start - eps, 0, 2),
+            AuctionPhase::BidSelfOpening
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, force_open_start, 0, 2),
+            AuctionPhase::BidForceOpening
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, self_open_start, 0, 0),
+            AuctionPhase::Complete
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, force_open_start, 0, 0),
+            AuctionPhase::Complete
+        );
+
+        // Discriminants match the contract enum
+        assert_eq!(AuctionPhase::BidCollection as u8, 0);
+        assert_eq!(AuctionPhase::BidSelfOpening as u8, 1);
+        assert_eq!(AuctionPhase::BidForceOpening as u8, 2);
+        assert_eq!(AuctionPhase::Complete as u8, 3);
+    }
+
+    #[test]
+    fn auction_phase_conversion_test() {
+        for (i, phase) in [
+            AuctionPhase::BidCollection,
+            AuctionPhase::BidSelfOpening,
+            AuctionPhase::BidForceOpening,
+            AuctionPhase::Complete,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            assert_eq!(u8::from(phase), i as u8);
+            assert_eq!(AuctionPhase::try_from(u8::from(phase)).unwrap(), phase);
+            assert_eq!(U256::from(phase), U256::from(i));
+        }
+        assert!(AuctionPhase::try_from(4u8).is_err());
+    }
+
+    #[test]
+    fn block_based_phase_at_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+        let auction_pp = AuctionParams {
+            timing: PhaseTiming::BlockBased {
+                collection_blocks: 20,
+                self_open_blocks: 10,
+                start_block: 100,
+            },
+            time_pp,
+            ped_pp,
+        };
+        // Wall-clock time is ignored
+        let t_start = Instant::now();
+        let later = t_start + Duration::from_secs(3600);
+
+        assert_eq!(
+            auction_pp.phase_at(t_start, later, 0, 2),
+            AuctionPhase::BidCollection
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, later, 100, 2),
+            AuctionPhase::BidCollection
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, t_start, 119, 2),
+            AuctionPhase::BidCollection
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, t_start, 120, 2),
+            AuctionPhase::BidSelfOpening
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, t_start, 129, 2),
+            AuctionPhase::BidSelfOpening
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, t_start, 130, 2),
+            AuctionPhase::BidForceOpening
+        );
+        assert_eq!(
+            auction_pp.phase_at(t_start, t_start, 120, 0),
+            AuctionPhase::Complete
+        );
+
+        // Auction phase follows the blocks it is told about
+        let mut auction = TestAuction::new(&auction_pp);
+        let (comm, _) = TestAuction::client_create_bid(&mut rng, &auction_pp, 7).unwrap();
+        auction.set_block(110);
+        auction.accept_bid(&auction_pp, &comm).unwrap();
+        assert_eq!(
+            auction.phase(&auction_pp, None),
+            AuctionPhase::BidCollection
+        );
+        auction.set_block(125);
+        assert_eq!(
+            auction.phase(&auction_pp, None),
+            AuctionPhase::BidSelfOpening
+        );
+        auction.set_block(130);
+        assert_eq!(
+            auction.phase(&auction_pp, None),
+            AuctionPhase::BidForceOpening
+        );
+    }
+
     #[test]
     fn basic_auction_test() {
         let mut rng = StdRng::seed_from_u64(0u64);
@@ -288,8 +728,10 @@ mod tests {
         let (time_pp, _) = TC::gen_time_params(40).unwrap();
         let ped_pp = TC::gen_pedersen_params(&mut rng);
         let auction_pp = AuctionParams {
-            t_bid_collection: Duration::from_secs(2),
-            t_bid_self_open: Duration::from_secs(2),
+            timing: PhaseTiming::WallClock {
+                t_bid_collection: Duration::from_secs(2),
+                t_bid_self_open: Duration::from_secs(2),
+            },
             time_pp,
             ped_pp,
         };
@@ -320,7 +762,7 @@ mod tests {
             .is_err());
 
         // Self opening phase
-        thread::sleep(auction_pp.t_bid_collection);
+        thread::sleep(Duration::from_secs(2));
         // assert_eq!(auction.phase(&auction_pp), AuctionPhase::BidSelfOpening);
 
         assert!(auction.accept_bid(&auction_pp, &comm4).is_err());
@@ -343,7 +785,7 @@ mod tests {
             .is_err());
 
         // Force opening phase
-        thread::sleep(auction_pp.t_bid_self_open);
+        thread::sleep(Duration::from_secs(2));
         // assert_eq!(auction.phase(&auction_pp), AuctionPhase::BidForceOpening);
         assert!(auction
             .accept_self_opening(&auction_pp, bid3, &opening3, index3)
@@ -367,8 +809,10 @@ mod tests {
         let (time_pp, _) = TC::gen_time_params(40).unwrap();
         let ped_pp = TC::gen_pedersen_params(&mut rng);
         let auction_pp = AuctionParams {
-            t_bid_collection: Duration::from_secs(2),
-            t_bid_self_open: Duration::from_secs(2),
+            timing: PhaseTiming::WallClock {
+                t_bid_collection: Duration::from_secs(2),
+                t_bid_self_open: Duration::from_secs(2),
+            },
             time_pp,
             ped_pp,
         };
@@ -389,7 +833,7 @@ mod tests {
         let index3 = auction.accept_bid(&auction_pp, &comm3).unwrap();
 
         // Self opening phase
-        thread::sleep(auction_pp.t_bid_collection);
+        thread::sleep(Duration::from_secs(2));
 
         auction
             .accept_self_opening(&auction_pp, bid1, &opening1, index1)
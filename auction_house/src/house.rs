@@ -1,17 +1,30 @@
-use ark_ec::ProjectiveCurve;
+use ark_ec::{AffineCurve, ProjectiveCurve};
 
-use ark_ff::PrimeField;
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use digest::Digest;
 use num_traits::Zero;
-use rand::{CryptoRng, Rng};
+use rand::{rngs::StdRng, CryptoRng, Rng, SeedableRng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::ops::Neg;
-use std::{collections::HashMap, marker::PhantomData};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error as ErrorTrait,
+    fmt,
+    io::{Read, Write},
+    marker::PhantomData,
+    time::Instant,
+};
 
 use crate::{
-    auction::{Auction, AuctionParams, AuctionPhase},
+    auction::{Auction, AuctionParams, AuctionPhase, BidState, PhaseTiming},
+    sealed_bid::{lazy_tc_comm_size, read_lazy_tc_comm, write_lazy_tc_comm, SealedBidScheme},
     AuctionError, Error,
 };
-use range_proofs::bulletproofs::{Bulletproofs, Params as RangeProofParams, Proof as RangeProof};
+use range_proofs::bulletproofs::{
+    Bulletproofs, Params as RangeProofParams, Proof as RangeProof, SumProof,
+};
 use rsa::{
     bigint::{nat_to_f, BigInt},
     hash_to_prime::HashToPrime,
@@ -19,12 +32,76 @@ use rsa::{
     poe::PoEParams,
 };
 use timed_commitments::{
-    lazy_tc::{Comm as TCComm, Opening as TCOpening},
-    PedersenParams,
+    lazy_tc::{Comm as TCComm, LazyTC},
+    PedersenComm, PedersenParams,
 };
 
 const BID_BITS: u32 = 32;
 
+fn add_balance(a: u32, b: u32) -> Result<u32, Error> {
+    a.checked_add(b)
+        .ok_or(Box::new(AuctionError::BalanceOverflow) as Error)
+}
+
+fn sub_balance(a: u32, b: u32) -> Result<u32, Error> {
+    a.checked_sub(b)
+        .ok_or(Box::new(AuctionError::BalanceUnderflow) as Error)
+}
+
+// Entries in ascending key order, so persisted state does not depend on hash map iteration order
+fn sorted_entries<K: Ord + Copy, V>(map: &HashMap<K, V>) -> Vec<(K, &V)> {
+    let mut entries = map.iter().map(|(k, v)| (*k, v)).collect::<Vec<_>>();
+    entries.sort_unstable_by_key(|(k, _)| *k);
+    entries
+}
+
+// Data appended to the transcript of a bid range proof, the encoded sealed bid for a bound proof
+fn bid_binding<G: ProjectiveCurve, RsaP: RsaGroupParams, S: SealedBidScheme<G, RsaP>>(
+    kind: BidProofKind,
+    comm: &S::Comm,
+) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    if kind == BidProofKind::Bound {
+        S::write_comm(comm, &mut bytes)?;
+    }
+    Ok(bytes)
+}
+
+// Bidder, auction and bid value commitment appended to the transcript of a balance breakdown
+fn breakdown_binding<G: ProjectiveCurve>(
+    user_id: u32,
+    auction_id: u32,
+    ped_comm: &G,
+) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&user_id.to_be_bytes());
+    bytes.extend_from_slice(&auction_id.to_be_bytes());
+    ped_comm.serialize(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn check_bid_bits(value: u32, max_bits: u32) -> Result<(), Error> {
+    if (value as u64) >> max_bits != 0 {
+        Err(Box::new(AuctionError::BidOutOfRange { value, max_bits }))
+    } else {
+        Ok(())
+    }
+}
+
+// Bid value to propose, checked against the auction's `max_bid_bits` when proposed
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BidValue(u32);
+
+impl BidValue {
+    pub fn new(value: u32) -> Self {
+        Self(value)
+    }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
 //TODO: PedersenParams should be here instead of in per-auction params (currently duplicated)
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct HouseParams<G: ProjectiveCurve> {
@@ -37,6 +114,41 @@ pub struct HouseAuctionParams<G: ProjectiveCurve, RsaP: RsaGroupParams> {
     pub auction_pp: AuctionParams<G, RsaP>,
     pub reward_self_open: u32,
     pub reward_force_open: u32,
+    pub seller_bond: u32,
+    pub bundle: Vec<NftToken>, // tokens auctioned together, all transferred to a single winner
+    pub reclaim_deadline: Option<u64>, // blocks after completion until `sweep_expired` is allowed
+    pub max_bid_bits: u32,     // widest bid accepted by `propose_bid`, capped at `BID_BITS`
+}
+
+// ERC-721 token held by the house while it is auctioned
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NftToken {
+    pub contract: [u8; 20],
+    pub token_id: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AuctionRewards {
+    pub reward_self_open: u32,
+    pub reward_force_open: u32,
+}
+
+impl<G: ProjectiveCurve, RsaP: RsaGroupParams> HouseAuctionParams<G, RsaP> {
+    pub fn rewards(&self) -> AuctionRewards {
+        AuctionRewards {
+            reward_self_open: self.reward_self_open,
+            reward_force_open: self.reward_force_open,
+        }
+    }
+
+    // Params seen by bidders of an auction whose rewards override the house defaults
+    pub fn with_rewards(&self, rewards: &AuctionRewards) -> Self {
+        Self {
+            reward_self_open: rewards.reward_self_open,
+            reward_force_open: rewards.reward_force_open,
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -45,15 +157,51 @@ pub struct AccountSummary<G: ProjectiveCurve> {
     pub comm_active_bids: G,
 }
 
+// Fungible token backing house balances. Deposits debit the depositor's token balance and
+// withdrawals credit it, so a failed debit rejects the deposit.
+pub trait CoinBackend {
+    fn debit(&mut self, user_id: u32, amt: u32) -> Result<(), Error>;
+    fn credit(&mut self, user_id: u32, amt: u32) -> Result<(), Error>;
+}
+
+// Built-in auction house coin. It is exchanged for ether outside the house, so deposits and
+// withdrawals need no bookkeeping here.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct AHCoin;
+
+impl CoinBackend for AHCoin {
+    fn debit(&mut self, _user_id: u32, _amt: u32) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn credit(&mut self, _user_id: u32, _amt: u32) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
 pub struct AuctionHouse<
     G: ProjectiveCurve,
     PoEP: PoEParams,
     RsaP: RsaGroupParams,
     H: Digest,
     H2P: HashToPrime,
+    S: SealedBidScheme<G, RsaP> = LazyTC<G, PoEP, RsaP, H, H2P>,
+    B: CoinBackend = AHCoin,
 > {
-    active_auctions: HashMap<u32, (Auction<G, PoEP, RsaP, H, H2P>, HashMap<u32, u32>)>, // auction_id -> (auction, (user_id -> bid_id))
+    active_auctions: HashMap<u32, (Auction<G, PoEP, RsaP, H, H2P, S>, HashMap<u32, u32>)>, // auction_id -> (auction, (user_id -> bid_id))
+    seller_bonds: HashMap<u32, (u32, u32)>, // auction_id -> (seller_id, bond)
+    auction_rewards: HashMap<u32, AuctionRewards>, // auction_id -> effective rewards
+    auction_timings: HashMap<u32, PhaseTiming>, // auction_id -> phase boundaries
     accounts: HashMap<u32, AccountSummary<G>>, // user_id -> account_info
+    bid_records: HashMap<u32, HashMap<u32, BidRecord<G, S::Opening>>>, // auction_id -> (bid_id -> proofs)
+    total_deposited: u64,
+    total_withdrawn: u64,
+    seller_proceeds: u64, // paid by auction winners, settled with sellers outside the house
+    forfeited_bonds: u64, // bonds of cancelled auctions, kept by the house
+    treasury: Option<u32>, // user_id credited with the escrow collected by `sweep_expired`
+    reclaim_deadlines: HashMap<u32, u64>, // auction_id -> block from which it can be swept
+    completed_auctions: HashSet<u32>, // auctions already settled, kept until swept
+    coin: B,
     //TODO: Will eventually overflow, use hash or replace finished auction ids
     ctr_auction: u32,
     ctr_account: u32,
@@ -66,22 +214,272 @@ pub struct AccountPrivateState<
     RsaP: RsaGroupParams,
     H: Digest,
     H2P: HashToPrime,
+    S: SealedBidScheme<G, RsaP> = LazyTC<G, PoEP, RsaP, H, H2P>,
 > {
     pub public_summary: AccountSummary<G>,
-    pub active_bids: HashMap<u32, (u32, TCOpening<G, RsaP, H2P>, TCComm<G, RsaP>)>, // auction_id -> (bid, opening, comm)
+    pub active_bids: HashMap<u32, (u32, S::Opening, S::Comm)>, // auction_id -> (bid, opening, comm)
     pub sum_active_bids: u32,
     pub opening_active_bids: G::ScalarField,
-    _auction: PhantomData<Auction<G, PoEP, RsaP, H, H2P>>,
+    balance_history: Vec<BalanceEvent>,
+    _auction: PhantomData<Auction<G, PoEP, RsaP, H, H2P, S>>,
+}
+
+// How the bid range proof of a proposal is bound to its sealed bid
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum BidProofKind {
+    Plain, // from `propose_bid`, also verified on-chain
+    Bound, // from `propose_bid_bound`, the transcript commits to the sealed bid; off-chain only
+}
+
+// Whether a checked bid is a user's first in the auction or replaces their unopened bid
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum BidSlot {
+    New,
+    Replace,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BalanceEventKind {
+    Deposit,
+    Withdrawal,
+    Bid,        // open rewards escrowed with the bid
+    OpenReward, // escrowed rewards returned on self open, or force open reward
+    Win,
+    Loss,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BalanceEvent {
+    pub seq: u64,
+    pub kind: BalanceEventKind,
+    pub amount: i64, // signed change to the account balance
+}
+
+// Proofs and opening a bid was accepted with, kept so `audit_auction` can re-verify them
+#[derive(Clone, PartialEq, Debug)]
+struct BidRecord<G: ProjectiveCurve, O> {
+    range_proof_bid: RangeProof<G>,
+    range_proof_balance: RangeProof<G>,
+    comm_balance: G, // commitment the balance proof was verified against
+    kind: BidProofKind,
+    opening: Option<RecordedOpening<G, O>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum RecordedOpening<G: ProjectiveCurve, O> {
+    Timed(Option<u32>, O), // from `account_self_open` or `account_force_open`
+    Pedersen(u32, G::ScalarField), // from `account_self_open_optimized`
+}
+
+// Outcome of re-verifying the proofs of a bid, `opening` is None while the bid is sealed
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AuditResult {
+    pub range_proof_bid: bool,
+    pub range_proof_balance: bool,
+    pub opening: Option<bool>,
+}
+
+impl AuditResult {
+    pub fn passed(&self) -> bool {
+        self.range_proof_bid && self.range_proof_balance && self.opening != Some(false)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BidReceipt {
+    pub auction_id: u32,
+    pub bid_id: u32,
+    pub balance: u32, // account balance after deposit and bid reward escrow
+}
+
+// Distance of a bid from the clearing price, `price - bid`. Zero or negative for winners.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClearingGap {
+    Opened(i64),
+    Unopened, // never opened to a valid bid
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompletionOutcome {
+    Cleared,
+    NoBids, // no bids were placed, the item stays with the seller
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CompletionReport {
+    pub outcome: CompletionOutcome,
+    pub price: u32,
+    pub winners: Vec<u32>,             // user_ids charged the price
+    pub losers: Vec<u32>,              // user_ids of remaining bidders, including unopened bids
+    pub seller_proceeds: u32,          // price * number of winners
+    pub gaps: Vec<(u32, ClearingGap)>, // per user_id, sorted by user_id
+    pub bundle_recipient: Option<u32>, // user_id receiving the bundle, the seller if no bids
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum InvariantViolation {
+    MissingRewards(u32), // auction_id with bids but no effective rewards
+    MissingBid {
+        auction_id: u32,
+        bid_id: u32,
+    },
+    Conservation {
+        held: u64,
+        deposited: u64,
+        withdrawn: u64,
+    },
 }
 
-pub struct BidProposal<G: ProjectiveCurve, RsaP: RsaGroupParams> {
-    pub comm_bid: TCComm<G, RsaP>,
+impl ErrorTrait for InvariantViolation {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(self: &Self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            InvariantViolation::MissingRewards(auction_id) => {
+                format!("auction {} has no rewards", auction_id)
+            }
+            InvariantViolation::MissingBid { auction_id, bid_id } => {
+                format!("auction {} has no bid {}", auction_id, bid_id)
+            }
+            InvariantViolation::Conservation {
+                held,
+                deposited,
+                withdrawn,
+            } => format!(
+                "house holds {} but {} was deposited and {} withdrawn",
+                held, deposited, withdrawn
+            ),
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+// Commitment to the amounts of an account's balance history, with a proof they sum to its balance
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BalanceBreakdown<G: ProjectiveCurve> {
+    pub comm_amounts: G,
+    pub proof: SumProof<G>,
+}
+
+pub struct BidProposal<G: ProjectiveCurve, C> {
+    pub comm_bid: C,
+    pub comm_range_bid: G, // value commitment the bid range proof was made for
     pub range_proof_bid: RangeProof<G>,
     pub range_proof_balance: RangeProof<G>,
 }
 
-impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
-    AccountPrivateState<G, PoEP, RsaP, H, H2P>
+// Wire format for relaying bids sealed with `LazyTC`: the commitment as in `write_comm`, the value
+// commitment of the bid range proof, then the bid and balance range proofs. Proofs must have the inner product rounds of a `BID_BITS` range.
+impl<G: ProjectiveCurve, RsaP: RsaGroupParams> CanonicalSerialize
+    for BidProposal<G, TCComm<G, RsaP>>
+{
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        write_lazy_tc_comm(&self.comm_bid, &mut writer)?;
+        self.comm_range_bid.into_affine().serialize(&mut writer)?;
+        write_range_proof(&self.range_proof_bid, &mut writer)?;
+        write_range_proof(&self.range_proof_balance, &mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        lazy_tc_comm_size(&self.comm_bid)
+            + self.comm_range_bid.into_affine().serialized_size()
+            + range_proof_size(&self.range_proof_bid)
+            + range_proof_size(&self.range_proof_balance)
+    }
+}
+
+impl<G: ProjectiveCurve, RsaP: RsaGroupParams> CanonicalDeserialize
+    for BidProposal<G, TCComm<G, RsaP>>
+{
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let lg_n = BID_BITS.trailing_zeros() as usize;
+        Ok(Self {
+            comm_bid: read_lazy_tc_comm(&mut reader)?,
+            comm_range_bid: G::Affine::deserialize(&mut reader)?.into_projective(),
+            range_proof_bid: read_range_proof(&mut reader, lg_n)?,
+            range_proof_balance: read_range_proof(&mut reader, lg_n)?,
+        })
+    }
+}
+
+fn write_range_proof<G: ProjectiveCurve, W: Write>(
+    proof: &RangeProof<G>,
+    mut writer: W,
+) -> Result<(), SerializationError> {
+    for g in [
+        &proof.comm_bits,
+        &proof.comm_blind,
+        &proof.comm_lc1,
+        &proof.comm_lc2,
+    ] {
+        g.into_affine().serialize(&mut writer)?;
+    }
+    for f in [&proof.t_x, &proof.r_t_x, &proof.r_ab] {
+        f.serialize(&mut writer)?;
+    }
+    (proof.comm_ipa.len() as u64).serialize(&mut writer)?;
+    for (comm_1, comm_2) in proof.comm_ipa.iter() {
+        comm_1.into_affine().serialize(&mut writer)?;
+        comm_2.into_affine().serialize(&mut writer)?;
+    }
+    proof.base_a.serialize(&mut writer)?;
+    proof.base_b.serialize(&mut writer)?;
+    Ok(())
+}
+
+fn range_proof_size<G: ProjectiveCurve>(proof: &RangeProof<G>) -> usize {
+    let g_size = proof.comm_bits.into_affine().serialized_size();
+    let f_size = proof.t_x.serialized_size();
+    (4 + 2 * proof.comm_ipa.len()) * g_size + 5 * f_size + 0u64.serialized_size()
+}
+
+// Rejects proofs whose inner product argument does not have `lg_n` rounds
+fn read_range_proof<G: ProjectiveCurve, R: Read>(
+    mut reader: R,
+    lg_n: usize,
+) -> Result<RangeProof<G>, SerializationError> {
+    let comm_bits = G::Affine::deserialize(&mut reader)?.into_projective();
+    let comm_blind = G::Affine::deserialize(&mut reader)?.into_projective();
+    let comm_lc1 = G::Affine::deserialize(&mut reader)?.into_projective();
+    let comm_lc2 = G::Affine::deserialize(&mut reader)?.into_projective();
+    let t_x = G::ScalarField::deserialize(&mut reader)?;
+    let r_t_x = G::ScalarField::deserialize(&mut reader)?;
+    let r_ab = G::ScalarField::deserialize(&mut reader)?;
+    if u64::deserialize(&mut reader)? != lg_n as u64 {
+        return Err(SerializationError::InvalidData);
+    }
+    let mut comm_ipa = Vec::with_capacity(lg_n);
+    for _ in 0..lg_n {
+        let comm_1 = G::Affine::deserialize(&mut reader)?.into_projective();
+        let comm_2 = G::Affine::deserialize(&mut reader)?.into_projective();
+        comm_ipa.push((comm_1, comm_2));
+    }
+    Ok(RangeProof {
+        comm_bits,
+        comm_blind,
+        comm_lc1,
+        comm_lc2,
+        t_x,
+        r_t_x,
+        r_ab,
+        comm_ipa,
+        base_a: G::ScalarField::deserialize(&mut reader)?,
+        base_b: G::ScalarField::deserialize(&mut reader)?,
+    })
+}
+
+impl<
+        G: ProjectiveCurve,
+        PoEP: PoEParams,
+        RsaP: RsaGroupParams,
+        H: Digest,
+        H2P: HashToPrime,
+        S: SealedBidScheme<G, RsaP>,
+    > AccountPrivateState<G, PoEP, RsaP, H, H2P, S>
 {
     pub fn new() -> Self {
         Self {
@@ -92,45 +490,89 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
             active_bids: HashMap::new(),
             sum_active_bids: 0,
             opening_active_bids: G::ScalarField::zero(),
+            balance_history: Vec::new(),
             _auction: PhantomData,
         }
     }
 
+    // Events in the order they were confirmed, their amounts sum to the current balance
+    pub fn balance_history(&self) -> &[BalanceEvent] {
+        &self.balance_history
+    }
+
+    fn record_balance_event(&mut self, kind: BalanceEventKind, amount: i64) {
+        self.balance_history.push(BalanceEvent {
+            seq: self.balance_history.len() as u64,
+            kind,
+            amount,
+        });
+        debug_assert_eq!(
+            self.balance_history.iter().map(|e| e.amount).sum::<i64>(),
+            self.public_summary.balance as i64
+        );
+    }
+
     pub fn propose_bid<R: CryptoRng + Rng>(
         &self,
         rng: &mut R,
         house_pp: &HouseParams<G>,
         auction_pp: &HouseAuctionParams<G, RsaP>,
-        bid: u32,
-    ) -> Result<(BidProposal<G, RsaP>, TCOpening<G, RsaP, H2P>), Error> {
-        if self.sum_active_bids + bid + auction_pp.reward_self_open + auction_pp.reward_force_open
-            > self.public_summary.balance
-        {
-            return Err(Box::new(AuctionError::InvalidBid));
-        }
-        let (comm_bid, opening_bid) =
-            Auction::<G, PoEP, RsaP, H, H2P>::client_create_bid(rng, &auction_pp.auction_pp, bid)?;
+        bid: BidValue,
+    ) -> Result<(BidProposal<G, S::Comm>, S::Opening), Error> {
+        self.propose_bid_with_binding(rng, house_pp, auction_pp, bid, BidProofKind::Plain)
+    }
+
+    // Same as `propose_bid`, but the bid range proof also commits to the encoded sealed bid, so it
+    // verifies only for this exact commitment, timed layer included. Accepted by
+    // `account_bid_bound` only: the solidity verifier does not absorb the binding, so bound
+    // proposals cannot be submitted on-chain.
+    pub fn propose_bid_bound<R: CryptoRng + Rng>(
+        &self,
+        rng: &mut R,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        bid: BidValue,
+    ) -> Result<(BidProposal<G, S::Comm>, S::Opening), Error> {
+        self.propose_bid_with_binding(rng, house_pp, auction_pp, bid, BidProofKind::Bound)
+    }
+
+    fn propose_bid_with_binding<R: CryptoRng + Rng>(
+        &self,
+        rng: &mut R,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        bid: BidValue,
+        kind: BidProofKind,
+    ) -> Result<(BidProposal<G, S::Comm>, S::Opening), Error> {
+        let bid = bid.value();
+        check_bid_bits(bid, auction_pp.max_bid_bits.min(BID_BITS))?;
+        let reward = self.check_bid_covered(auction_pp, bid)?;
+        let (comm_bid, opening_bid) = Auction::<G, PoEP, RsaP, H, H2P, S>::client_create_bid(
+            rng,
+            &auction_pp.auction_pp,
+            bid,
+        )?;
         // Prove bid > 0
-        let range_proof_bid = Bulletproofs::<G, H>::prove_range(
+        let ad = bid_binding::<G, RsaP, S>(kind, &comm_bid)?;
+        let range_proof_bid = Bulletproofs::<G, H>::prove_range_with_ad(
             rng,
             &house_pp.range_proof_pp,
             &auction_pp.auction_pp.ped_pp,
-            &comm_bid.ped_comm,
+            &S::ped_comm(&comm_bid),
             &BigInt::from(bid),
-            &opening_bid.get_ped_opening(),
+            &S::ped_opening(&opening_bid),
             BID_BITS as u64,
+            &ad,
         )?;
         // Prove balance - reward - bid - active_bids > 0
-        let balance_less_reward = self.public_summary.balance
-            - auction_pp.reward_self_open
-            - auction_pp.reward_force_open;
+        let balance_less_reward = sub_balance(self.public_summary.balance, reward)?;
         let f_balance_less_reward = nat_to_f::<G::ScalarField>(&BigInt::from(balance_less_reward))?;
         let comm_balance = auction_pp
             .auction_pp
             .ped_pp
             .g
             .mul(&f_balance_less_reward.into_repr())
-            - &comm_bid.ped_comm
+            - &S::ped_comm(&comm_bid)
             - &self.public_summary.comm_active_bids;
         let range_proof_balance = Bulletproofs::<G, H>::prove_range(
             rng,
@@ -138,11 +580,12 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
             &auction_pp.auction_pp.ped_pp,
             &comm_balance,
             &BigInt::from(balance_less_reward - bid - self.sum_active_bids),
-            &(opening_bid.get_ped_opening().neg() - &self.opening_active_bids),
+            &(S::ped_opening(&opening_bid).neg() - &self.opening_active_bids),
             BID_BITS as u64,
         )?;
         Ok((
             BidProposal {
+                comm_range_bid: S::ped_comm(&comm_bid),
                 comm_bid,
                 range_proof_bid,
                 range_proof_balance,
@@ -151,25 +594,147 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         ))
     }
 
+    // Balance must cover the open rewards and the bid on top of active bids, returns the rewards
+    fn check_bid_covered(
+        &self,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        bid: u32,
+    ) -> Result<u32, Error> {
+        let reward = add_balance(auction_pp.reward_self_open, auction_pp.reward_force_open)?;
+        if add_balance(add_balance(self.sum_active_bids, bid)?, reward)?
+            > self.public_summary.balance
+        {
+            return Err(Box::new(AuctionError::InvalidBid));
+        }
+        Ok(reward)
+    }
+
+    // Proposes a bid for each value, the i-th drawing its randomness from `StdRng` seeded with
+    // the i-th seed so results match serial `propose_bid` calls regardless of scheduling
+    pub fn propose_bids_parallel(
+        &self,
+        rng_seeds: &[u64],
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        values: &[BidValue],
+    ) -> Result<Vec<(BidProposal<G, S::Comm>, S::Opening)>, Error>
+    where
+        Self: Sync,
+        HouseAuctionParams<G, RsaP>: Sync,
+        S::Comm: Send,
+        S::Opening: Send,
+    {
+        if rng_seeds.len() != values.len() {
+            return Err(Box::new(AuctionError::InvalidBid));
+        }
+        // Checked up front so a bid the balance cannot cover fails with its own error
+        for bid in values.iter() {
+            self.check_bid_covered(auction_pp, bid.value())?;
+        }
+        #[cfg(feature = "parallel")]
+        let seeded_values = rng_seeds.par_iter().zip(values.par_iter());
+        #[cfg(not(feature = "parallel"))]
+        let seeded_values = rng_seeds.iter().zip(values.iter());
+        // Errors are not Send, so only auction errors cross threads. Anything else is a proving
+        // failure on a bid already checked above and is reported as an invalid bid.
+        seeded_values
+            .map(|(seed, bid)| {
+                let mut rng = StdRng::seed_from_u64(*seed);
+                self.propose_bid(&mut rng, house_pp, auction_pp, *bid)
+                    .map_err(|e| match e.downcast::<AuctionError>() {
+                        Ok(e) => *e,
+                        Err(_) => AuctionError::InvalidBid,
+                    })
+            })
+            .collect::<Result<Vec<_>, AuctionError>>()
+            .map_err(|e| Box::new(e) as Error)
+    }
+
+    // Same as `propose_bid`, also breaking the balance down into the committed amounts of the
+    // deposits and other changes in its history, e.g. for audited accounts. The breakdown is
+    // bound to `user_id`, `auction_id` and the proposed bid.
+    pub fn propose_bid_with_balance_breakdown<R: CryptoRng + Rng>(
+        &self,
+        rng: &mut R,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        user_id: u32,
+        bid: BidValue,
+    ) -> Result<(BidProposal<G, S::Comm>, S::Opening, BalanceBreakdown<G>), Error> {
+        let (proposal, opening) = self.propose_bid(rng, house_pp, auction_pp, bid)?;
+        let amounts = self
+            .balance_history
+            .iter()
+            .map(|e| e.amount)
+            .collect::<Vec<_>>();
+        let ad = breakdown_binding(user_id, auction_id, &S::ped_comm(&proposal.comm_bid))?;
+        let breakdown = Self::balance_breakdown(rng, house_pp, &amounts, &ad)?;
+        Ok((proposal, opening, breakdown))
+    }
+
+    fn balance_breakdown<R: CryptoRng + Rng>(
+        rng: &mut R,
+        house_pp: &HouseParams<G>,
+        amounts: &[i64],
+        ad: &[u8],
+    ) -> Result<BalanceBreakdown<G>, Error> {
+        let f_amounts = amounts
+            .iter()
+            .map(|a| {
+                let f_abs = G::ScalarField::from(a.unsigned_abs());
+                if *a < 0 {
+                    f_abs.neg()
+                } else {
+                    f_abs
+                }
+            })
+            .collect::<Vec<_>>();
+        let opening = G::ScalarField::rand(rng);
+        let comm_amounts = Bulletproofs::<G, H>::commit_vector(
+            &house_pp.range_proof_pp,
+            &house_pp.ped_pp,
+            &f_amounts,
+            &opening,
+        )?;
+        let proof = Bulletproofs::<G, H>::prove_sum(
+            rng,
+            &house_pp.range_proof_pp,
+            &house_pp.ped_pp,
+            &comm_amounts,
+            &f_amounts,
+            &opening,
+            ad,
+        )?;
+        Ok(BalanceBreakdown {
+            comm_amounts,
+            proof,
+        })
+    }
+
     pub fn confirm_bid(
         &mut self,
         _house_pp: &HouseParams<G>,
         auction_pp: &HouseAuctionParams<G, RsaP>,
         auction_id: u32,
         bid: u32,
-        proposal: &BidProposal<G, RsaP>,
-        opening: &TCOpening<G, RsaP, H2P>,
+        proposal: &BidProposal<G, S::Comm>,
+        opening: &S::Opening,
     ) -> Result<(), Error> {
+        let reward = add_balance(auction_pp.reward_self_open, auction_pp.reward_force_open)?;
+        let balance = sub_balance(self.public_summary.balance, reward)?;
+        let sum_active_bids = add_balance(self.sum_active_bids, bid)?;
         // Update balance to remove reward
-        self.public_summary.balance -= auction_pp.reward_self_open + auction_pp.reward_force_open;
+        self.public_summary.balance = balance;
         // Update active bids
-        self.sum_active_bids += bid;
-        self.opening_active_bids += opening.get_ped_opening();
-        self.public_summary.comm_active_bids += proposal.comm_bid.ped_comm;
+        self.sum_active_bids = sum_active_bids;
+        self.opening_active_bids += S::ped_opening(opening);
+        self.public_summary.comm_active_bids += S::ped_comm(&proposal.comm_bid);
         self.active_bids.insert(
             auction_id,
             (bid, opening.clone(), proposal.comm_bid.clone()),
         );
+        self.record_balance_event(BalanceEventKind::Bid, -(reward as i64));
         Ok(())
     }
 
@@ -178,7 +743,9 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         _house_pp: &HouseParams<G>,
         auction_pp: &HouseAuctionParams<G, RsaP>,
     ) -> Result<(), Error> {
-        self.public_summary.balance += auction_pp.reward_self_open + auction_pp.reward_force_open;
+        let reward = add_balance(auction_pp.reward_self_open, auction_pp.reward_force_open)?;
+        self.public_summary.balance = add_balance(self.public_summary.balance, reward)?;
+        self.record_balance_event(BalanceEventKind::OpenReward, reward as i64);
         Ok(())
     }
 
@@ -187,7 +754,12 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         _house_pp: &HouseParams<G>,
         auction_pp: &HouseAuctionParams<G, RsaP>,
     ) -> Result<(), Error> {
-        self.public_summary.balance += auction_pp.reward_force_open;
+        self.public_summary.balance =
+            add_balance(self.public_summary.balance, auction_pp.reward_force_open)?;
+        self.record_balance_event(
+            BalanceEventKind::OpenReward,
+            auction_pp.reward_force_open as i64,
+        );
         Ok(())
     }
 
@@ -203,12 +775,15 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
                 .active_bids
                 .get(&auction_id)
                 .ok_or(Box::new(AuctionError::InvalidID))?;
-            self.public_summary.balance -= price;
-            self.sum_active_bids -= bid;
-            self.opening_active_bids -= opening.get_ped_opening();
-            self.public_summary.comm_active_bids -= bid_comm.ped_comm;
+            let balance = sub_balance(self.public_summary.balance, price)?;
+            let sum_active_bids = sub_balance(self.sum_active_bids, *bid)?;
+            self.public_summary.balance = balance;
+            self.sum_active_bids = sum_active_bids;
+            self.opening_active_bids -= S::ped_opening(opening);
+            self.public_summary.comm_active_bids -= S::ped_comm(bid_comm);
         }
         self.active_bids.remove(&auction_id);
+        self.record_balance_event(BalanceEventKind::Win, -(price as i64));
         Ok(())
     }
 
@@ -223,16 +798,54 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
                 .active_bids
                 .get(&auction_id)
                 .ok_or(Box::new(AuctionError::InvalidID))?;
-            self.sum_active_bids -= bid;
-            self.opening_active_bids -= opening.get_ped_opening();
-            self.public_summary.comm_active_bids -= bid_comm.ped_comm;
+            self.sum_active_bids = sub_balance(self.sum_active_bids, *bid)?;
+            self.opening_active_bids -= S::ped_opening(opening);
+            self.public_summary.comm_active_bids -= S::ped_comm(bid_comm);
         }
         self.active_bids.remove(&auction_id);
+        self.record_balance_event(BalanceEventKind::Loss, 0);
         Ok(())
     }
 
+    pub fn confirm_auction_cancel(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+    ) -> Result<(), Error> {
+        self.confirm_auction_loss(house_pp, auction_pp, auction_id)?;
+        self.confirm_bid_self_open(house_pp, auction_pp)
+    }
+
+    // Seller bond is proven with the same range proof as a withdrawal of the bond amount
+    pub fn propose_seller_bond<R: CryptoRng + Rng>(
+        &self,
+        rng: &mut R,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+    ) -> Result<RangeProof<G>, Error> {
+        self.propose_withdrawal(rng, house_pp, auction_pp.seller_bond)
+    }
+
+    pub fn confirm_seller_bond(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+    ) -> Result<(), Error> {
+        self.confirm_withdrawal(house_pp, auction_pp.seller_bond)
+    }
+
+    pub fn confirm_seller_bond_return(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+    ) -> Result<(), Error> {
+        self.confirm_deposit(house_pp, auction_pp.seller_bond)
+    }
+
     pub fn confirm_deposit(&mut self, _house_pp: &HouseParams<G>, amt: u32) -> Result<(), Error> {
-        self.public_summary.balance += amt;
+        self.public_summary.balance = add_balance(self.public_summary.balance, amt)?;
+        self.record_balance_event(BalanceEventKind::Deposit, amt as i64);
         Ok(())
     }
 
@@ -242,11 +855,11 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         house_pp: &HouseParams<G>,
         amt: u32,
     ) -> Result<RangeProof<G>, Error> {
-        if self.sum_active_bids > self.public_summary.balance - amt {
+        let balance_less_amt = sub_balance(self.public_summary.balance, amt)?;
+        if self.sum_active_bids > balance_less_amt {
             return Err(Box::new(AuctionError::InvalidBid));
         }
         // Prove balance - amt - active_bids > 0
-        let balance_less_amt = self.public_summary.balance - amt;
         let f_balance_less_amt = nat_to_f::<G::ScalarField>(&BigInt::from(balance_less_amt))?;
         let comm_balance = house_pp.ped_pp.g.mul(&f_balance_less_amt.into_repr())
             - &self.public_summary.comm_active_bids;
@@ -267,23 +880,265 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         _house_pp: &HouseParams<G>,
         amt: u32,
     ) -> Result<(), Error> {
-        self.public_summary.balance -= amt;
+        self.public_summary.balance = sub_balance(self.public_summary.balance, amt)?;
+        self.record_balance_event(BalanceEventKind::Withdrawal, -(amt as i64));
         Ok(())
     }
 }
 
-impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
-    AuctionHouse<G, PoEP, RsaP, H, H2P>
+impl<
+        G: ProjectiveCurve,
+        PoEP: PoEParams,
+        RsaP: RsaGroupParams,
+        H: Digest,
+        H2P: HashToPrime,
+        S: SealedBidScheme<G, RsaP>,
+        B: CoinBackend,
+    > AuctionHouse<G, PoEP, RsaP, H, H2P, S, B>
 {
-    pub fn new(_house_pp: &HouseParams<G>) -> Self {
+    pub fn new(house_pp: &HouseParams<G>) -> Self
+    where
+        B: Default,
+    {
+        Self::with_coin(house_pp, B::default())
+    }
+
+    // House whose deposits and withdrawals move funds of `coin`
+    pub fn with_coin(_house_pp: &HouseParams<G>, coin: B) -> Self {
         Self {
             active_auctions: HashMap::new(),
+            seller_bonds: HashMap::new(),
+            auction_rewards: HashMap::new(),
+            auction_timings: HashMap::new(),
             accounts: HashMap::new(),
+            bid_records: HashMap::new(),
+            total_deposited: 0,
+            total_withdrawn: 0,
+            seller_proceeds: 0,
+            forfeited_bonds: 0,
+            treasury: None,
+            reclaim_deadlines: HashMap::new(),
+            completed_auctions: HashSet::new(),
+            coin,
             ctr_auction: 0,
             ctr_account: 0,
         }
     }
 
+    pub fn coin(&self) -> &B {
+        &self.coin
+    }
+
+    // Persists accounts, active auctions with their bids and bid records, seller bonds, rewards,
+    // phase timings, reclaim deadlines and which auctions are completed. Parameters and the coin backend are not included and must be supplied
+    // again alongside the loaded house.
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        self.ctr_auction.serialize(&mut writer)?;
+        self.ctr_account.serialize(&mut writer)?;
+        self.total_deposited.serialize(&mut writer)?;
+        self.total_withdrawn.serialize(&mut writer)?;
+        self.seller_proceeds.serialize(&mut writer)?;
+        self.forfeited_bonds.serialize(&mut writer)?;
+        self.treasury.is_some().serialize(&mut writer)?;
+        if let Some(treasury) = self.treasury {
+            treasury.serialize(&mut writer)?;
+        }
+        (self.accounts.len() as u64).serialize(&mut writer)?;
+        for (user_id, summary) in sorted_entries(&self.accounts) {
+            user_id.serialize(&mut writer)?;
+            summary.balance.serialize(&mut writer)?;
+            summary
+                .comm_active_bids
+                .into_affine()
+                .serialize(&mut writer)?;
+        }
+        (self.seller_bonds.len() as u64).serialize(&mut writer)?;
+        for (auction_id, (seller_id, bond)) in sorted_entries(&self.seller_bonds) {
+            auction_id.serialize(&mut writer)?;
+            seller_id.serialize(&mut writer)?;
+            bond.serialize(&mut writer)?;
+        }
+        (self.auction_rewards.len() as u64).serialize(&mut writer)?;
+        for (auction_id, rewards) in sorted_entries(&self.auction_rewards) {
+            auction_id.serialize(&mut writer)?;
+            rewards.reward_self_open.serialize(&mut writer)?;
+            rewards.reward_force_open.serialize(&mut writer)?;
+        }
+        (self.auction_timings.len() as u64).serialize(&mut writer)?;
+        for (auction_id, timing) in sorted_entries(&self.auction_timings) {
+            auction_id.serialize(&mut writer)?;
+            timing.write(&mut writer)?;
+        }
+        (self.reclaim_deadlines.len() as u64).serialize(&mut writer)?;
+        for (auction_id, deadline) in sorted_entries(&self.reclaim_deadlines) {
+            auction_id.serialize(&mut writer)?;
+            deadline.serialize(&mut writer)?;
+        }
+        let mut completed = self.completed_auctions.iter().copied().collect::<Vec<_>>();
+        completed.sort_unstable();
+        (completed.len() as u64).serialize(&mut writer)?;
+        for auction_id in completed {
+            auction_id.serialize(&mut writer)?;
+        }
+        (self.active_auctions.len() as u64).serialize(&mut writer)?;
+        for (auction_id, (auction, bid_map)) in sorted_entries(&self.active_auctions) {
+            auction_id.serialize(&mut writer)?;
+            auction.write_state(&mut writer)?;
+            (bid_map.len() as u64).serialize(&mut writer)?;
+            for (user_id, bid_id) in sorted_entries(bid_map) {
+                user_id.serialize(&mut writer)?;
+                bid_id.serialize(&mut writer)?;
+            }
+        }
+        (self.bid_records.len() as u64).serialize(&mut writer)?;
+        for (auction_id, records) in sorted_entries(&self.bid_records) {
+            auction_id.serialize(&mut writer)?;
+            (records.len() as u64).serialize(&mut writer)?;
+            for (bid_id, record) in sorted_entries(records) {
+                bid_id.serialize(&mut writer)?;
+                write_range_proof(&record.range_proof_bid, &mut writer)?;
+                write_range_proof(&record.range_proof_balance, &mut writer)?;
+                record.comm_balance.into_affine().serialize(&mut writer)?;
+                (record.kind == BidProofKind::Bound).serialize(&mut writer)?;
+                match &record.opening {
+                    None => 0u8.serialize(&mut writer)?,
+                    Some(RecordedOpening::Timed(bid, opening)) => {
+                        1u8.serialize(&mut writer)?;
+                        bid.is_some().serialize(&mut writer)?;
+                        bid.unwrap_or(0).serialize(&mut writer)?;
+                        S::write_opening(opening, &mut writer)?;
+                    }
+                    Some(RecordedOpening::Pedersen(bid, opening)) => {
+                        2u8.serialize(&mut writer)?;
+                        bid.serialize(&mut writer)?;
+                        opening.serialize(&mut writer)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load<R: Read>(reader: R) -> Result<Self, Error>
+    where
+        B: Default,
+    {
+        Self::load_with_coin(reader, B::default())
+    }
+
+    pub fn load_with_coin<R: Read>(mut reader: R, coin: B) -> Result<Self, Error> {
+        let mut house = Self {
+            active_auctions: HashMap::new(),
+            seller_bonds: HashMap::new(),
+            auction_rewards: HashMap::new(),
+            auction_timings: HashMap::new(),
+            accounts: HashMap::new(),
+            bid_records: HashMap::new(),
+            ctr_auction: u32::deserialize(&mut reader)?,
+            ctr_account: u32::deserialize(&mut reader)?,
+            total_deposited: u64::deserialize(&mut reader)?,
+            total_withdrawn: u64::deserialize(&mut reader)?,
+            seller_proceeds: u64::deserialize(&mut reader)?,
+            forfeited_bonds: u64::deserialize(&mut reader)?,
+            treasury: match bool::deserialize(&mut reader)? {
+                true => Some(u32::deserialize(&mut reader)?),
+                false => None,
+            },
+            reclaim_deadlines: HashMap::new(),
+            completed_auctions: HashSet::new(),
+            coin,
+        };
+        for _ in 0..u64::deserialize(&mut reader)? {
+            let user_id = u32::deserialize(&mut reader)?;
+            let summary = AccountSummary {
+                balance: u32::deserialize(&mut reader)?,
+                comm_active_bids: G::Affine::deserialize(&mut reader)?.into_projective(),
+            };
+            house.accounts.insert(user_id, summary);
+        }
+        for _ in 0..u64::deserialize(&mut reader)? {
+            let auction_id = u32::deserialize(&mut reader)?;
+            let seller_id = u32::deserialize(&mut reader)?;
+            let bond = u32::deserialize(&mut reader)?;
+            house.seller_bonds.insert(auction_id, (seller_id, bond));
+        }
+        for _ in 0..u64::deserialize(&mut reader)? {
+            let auction_id = u32::deserialize(&mut reader)?;
+            let rewards = AuctionRewards {
+                reward_self_open: u32::deserialize(&mut reader)?,
+                reward_force_open: u32::deserialize(&mut reader)?,
+            };
+            house.auction_rewards.insert(auction_id, rewards);
+        }
+        for _ in 0..u64::deserialize(&mut reader)? {
+            let auction_id = u32::deserialize(&mut reader)?;
+            let timing = PhaseTiming::read(&mut reader)?;
+            house.auction_timings.insert(auction_id, timing);
+        }
+        for _ in 0..u64::deserialize(&mut reader)? {
+            let auction_id = u32::deserialize(&mut reader)?;
+            let deadline = u64::deserialize(&mut reader)?;
+            house.reclaim_deadlines.insert(auction_id, deadline);
+        }
+        for _ in 0..u64::deserialize(&mut reader)? {
+            house
+                .completed_auctions
+                .insert(u32::deserialize(&mut reader)?);
+        }
+        for _ in 0..u64::deserialize(&mut reader)? {
+            let auction_id = u32::deserialize(&mut reader)?;
+            let auction = Auction::read_state(&mut reader)?;
+            let mut bid_map = HashMap::new();
+            for _ in 0..u64::deserialize(&mut reader)? {
+                let user_id = u32::deserialize(&mut reader)?;
+                let bid_id = u32::deserialize(&mut reader)?;
+                bid_map.insert(user_id, bid_id);
+            }
+            house.active_auctions.insert(auction_id, (auction, bid_map));
+        }
+        let lg_n = BID_BITS.trailing_zeros() as usize;
+        for _ in 0..u64::deserialize(&mut reader)? {
+            let auction_id = u32::deserialize(&mut reader)?;
+            let mut records = HashMap::new();
+            for _ in 0..u64::deserialize(&mut reader)? {
+                let bid_id = u32::deserialize(&mut reader)?;
+                let range_proof_bid = read_range_proof(&mut reader, lg_n)?;
+                let range_proof_balance = read_range_proof(&mut reader, lg_n)?;
+                let comm_balance = G::Affine::deserialize(&mut reader)?.into_projective();
+                let kind = match bool::deserialize(&mut reader)? {
+                    true => BidProofKind::Bound,
+                    false => BidProofKind::Plain,
+                };
+                let opening = match u8::deserialize(&mut reader)? {
+                    0 => None,
+                    1 => {
+                        let opened = bool::deserialize(&mut reader)?;
+                        let bid = u32::deserialize(&mut reader)?;
+                        let bid = if opened { Some(bid) } else { None };
+                        Some(RecordedOpening::Timed(bid, S::read_opening(&mut reader)?))
+                    }
+                    2 => Some(RecordedOpening::Pedersen(
+                        u32::deserialize(&mut reader)?,
+                        G::ScalarField::deserialize(&mut reader)?,
+                    )),
+                    _ => return Err(Box::new(SerializationError::InvalidData)),
+                };
+                records.insert(
+                    bid_id,
+                    BidRecord {
+                        range_proof_bid,
+                        range_proof_balance,
+                        comm_balance,
+                        kind,
+                        opening,
+                    },
+                );
+            }
+            house.bid_records.insert(auction_id, records);
+        }
+        Ok(house)
+    }
+
     pub fn new_account(&mut self, _house_pp: &HouseParams<G>) -> (u32, AccountSummary<G>) {
         let user_id = self.ctr_account;
         let user_summary = AccountSummary {
@@ -295,99 +1150,333 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         (user_id, user_summary)
     }
 
+    // Allocates `count` contiguous user ids, equivalent to `count` calls to `new_account`
+    pub fn new_accounts(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        count: usize,
+    ) -> Vec<(u32, AccountPrivateState<G, PoEP, RsaP, H, H2P, S>)> {
+        (0..count)
+            .map(|_| {
+                let (user_id, _) = self.new_account(house_pp);
+                (user_id, AccountPrivateState::new())
+            })
+            .collect()
+    }
+
     pub fn account_deposit(
         &mut self,
         _house_pp: &HouseParams<G>,
         user_id: u32,
         amt: u32,
     ) -> Result<(), Error> {
-        let summary = self
+        let balance = self
             .accounts
-            .get_mut(&user_id)
-            .ok_or(Box::new(AuctionError::InvalidID))?;
-        summary.balance += amt;
+            .get(&user_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?
+            .balance;
+        let balance = add_balance(balance, amt)?;
+        self.coin.debit(user_id, amt)?;
+        self.accounts.get_mut(&user_id).unwrap().balance = balance;
+        self.total_deposited += amt as u64;
+        self.debug_check_invariants();
         Ok(())
     }
 
-    pub fn account_withdrawal(
-        &mut self,
+    // Validates a withdrawal without applying it, returning the balance after withdrawal
+    fn check_account_withdrawal(
+        &self,
         house_pp: &HouseParams<G>,
         user_id: u32,
         amt: u32,
         proof: &RangeProof<G>,
-    ) -> Result<(), Error> {
+    ) -> Result<u32, Error> {
         let user_summary = self
             .accounts
-            .get_mut(&user_id)
+            .get(&user_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?;
+        let balance_less_amt = sub_balance(user_summary.balance, amt)?;
+        if !self.verify_withdrawal(house_pp, user_id, amt, proof)? {
+            return Err(Box::new(AuctionError::InvalidBid));
+        }
+        Ok(balance_less_amt)
+    }
+
+    // Checks the withdrawal proof against the account's balance less `amt` and its active bids,
+    // e.g. for a relayer to validate before submitting. Withdrawals over the balance are invalid.
+    pub fn verify_withdrawal(
+        &self,
+        house_pp: &HouseParams<G>,
+        user_id: u32,
+        amt: u32,
+        proof: &RangeProof<G>,
+    ) -> Result<bool, Error> {
+        let user_summary = self
+            .accounts
+            .get(&user_id)
             .ok_or(Box::new(AuctionError::InvalidID))?;
-        let balance_less_amt = user_summary.balance - amt;
+        let balance_less_amt = match user_summary.balance.checked_sub(amt) {
+            Some(balance_less_amt) => balance_less_amt,
+            None => return Ok(false),
+        };
         let f_balance_less_amt = nat_to_f::<G::ScalarField>(&BigInt::from(balance_less_amt))?;
         let comm_balance =
             house_pp.ped_pp.g.mul(&f_balance_less_amt.into_repr()) - &user_summary.comm_active_bids;
-        if !Bulletproofs::<G, H>::verify_range(
+        Bulletproofs::<G, H>::verify_range(
             &house_pp.range_proof_pp,
             &house_pp.ped_pp,
             &comm_balance,
             BID_BITS as u64,
             proof,
-        )? {
-            return Err(Box::new(AuctionError::InvalidBid));
-        }
-        user_summary.balance -= amt;
+        )
+    }
+
+    pub fn account_withdrawal(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        user_id: u32,
+        amt: u32,
+        proof: &RangeProof<G>,
+    ) -> Result<(), Error> {
+        let balance_less_amt = self.check_account_withdrawal(house_pp, user_id, amt, proof)?;
+        self.coin.credit(user_id, amt)?;
+        self.accounts.get_mut(&user_id).unwrap().balance = balance_less_amt;
+        self.total_withdrawn += amt as u64;
+        self.debug_check_invariants();
         Ok(())
     }
 
+    // Reports whether `account_withdrawal` would succeed, leaving house state untouched
+    pub fn account_withdrawal_dry_run(
+        &self,
+        house_pp: &HouseParams<G>,
+        user_id: u32,
+        amt: u32,
+        proof: &RangeProof<G>,
+    ) -> Result<(), Error> {
+        self.check_account_withdrawal(house_pp, user_id, amt, proof)
+            .map(|_| ())
+    }
+
+    // Checks the committed amounts of a balance breakdown sum to the account's current balance,
+    // and that the breakdown was made for this account's `proposal` in `auction_id`
+    pub fn verify_balance_breakdown(
+        &self,
+        house_pp: &HouseParams<G>,
+        auction_id: u32,
+        user_id: u32,
+        proposal: &BidProposal<G, S::Comm>,
+        breakdown: &BalanceBreakdown<G>,
+    ) -> Result<bool, Error> {
+        let balance = self
+            .accounts
+            .get(&user_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?
+            .balance;
+        Bulletproofs::<G, H>::verify_sum(
+            &house_pp.range_proof_pp,
+            &house_pp.ped_pp,
+            &breakdown.comm_amounts,
+            &G::ScalarField::from(balance as u64),
+            &breakdown.proof,
+            &breakdown_binding(user_id, auction_id, &S::ped_comm(&proposal.comm_bid))?,
+        )
+    }
+
+    // Locks the seller bond until the auction completes. The bond proof shows the seller's
+    // balance covers the bond on top of their active bids and may be omitted for a zero bond.
+    // Rewards default to those of `auction_pp` unless overridden for this auction.
     pub fn new_auction(
         &mut self,
-        _house_pp: &HouseParams<G>,
+        house_pp: &HouseParams<G>,
         auction_pp: &HouseAuctionParams<G, RsaP>,
-    ) -> u32 {
+        seller_id: u32,
+        bond_proof: Option<&RangeProof<G>>,
+        rewards: Option<AuctionRewards>,
+    ) -> Result<u32, Error> {
+        let seller_summary = self
+            .accounts
+            .get_mut(&seller_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?;
+        if auction_pp.seller_bond > 0 {
+            let balance_less_bond = sub_balance(seller_summary.balance, auction_pp.seller_bond)?;
+            let proof = bond_proof.ok_or(Box::new(AuctionError::InvalidBid))?;
+            let f_balance_less_bond = nat_to_f::<G::ScalarField>(&BigInt::from(balance_less_bond))?;
+            let comm_balance = house_pp.ped_pp.g.mul(&f_balance_less_bond.into_repr())
+                - &seller_summary.comm_active_bids;
+            if !Bulletproofs::<G, H>::verify_range(
+                &house_pp.range_proof_pp,
+                &house_pp.ped_pp,
+                &comm_balance,
+                BID_BITS as u64,
+                proof,
+            )? {
+                return Err(Box::new(AuctionError::InvalidBid));
+            }
+            seller_summary.balance = balance_less_bond;
+        }
+
         let auction_id = self.ctr_auction;
         //TODO: Assert Pedersen parameters between auction and house are the same
         self.active_auctions.insert(
             auction_id,
             (Auction::new(&auction_pp.auction_pp), HashMap::new()),
         );
+        self.seller_bonds
+            .insert(auction_id, (seller_id, auction_pp.seller_bond));
+        self.auction_rewards
+            .insert(auction_id, rewards.unwrap_or_else(|| auction_pp.rewards()));
+        self.auction_timings
+            .insert(auction_id, auction_pp.auction_pp.timing);
         self.ctr_auction += 1;
-        auction_id
+        self.debug_check_invariants();
+        Ok(auction_id)
     }
 
-    pub fn account_bid(
+    // Cancels auction during bid collection. Bidders are refunded their rewards, the seller bond
+    // is forfeited to the house.
+    pub fn cancel_auction(
         &mut self,
+        _house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+    ) -> Result<(), Error> {
+        let rewards = self.auction_rewards(auction_id)?;
+        {
+            let (auction, bid_map) = self
+                .active_auctions
+                .get(&auction_id)
+                .ok_or(Box::new(AuctionError::InvalidID))?;
+            if auction.phase(&auction_pp.auction_pp, None) != AuctionPhase::BidCollection {
+                return Err(Box::new(AuctionError::InvalidPhase));
+            }
+            let reward = add_balance(rewards.reward_self_open, rewards.reward_force_open)?;
+            for (uid, bid_id) in bid_map.iter() {
+                let bid_comm = auction.bid_comms_i.get(&(*bid_id as usize)).unwrap();
+                let user_summary = self.accounts.get_mut(uid).unwrap();
+                user_summary.balance = add_balance(user_summary.balance, reward)?;
+                user_summary.comm_active_bids -= S::ped_comm(bid_comm);
+            }
+        }
+        self.active_auctions.remove(&auction_id);
+        if let Some((_, bond)) = self.seller_bonds.remove(&auction_id) {
+            self.forfeited_bonds += bond as u64;
+        }
+        self.auction_rewards.remove(&auction_id);
+        self.auction_timings.remove(&auction_id);
+        self.bid_records.remove(&auction_id);
+        self.debug_check_invariants();
+        Ok(())
+    }
+
+    // Advances the block number seen by active auctions, for auctions with block-based timing
+    pub fn set_block_number(&mut self, block: u64) {
+        for (auction, _) in self.active_auctions.values_mut() {
+            auction.set_block(block);
+        }
+    }
+
+    // Auctions in `phase` at `now` and `block`, in ascending auction_id order
+    pub fn auctions_in_phase(&self, phase: AuctionPhase, now: Instant, block: u64) -> Vec<u32> {
+        sorted_entries(&self.active_auctions)
+            .into_iter()
+            .filter(|(auction_id, (auction, _))| {
+                self.auction_timings
+                    .get(auction_id)
+                    .map_or(false, |timing| {
+                        auction.phase_at(timing, now, block) == phase
+                    })
+            })
+            .map(|(auction_id, _)| auction_id)
+            .collect()
+    }
+
+    pub fn auction_rewards(&self, auction_id: u32) -> Result<AuctionRewards, Error> {
+        self.auction_rewards
+            .get(&auction_id)
+            .copied()
+            .ok_or(Box::new(AuctionError::InvalidID) as Error)
+    }
+
+    // Bids of an auction with their current state, in ascending user_id order
+    pub fn iter_bids(
+        &self,
+        auction_id: u32,
+    ) -> Result<impl Iterator<Item = (u32, BidState<'_, S::Comm>)> + '_, Error> {
+        let (auction, bid_map) = self
+            .active_auctions
+            .get(&auction_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?;
+        let mut bids = bid_map.iter().collect::<Vec<_>>();
+        bids.sort_unstable_by_key(|(uid, _)| **uid);
+        Ok(bids
+            .into_iter()
+            .map(move |(uid, bid_id)| (*uid, auction.bid_state(*bid_id as usize).unwrap())))
+    }
+
+    // Bidders whose bids are still sealed, in ascending user_id order. Once the self open
+    // window closes these are exactly the bids left to force open.
+    pub fn unopened_bidders(&self, auction_id: u32) -> Result<Vec<u32>, Error> {
+        Ok(self
+            .iter_bids(auction_id)?
+            .filter(|(_, state)| matches!(state, BidState::Sealed(_)))
+            .map(|(uid, _)| uid)
+            .collect())
+    }
+
+    // Validates a bid without accepting it, returning the balance after reward escrow and the
+    // commitment the balance proof was checked against
+    fn check_account_bid(
+        &self,
         house_pp: &HouseParams<G>,
         auction_pp: &HouseAuctionParams<G, RsaP>,
         auction_id: u32,
         user_id: u32,
-        bid: &BidProposal<G, RsaP>,
-    ) -> Result<(), Error> {
+        bid: &BidProposal<G, S::Comm>,
+        slot: BidSlot,
+        kind: BidProofKind,
+    ) -> Result<(u32, G), Error> {
+        let rewards = self.auction_rewards(auction_id)?;
         let user_summary = self
             .accounts
-            .get_mut(&user_id)
+            .get(&user_id)
             .ok_or(Box::new(AuctionError::InvalidID))?;
         let (auction, bid_map) = self
             .active_auctions
-            .get_mut(&auction_id)
+            .get(&auction_id)
             .ok_or(Box::new(AuctionError::InvalidID))?;
-        // TODO: Allow multiple bids from a single user
-        if bid_map.contains_key(&user_id) {
+        match (bid_map.contains_key(&user_id), slot) {
+            (true, BidSlot::New) => return Err(Box::new(AuctionError::DuplicateBid)),
+            (false, BidSlot::Replace) => return Err(Box::new(AuctionError::InvalidBid)),
+            _ => (),
+        }
+        // Reject malformed commitments before they can only be caught by force opening
+        if !S::check_comm(&auction_pp.auction_pp.time_pp, &bid.comm_bid)? {
             return Err(Box::new(AuctionError::InvalidBid));
         }
-        // Verify bid > 0
-        if !Bulletproofs::<G, H>::verify_range(
+        // Verify bid > 0 against the Pedersen layer of the sealed bid, so the range proven is
+        // that of the value that will be opened. A proof made for another value commitment is a
+        // mismatch, one that fails for this commitment is invalid.
+        if bid.comm_range_bid != S::ped_comm(&bid.comm_bid) {
+            return Err(Box::new(AuctionError::BidCommitmentMismatch));
+        }
+        let ad = bid_binding::<G, RsaP, S>(kind, &bid.comm_bid)?;
+        if !Bulletproofs::<G, H>::verify_range_with_ad(
             &house_pp.range_proof_pp,
             &house_pp.ped_pp,
-            &bid.comm_bid.ped_comm,
+            &bid.comm_range_bid,
             BID_BITS as u64,
             &bid.range_proof_bid,
+            &ad,
         )? {
             return Err(Box::new(AuctionError::InvalidBid));
         }
         // Verify balance - reward - bid - active_bids > 0
-        let balance_less_reward =
-            user_summary.balance - auction_pp.reward_self_open - auction_pp.reward_force_open;
+        let reward = add_balance(rewards.reward_self_open, rewards.reward_force_open)?;
+        let balance_less_reward = sub_balance(user_summary.balance, reward)?;
         let f_balance_less_reward = nat_to_f::<G::ScalarField>(&BigInt::from(balance_less_reward))?;
         let comm_balance = house_pp.ped_pp.g.mul(&f_balance_less_reward.into_repr())
-            - &bid.comm_bid.ped_comm
+            - &S::ped_comm(&bid.comm_bid)
             - &user_summary.comm_active_bids;
         if !Bulletproofs::<G, H>::verify_range(
             &house_pp.range_proof_pp,
@@ -398,14 +1487,233 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         )? {
             return Err(Box::new(AuctionError::InvalidBid));
         }
+        auction.check_bid(&auction_pp.auction_pp, &bid.comm_bid)?;
+        Ok((balance_less_reward, comm_balance))
+    }
+
+    // Keeps the proofs of an accepted bid for `audit_auction`, replacing those of a prior bid
+    fn record_bid(
+        &mut self,
+        auction_id: u32,
+        bid_id: u32,
+        bid: &BidProposal<G, S::Comm>,
+        comm_balance: G,
+        kind: BidProofKind,
+    ) {
+        self.bid_records.entry(auction_id).or_default().insert(
+            bid_id,
+            BidRecord {
+                range_proof_bid: bid.range_proof_bid.clone(),
+                range_proof_balance: bid.range_proof_balance.clone(),
+                comm_balance,
+                kind,
+                opening: None,
+            },
+        );
+    }
+
+    fn record_opening(
+        &mut self,
+        auction_id: u32,
+        bid_id: u32,
+        opening: RecordedOpening<G, S::Opening>,
+    ) {
+        if let Some(record) = self
+            .bid_records
+            .get_mut(&auction_id)
+            .and_then(|records| records.get_mut(&bid_id))
+        {
+            record.opening = Some(opening);
+        }
+    }
+
+    pub fn account_bid(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        user_id: u32,
+        bid: &BidProposal<G, S::Comm>,
+    ) -> Result<(), Error> {
+        self.account_bid_with_binding(
+            house_pp,
+            auction_pp,
+            auction_id,
+            user_id,
+            bid,
+            BidProofKind::Plain,
+        )
+    }
+
+    // Same as `account_bid`, requiring a bid range proof bound to the sealed bid as produced by
+    // `propose_bid_bound`. Off-chain only, there is no on-chain counterpart.
+    pub fn account_bid_bound(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        user_id: u32,
+        bid: &BidProposal<G, S::Comm>,
+    ) -> Result<(), Error> {
+        self.account_bid_with_binding(
+            house_pp,
+            auction_pp,
+            auction_id,
+            user_id,
+            bid,
+            BidProofKind::Bound,
+        )
+    }
+
+    fn account_bid_with_binding(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        user_id: u32,
+        bid: &BidProposal<G, S::Comm>,
+        kind: BidProofKind,
+    ) -> Result<(), Error> {
+        let (balance_less_reward, comm_balance) = self.check_account_bid(
+            house_pp,
+            auction_pp,
+            auction_id,
+            user_id,
+            bid,
+            BidSlot::New,
+            kind,
+        )?;
         // Update state
+        let (auction, bid_map) = self.active_auctions.get_mut(&auction_id).unwrap();
         let bid_id = auction.accept_bid(&auction_pp.auction_pp, &bid.comm_bid)?;
         bid_map.insert(user_id, bid_id as u32);
-        user_summary.balance -= auction_pp.reward_self_open + auction_pp.reward_force_open;
-        user_summary.comm_active_bids += &bid.comm_bid.ped_comm;
+        self.record_bid(auction_id, bid_id as u32, bid, comm_balance, kind);
+        let user_summary = self.accounts.get_mut(&user_id).unwrap();
+        user_summary.balance = balance_less_reward;
+        user_summary.comm_active_bids += &S::ped_comm(&bid.comm_bid);
+        self.debug_check_invariants();
+        Ok(())
+    }
+
+    // Reports whether `account_bid` would succeed, leaving house state untouched
+    pub fn account_bid_dry_run(
+        &self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        user_id: u32,
+        bid: &BidProposal<G, S::Comm>,
+    ) -> Result<(), Error> {
+        self.check_account_bid(
+            house_pp,
+            auction_pp,
+            auction_id,
+            user_id,
+            bid,
+            BidSlot::New,
+            BidProofKind::Plain,
+        )
+        .map(|_| ())
+    }
+
+    // Replaces the user's unopened bid, keeping its bid id. The reservation of the replaced bid is
+    // refunded before the new bid is checked, and restored if the new bid is rejected.
+    pub fn account_replace_bid(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        user_id: u32,
+        bid: &BidProposal<G, S::Comm>,
+    ) -> Result<(), Error> {
+        let rewards = self.auction_rewards(auction_id)?;
+        let prior_summary = self
+            .accounts
+            .get(&user_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?
+            .clone();
+        let (bid_id, prior_comm) = {
+            let (auction, bid_map) = self
+                .active_auctions
+                .get(&auction_id)
+                .ok_or(Box::new(AuctionError::InvalidID))?;
+            let bid_id = *bid_map
+                .get(&user_id)
+                .ok_or(Box::new(AuctionError::InvalidBid))?;
+            let prior_comm = auction
+                .bid_comms_i
+                .get(&(bid_id as usize))
+                .ok_or(Box::new(AuctionError::InvalidBid))?
+                .clone();
+            (bid_id, prior_comm)
+        };
+        // Refund prior reservation
+        let reward = add_balance(rewards.reward_self_open, rewards.reward_force_open)?;
+        let user_summary = self.accounts.get_mut(&user_id).unwrap();
+        user_summary.balance = add_balance(user_summary.balance, reward)?;
+        user_summary.comm_active_bids -= &S::ped_comm(&prior_comm);
+        let (balance_less_reward, comm_balance) = match self.check_account_bid(
+            house_pp,
+            auction_pp,
+            auction_id,
+            user_id,
+            bid,
+            BidSlot::Replace,
+            BidProofKind::Plain,
+        ) {
+            Ok(checked) => checked,
+            Err(e) => {
+                self.accounts.insert(user_id, prior_summary);
+                return Err(e);
+            }
+        };
+        // Update state
+        let (auction, _) = self.active_auctions.get_mut(&auction_id).unwrap();
+        let bid_index = bid_id as usize;
+        if let Err(e) = auction.replace_bid(&auction_pp.auction_pp, bid_index, &bid.comm_bid) {
+            self.accounts.insert(user_id, prior_summary);
+            return Err(e);
+        }
+        self.record_bid(auction_id, bid_id, bid, comm_balance, BidProofKind::Plain);
+        let user_summary = self.accounts.get_mut(&user_id).unwrap();
+        user_summary.balance = balance_less_reward;
+        user_summary.comm_active_bids += &S::ped_comm(&bid.comm_bid);
+        self.debug_check_invariants();
         Ok(())
     }
 
+    // Deposits and bids atomically, the deposit is refunded if the bid is rejected
+    pub fn deposit_and_bid(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        user_id: u32,
+        amt: u32,
+        bid: &BidProposal<G, S::Comm>,
+    ) -> Result<BidReceipt, Error> {
+        self.account_deposit(house_pp, user_id, amt)?;
+        if let Err(e) = self.account_bid(house_pp, auction_pp, auction_id, user_id, bid) {
+            let user_summary = self.accounts.get_mut(&user_id).unwrap();
+            user_summary.balance = sub_balance(user_summary.balance, amt)?;
+            self.total_deposited -= amt as u64;
+            // The bid error is what the caller acts on, so a failed refund is reported with it
+            if let Err(refund_e) = self.coin.credit(user_id, amt) {
+                return Err(Box::new(AuctionError::RefundFailed {
+                    bid: e.to_string(),
+                    refund: refund_e.to_string(),
+                }));
+            }
+            return Err(e);
+        }
+        let (_, bid_map) = self.active_auctions.get(&auction_id).unwrap();
+        Ok(BidReceipt {
+            auction_id,
+            bid_id: *bid_map.get(&user_id).unwrap(),
+            balance: self.accounts.get(&user_id).unwrap().balance,
+        })
+    }
+
     pub fn account_self_open(
         &mut self,
         _house_pp: &HouseParams<G>,
@@ -413,8 +1721,9 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         auction_id: u32,
         user_id: u32,
         bid: u32,
-        opening: &TCOpening<G, RsaP, H2P>,
+        opening: &S::Opening,
     ) -> Result<(), Error> {
+        let rewards = self.auction_rewards(auction_id)?;
         let user_summary = self
             .accounts
             .get_mut(&user_id)
@@ -426,9 +1735,18 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         let bid_id = bid_map
             .get(&user_id)
             .ok_or(Box::new(AuctionError::InvalidID))?;
+        let reward = add_balance(rewards.reward_self_open, rewards.reward_force_open)?;
+        let balance = add_balance(user_summary.balance, reward)?;
         // Update state
         auction.accept_self_opening(&auction_pp.auction_pp, bid, opening, *bid_id as usize)?;
-        user_summary.balance += auction_pp.reward_self_open + auction_pp.reward_force_open;
+        user_summary.balance = balance;
+        let bid_id = *bid_id;
+        self.record_opening(
+            auction_id,
+            bid_id,
+            RecordedOpening::Timed(Some(bid), opening.clone()),
+        );
+        self.debug_check_invariants();
         Ok(())
     }
 
@@ -441,6 +1759,7 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         bid: u32,
         opening: &G::ScalarField,
     ) -> Result<(), Error> {
+        let rewards = self.auction_rewards(auction_id)?;
         let user_summary = self
             .accounts
             .get_mut(&user_id)
@@ -452,6 +1771,8 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         let bid_id = bid_map
             .get(&user_id)
             .ok_or(Box::new(AuctionError::InvalidID))?;
+        let reward = add_balance(rewards.reward_self_open, rewards.reward_force_open)?;
+        let balance = add_balance(user_summary.balance, reward)?;
         // Update state
         auction.accept_self_opening_optimized(
             &auction_pp.auction_pp,
@@ -459,10 +1780,15 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
             opening,
             *bid_id as usize,
         )?;
-        user_summary.balance += auction_pp.reward_self_open + auction_pp.reward_force_open;
+        user_summary.balance = balance;
+        let bid_id = *bid_id;
+        self.record_opening(auction_id, bid_id, RecordedOpening::Pedersen(bid, *opening));
+        self.debug_check_invariants();
         Ok(())
     }
 
+    // `bid` is None for a commitment that did not open to a valid bid, including one revealing
+    // bytes outside the bid range. The bidder forfeits and completion counts them as a loser.
     pub fn account_force_open(
         &mut self,
         _house_pp: &HouseParams<G>,
@@ -471,8 +1797,9 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         user_id: u32,
         bid_id: u32,
         bid: Option<u32>,
-        opening: &TCOpening<G, RsaP, H2P>,
+        opening: &S::Opening,
     ) -> Result<(), Error> {
+        let rewards = self.auction_rewards(auction_id)?;
         let user_summary = self
             .accounts
             .get_mut(&user_id)
@@ -481,21 +1808,138 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
             .active_auctions
             .get_mut(&auction_id)
             .ok_or(Box::new(AuctionError::InvalidID))?;
+        let balance = add_balance(user_summary.balance, rewards.reward_force_open)?;
         // Update state
         auction.accept_force_opening(&auction_pp.auction_pp, bid, opening, bid_id as usize)?;
-        user_summary.balance += auction_pp.reward_force_open;
+        user_summary.balance = balance;
+        self.record_opening(
+            auction_id,
+            bid_id,
+            RecordedOpening::Timed(bid, opening.clone()),
+        );
+        self.debug_check_invariants();
         Ok(())
     }
 
+    // Re-verifies the range proofs and the accepted opening of every bid of an auction, keyed by
+    // user_id. Errors if any bid has no recorded proofs rather than leaving it out.
+    pub fn audit_auction(
+        &self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+    ) -> Result<HashMap<u32, AuditResult>, Error> {
+        let (auction, bid_map) = self
+            .active_auctions
+            .get(&auction_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?;
+        let pp = &auction_pp.auction_pp;
+        let mut results = HashMap::new();
+        for (user_id, bid_id) in bid_map.iter() {
+            let record = self
+                .bid_records
+                .get(&auction_id)
+                .and_then(|records| records.get(bid_id))
+                .ok_or(Box::new(AuctionError::MissingBidRecord(*bid_id)))?;
+            let comm = auction
+                .bid_comms_i
+                .get(&(*bid_id as usize))
+                .ok_or(Box::new(AuctionError::InvalidBid))?;
+            let ad = bid_binding::<G, RsaP, S>(record.kind, comm)?;
+            let range_proof_bid = Bulletproofs::<G, H>::verify_range_with_ad(
+                &house_pp.range_proof_pp,
+                &house_pp.ped_pp,
+                &S::ped_comm(comm),
+                BID_BITS as u64,
+                &record.range_proof_bid,
+                &ad,
+            )
+            .unwrap_or(false);
+            let range_proof_balance = Bulletproofs::<G, H>::verify_range(
+                &house_pp.range_proof_pp,
+                &house_pp.ped_pp,
+                &record.comm_balance,
+                BID_BITS as u64,
+                &record.range_proof_balance,
+            )
+            .unwrap_or(false);
+            // The opening must verify and open to the value the auction recorded
+            let opened = auction.bid_openings.get(&(*bid_id as usize));
+            let opening = record.opening.as_ref().map(|opening| match opening {
+                RecordedOpening::Timed(bid, opening) => {
+                    opened == Some(bid)
+                        && S::ver_open(
+                            &pp.time_pp,
+                            &pp.ped_pp,
+                            comm,
+                            &bid.map(|b| b.to_le_bytes().to_vec()),
+                            opening,
+                        )
+                        .unwrap_or(false)
+                }
+                RecordedOpening::Pedersen(bid, opening) => {
+                    opened == Some(&Some(*bid))
+                        && PedersenComm::<G>::ver_open(
+                            &pp.ped_pp,
+                            &S::ped_comm(comm),
+                            &bid.to_le_bytes(),
+                            opening,
+                        )
+                        .unwrap_or(false)
+                }
+            });
+            results.insert(
+                *user_id,
+                AuditResult {
+                    range_proof_bid,
+                    range_proof_balance,
+                    opening,
+                },
+            );
+        }
+        Ok(results)
+    }
+
     // Completes auction and returns (price, winners)
     pub fn complete_kplusone_price_auction(
         &mut self,
-        _house_pp: &HouseParams<G>,
+        house_pp: &HouseParams<G>,
         auction_pp: &HouseAuctionParams<G, RsaP>,
         auction_id: u32,
         k: usize,
     ) -> Result<(u32, Vec<u32>), Error> {
-        let (price, winners) = {
+        let report =
+            self.complete_kplusone_price_auction_report(house_pp, auction_pp, auction_id, k)?;
+        Ok((report.price, report.winners))
+    }
+
+    // Completes auction and returns outcome for every bidder
+    pub fn complete_kplusone_price_auction_report(
+        &mut self,
+        _house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        k: usize,
+    ) -> Result<CompletionReport, Error> {
+        let no_bids = self
+            .active_auctions
+            .get(&auction_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?
+            .1
+            .is_empty();
+        // Balances were already settled by an earlier completion
+        if self.completed_auctions.contains(&auction_id) {
+            return Err(Box::new(AuctionError::InvalidPhase));
+        }
+        if no_bids {
+            let report = self.complete_no_bids(auction_pp, auction_id)?;
+            self.completed_auctions.insert(auction_id);
+            return Ok(report);
+        }
+        if !auction_pp.bundle.is_empty() && k != 1 {
+            return Err(Box::new(AuctionError::BundleSplit));
+        }
+        let (price, winners, losers, gaps) = {
             let (auction, bid_map) = self
                 .active_auctions
                 .get(&auction_id)
@@ -503,106 +1947,357 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
             // if auction.phase(&auction_pp.auction_pp) != AuctionPhase::Complete {
             //     return Err(Box::new(AuctionError::InvalidPhase));
             // }
-            let mut bids = bid_map
+            let openings = Self::bid_openings(auction, bid_map);
+            let (price, winners) = Self::kplusone_clearing(&openings, k)?;
+            // Checked before any account changes, so an underflow leaves them untouched
+            let winner_balances = winners
                 .iter()
-                .map(|(uid, bid_id)| (*uid, auction.bid_openings.get(&(*bid_id as usize)).unwrap()))
-                .filter(|(_uid, bid)| bid.is_some())
-                .map(|(uid, bid)| (uid, bid.unwrap()))
-                .collect::<Vec<_>>();
-
-            assert!(bids.len() > k as usize);
-
-            // TODO: Does not handle tie bids. Currently tie is broken by unstable selection algo.
-            let k1_index = bids.len() - (k + 1);
-            bids.select_nth_unstable_by_key(k1_index, |(_, bid)| *bid);
-            let price = bids[k1_index].1;
-            let winners = bids[k1_index + 1..]
-                .iter()
-                .map(|(uid, _)| *uid)
-                .collect::<Vec<_>>();
+                .map(|uid| Ok((*uid, sub_balance(self.accounts[uid].balance, price)?)))
+                .collect::<Result<Vec<_>, Error>>()?;
 
             for (uid, bid_id) in bid_map.iter() {
                 let bid_comm = auction.bid_comms_i.get(&(*bid_id as usize)).unwrap();
-                self.accounts.get_mut(uid).unwrap().comm_active_bids -= bid_comm.ped_comm;
+                self.accounts.get_mut(uid).unwrap().comm_active_bids -= S::ped_comm(bid_comm);
             }
-            for uid in winners.iter() {
-                self.accounts.get_mut(uid).unwrap().balance -= price;
+            for (uid, balance) in winner_balances {
+                self.accounts.get_mut(&uid).unwrap().balance = balance;
             }
-            (price, winners)
+            let (losers, gaps) = Self::settlement_outcomes(bid_map, &openings, &winners, price);
+            (price, winners, losers, gaps)
         };
-        //self.active_auctions.remove(&auction_id);
-        Ok((price, winners))
+        let seller_proceeds = price
+            .checked_mul(winners.len() as u32)
+            .ok_or(Box::new(AuctionError::BalanceOverflow))?;
+        self.seller_proceeds += seller_proceeds as u64;
+        self.return_seller_bond(auction_id)?;
+        self.start_reclaim_window(auction_pp, auction_id);
+        self.completed_auctions.insert(auction_id);
+        self.debug_check_invariants();
+        // Bundles are only auctioned to a single winner
+        let bundle_recipient = winners
+            .first()
+            .copied()
+            .filter(|_| !auction_pp.bundle.is_empty());
+        Ok(CompletionReport {
+            outcome: CompletionOutcome::Cleared,
+            price,
+            winners,
+            losers,
+            seller_proceeds,
+            gaps,
+            bundle_recipient,
+        })
     }
 
-    // Dummy function
-    pub fn complete_fixed_price(
+    // No bids were placed: the item and the seller bond stay with the seller
+    fn complete_no_bids(
         &mut self,
-        _house_pp: &HouseParams<G>,
         auction_pp: &HouseAuctionParams<G, RsaP>,
         auction_id: u32,
-        k: usize,
-    ) -> Result<(u32, Vec<u32>), Error> {
-        let (price, winners) = {
-            let (auction, bid_map) = self
-                .active_auctions
-                .get(&auction_id)
-                .ok_or(Box::new(AuctionError::InvalidID))?;
-            // if auction.phase(&auction_pp.auction_pp) != AuctionPhase::Complete {
-            //     return Err(Box::new(AuctionError::InvalidPhase));
-            // }
-            let mut bids = bid_map
-                .iter()
-                .map(|(uid, bid_id)| (*uid, auction.bid_openings.get(&(*bid_id as usize)).unwrap()))
-                .filter(|(_uid, bid)| bid.is_some())
-                .map(|(uid, bid)| (uid, bid.unwrap()))
-                .collect::<Vec<_>>();
-
-            assert!(bids.len() > k as usize);
-
-            // // TODO: Does not handle tie bids. Currently tie is broken by unstable selection algo.
-            // let k1_index = bids.len() - (k + 1);
-            // bids.select_nth_unstable_by_key(k1_index, |(_, bid)| *bid);
-            // let price = bids[k1_index].1;
-            // let winners = bids[k1_index + 1..]
-            //     .iter()
-            //     .map(|(uid, _)| *uid)
-            //     .collect::<Vec<_>>();
-
-            // let price = 0;
-            // let winners = vec![1];
-
-            // for (uid, bid_id) in bid_map.iter() {
-            //     let bid_comm = auction.bid_comms_i.get(&(*bid_id as usize)).unwrap();
-            //     self.accounts.get_mut(uid).unwrap().comm_active_bids -= bid_comm.ped_comm;
-            // }
-            // for uid in winners.iter() {
-            //     self.accounts.get_mut(uid).unwrap().balance -= price;
-            // }
-            // (price, winners)
+    ) -> Result<CompletionReport, Error> {
+        let seller_id = self
+            .seller_bonds
+            .get(&auction_id)
+            .map(|(seller_id, _)| *seller_id);
+        self.return_seller_bond(auction_id)?;
+        self.start_reclaim_window(auction_pp, auction_id);
+        self.debug_check_invariants();
+        Ok(CompletionReport {
+            outcome: CompletionOutcome::NoBids,
+            price: 0,
+            winners: Vec::new(),
+            losers: Vec::new(),
+            seller_proceeds: 0,
+            gaps: Vec::new(),
+            bundle_recipient: seller_id.filter(|_| !auction_pp.bundle.is_empty()),
+        })
+    }
 
-            self.accounts.get_mut(&1).unwrap().balance -= 0;
-            (0, vec![1])
-        };
-        // self.active_auctions.remove(&auction_id);
-        Ok((price, winners))
+    // Bidders other than the winners, and the gap of every bid to the price, sorted by user_id
+    fn settlement_outcomes(
+        bid_map: &HashMap<u32, u32>,
+        openings: &[(u32, Option<u32>)],
+        winners: &[u32],
+        price: u32,
+    ) -> (Vec<u32>, Vec<(u32, ClearingGap)>) {
+        let mut losers = bid_map
+            .keys()
+            .filter(|uid| !winners.contains(uid))
+            .copied()
+            .collect::<Vec<_>>();
+        losers.sort_unstable();
+        let mut gaps = openings
+            .iter()
+            .map(|(uid, bid)| match bid {
+                Some(bid) => (*uid, ClearingGap::Opened(price as i64 - *bid as i64)),
+                None => (*uid, ClearingGap::Unopened),
+            })
+            .collect::<Vec<_>>();
+        gaps.sort_unstable_by_key(|(uid, _)| *uid);
+        (losers, gaps)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use ark_bls12_381::G1Projective as G;
-    use once_cell::sync::Lazy;
-    use rand::{rngs::StdRng, SeedableRng};
-    use sha3::Keccak256;
-    use std::{str::FromStr, thread, time::Duration};
+    // Seller bond is returned on a successful sale, or when no bids were placed
+    fn return_seller_bond(&mut self, auction_id: u32) -> Result<(), Error> {
+        if let Some((seller_id, bond)) = self.seller_bonds.remove(&auction_id) {
+            let seller_summary = self
+                .accounts
+                .get_mut(&seller_id)
+                .ok_or(Box::new(AuctionError::InvalidID))?;
+            seller_summary.balance = add_balance(seller_summary.balance, bond)?;
+        }
+        Ok(())
+    }
+
+    // Reclaim deadline counts from the block the auction completed in, if the auction has one
+    fn start_reclaim_window(&mut self, auction_pp: &HouseAuctionParams<G, RsaP>, auction_id: u32) {
+        if let (Some(blocks), Some((auction, _))) = (
+            auction_pp.reclaim_deadline,
+            self.active_auctions.get(&auction_id),
+        ) {
+            self.reclaim_deadlines
+                .insert(auction_id, auction.block().saturating_add(blocks));
+        }
+    }
+
+    // Account that `sweep_expired` credits with the escrow left in expired auctions
+    pub fn set_treasury(&mut self, user_id: u32) -> Result<(), Error> {
+        if !self.accounts.contains_key(&user_id) {
+            return Err(Box::new(AuctionError::InvalidID));
+        }
+        self.treasury = Some(user_id);
+        Ok(())
+    }
+
+    // Closes a completed auction once block `now` reaches its reclaim deadline. Opened bids were
+    // already refunded their escrow when they were opened, so nothing is left to credit them.
+    // Rewards still escrowed for bids that were never opened, and the self-open rewards of force
+    // opened bids, are credited to the treasury account. Returns the number of sealed bids swept.
+    pub fn sweep_expired(&mut self, auction_id: u32, now: u64) -> Result<u32, Error> {
+        if !self.active_auctions.contains_key(&auction_id) {
+            return Err(Box::new(AuctionError::InvalidID));
+        }
+        let deadline = *self
+            .reclaim_deadlines
+            .get(&auction_id)
+            .ok_or(Box::new(AuctionError::InvalidPhase))?;
+        if now < deadline {
+            return Err(Box::new(AuctionError::ReclaimNotExpired(deadline)));
+        }
+        let treasury = self.treasury.ok_or(Box::new(AuctionError::NoTreasury))?;
+        let rewards = self.auction_rewards(auction_id)?;
+        let escrow = add_balance(rewards.reward_self_open, rewards.reward_force_open)?;
+        let mut swept = 0;
+        let mut sealed = 0;
+        {
+            let (auction, bid_map) = self.active_auctions.get(&auction_id).unwrap();
+            for (_, bid_id) in sorted_entries(bid_map) {
+                match auction.bid_state(*bid_id as usize) {
+                    Some(BidState::Sealed(_)) => {
+                        swept = add_balance(swept, escrow)?;
+                        sealed += 1;
+                    }
+                    Some(BidState::ForceOpened(_)) | Some(BidState::Forfeited) => {
+                        swept = add_balance(swept, rewards.reward_self_open)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let treasury_summary = self
+            .accounts
+            .get_mut(&treasury)
+            .ok_or(Box::new(AuctionError::InvalidID))?;
+        treasury_summary.balance = add_balance(treasury_summary.balance, swept)?;
+        self.active_auctions.remove(&auction_id);
+        self.auction_rewards.remove(&auction_id);
+        self.auction_timings.remove(&auction_id);
+        self.reclaim_deadlines.remove(&auction_id);
+        self.completed_auctions.remove(&auction_id);
+        self.bid_records.remove(&auction_id);
+        self.debug_check_invariants();
+        Ok(sealed)
+    }
+
+    // Price `complete_kplusone_price_auction` would charge given the bids opened so far, without
+    // completing the auction or touching balances
+    pub fn compute_clearing_price(&self, auction_id: u32, k: usize) -> Result<u32, Error> {
+        let (auction, bid_map) = self
+            .active_auctions
+            .get(&auction_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?;
+        let (price, _) = Self::kplusone_clearing(&Self::bid_openings(auction, bid_map), k)?;
+        Ok(price)
+    }
+
+    // (user_id, opened bid) for every bidder, `None` if not yet opened to a valid bid
+    fn bid_openings(
+        auction: &Auction<G, PoEP, RsaP, H, H2P, S>,
+        bid_map: &HashMap<u32, u32>,
+    ) -> Vec<(u32, Option<u32>)> {
+        bid_map
+            .iter()
+            .map(|(uid, bid_id)| {
+                let bid = auction.bid_openings.get(&(*bid_id as usize)).copied();
+                (*uid, bid.flatten())
+            })
+            .collect()
+    }
+
+    // Price is the (k+1)-th highest opened bid, won by the k bids above it
+    fn kplusone_clearing(
+        openings: &[(u32, Option<u32>)],
+        k: usize,
+    ) -> Result<(u32, Vec<u32>), Error> {
+        let mut bids = openings
+            .iter()
+            .filter_map(|(uid, bid)| bid.map(|bid| (*uid, bid)))
+            .collect::<Vec<_>>();
+        if bids.len() <= k {
+            return Err(Box::new(AuctionError::InvalidPhase));
+        }
+
+        // TODO: Does not handle tie bids. Currently tie is broken by unstable selection algo.
+        let k1_index = bids.len() - (k + 1);
+        bids.select_nth_unstable_by_key(k1_index, |(_, bid)| *bid);
+        let price = bids[k1_index].1;
+        let winners = bids[k1_index + 1..]
+            .iter()
+            .map(|(uid, _)| *uid)
+            .collect::<Vec<_>>();
+        Ok((price, winners))
+    }
+
+    // Dummy function: the k highest opened bids win at a fixed price of 0, and no balances or bid
+    // commitments are settled. Used to benchmark completion without the clearing computation.
+    pub fn complete_fixed_price(
+        &mut self,
+        house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        k: usize,
+    ) -> Result<(u32, Vec<u32>), Error> {
+        let report = self.complete_fixed_price_report(house_pp, auction_pp, auction_id, k)?;
+        Ok((report.price, report.winners))
+    }
+
+    pub fn complete_fixed_price_report(
+        &mut self,
+        _house_pp: &HouseParams<G>,
+        auction_pp: &HouseAuctionParams<G, RsaP>,
+        auction_id: u32,
+        k: usize,
+    ) -> Result<CompletionReport, Error> {
+        let no_bids = self
+            .active_auctions
+            .get(&auction_id)
+            .ok_or(Box::new(AuctionError::InvalidID))?
+            .1
+            .is_empty();
+        if no_bids {
+            return self.complete_no_bids(auction_pp, auction_id);
+        }
+        let (auction, bid_map) = self.active_auctions.get(&auction_id).unwrap();
+        let price = 0;
+        let openings = Self::bid_openings(auction, bid_map);
+        // Errors rather than panics if fewer than k + 1 bids were opened
+        let (_, winners) = Self::kplusone_clearing(&openings, k)?;
+        let (losers, gaps) = Self::settlement_outcomes(bid_map, &openings, &winners, price);
+        let bundle_recipient = winners
+            .first()
+            .copied()
+            .filter(|_| !auction_pp.bundle.is_empty());
+        Ok(CompletionReport {
+            outcome: CompletionOutcome::Cleared,
+            price,
+            winners,
+            losers,
+            seller_proceeds: 0,
+            gaps,
+            bundle_recipient,
+        })
+    }
+
+    // Checks that all funds deposited and not withdrawn are accounted for: account balances, bid
+    // rewards escrowed for sealed bids, held seller bonds, seller proceeds, and the bonds
+    // forfeited to the house
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let mut held = self
+            .accounts
+            .values()
+            .map(|a| a.balance as u64)
+            .sum::<u64>();
+        held += self
+            .seller_bonds
+            .values()
+            .map(|(_, bond)| *bond as u64)
+            .sum::<u64>();
+        held += self.seller_proceeds + self.forfeited_bonds;
+        for (auction_id, (auction, bid_map)) in sorted_entries(&self.active_auctions) {
+            let rewards = self
+                .auction_rewards
+                .get(&auction_id)
+                .ok_or(InvariantViolation::MissingRewards(auction_id))?;
+            for (_, bid_id) in sorted_entries(bid_map) {
+                held += match auction.bid_state(*bid_id as usize) {
+                    Some(BidState::Sealed(_)) => {
+                        rewards.reward_self_open as u64 + rewards.reward_force_open as u64
+                    }
+                    Some(BidState::SelfOpened(_)) => 0,
+                    // Force open reward is paid to the opener, the self-open reward is forfeited
+                    Some(BidState::ForceOpened(_)) | Some(BidState::Forfeited) => {
+                        rewards.reward_self_open as u64
+                    }
+                    None => {
+                        return Err(InvariantViolation::MissingBid {
+                            auction_id,
+                            bid_id: *bid_id,
+                        })
+                    }
+                };
+            }
+        }
+        if held + self.total_withdrawn != self.total_deposited {
+            return Err(InvariantViolation::Conservation {
+                held,
+                deposited: self.total_deposited,
+                withdrawn: self.total_withdrawn,
+            });
+        }
+        Ok(())
+    }
+
+    // Checked after each operation that moves funds, in debug builds with `check-invariants`
+    fn debug_check_invariants(&self) {
+        #[cfg(all(debug_assertions, feature = "check-invariants"))]
+        if let Err(e) = self.check_invariants() {
+            panic!("{}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auction::PhaseTiming;
+    use ark_bls12_381::G1Projective as G;
+    use once_cell::sync::Lazy;
+    use rand::{rngs::StdRng, SeedableRng};
+    use sha3::Keccak256;
+    use std::{str::FromStr, thread, time::Duration};
 
     use rsa::{
         bigint::BigInt,
         hash_to_prime::pocklington::{PocklingtonCertParams, PocklingtonHash},
     };
 
-    use timed_commitments::lazy_tc::LazyTC;
+    use timed_commitments::{
+        basic_tc::TimeParams,
+        lazy_tc::{Comm as TCComm, LazyTC},
+        PedersenComm,
+    };
+
+    use crate::test_utils::DeterministicRng;
 
     #[derive(Clone, PartialEq, Eq, Debug)]
     pub struct TestRsaParams;
@@ -663,6 +2358,41 @@ mod tests {
         PocklingtonHash<TestPocklingtonParams, Keccak256>,
     >;
 
+    fn bid_value(value: u32) -> BidValue {
+        BidValue::new(value)
+    }
+
+    // House parameters and a wall clock auction with 20s phases, 200/300 open rewards and no
+    // seller bond; tests override the auction fields they exercise
+    fn setup_test_params(
+        rng: &mut StdRng,
+    ) -> (HouseParams<G>, HouseAuctionParams<G, TestRsaParams>) {
+        let (time_pp, _) = TC::gen_time_params(1).unwrap();
+        let ped_pp = TC::gen_pedersen_params(rng);
+        let range_proof_pp = TestRangeProof::gen_params(rng, BID_BITS as u64);
+        let auction_pp = HouseAuctionParams {
+            auction_pp: AuctionParams {
+                timing: PhaseTiming::WallClock {
+                    t_bid_collection: Duration::from_secs(20),
+                    t_bid_self_open: Duration::from_secs(20),
+                },
+                time_pp,
+                ped_pp: ped_pp.clone(),
+            },
+            reward_self_open: 200,
+            reward_force_open: 300,
+            seller_bond: 0,
+            bundle: Vec::new(),
+            reclaim_deadline: None,
+            max_bid_bits: BID_BITS,
+        };
+        let house_pp = HouseParams {
+            range_proof_pp,
+            ped_pp,
+        };
+        (house_pp, auction_pp)
+    }
+
     #[test]
     #[ignore] // Expensive test, run with ``cargo test basic_auction_house_test -- --ignored --nocapture``
     fn basic_auction_house_test() {
@@ -671,25 +2401,39 @@ mod tests {
         let (time_pp, _) = TC::gen_time_params(1).unwrap();
         let ped_pp = TC::gen_pedersen_params(&mut rng);
         let range_proof_pp = TestRangeProof::gen_params(&mut rng, BID_BITS as u64);
+        let auction1_t_bid_collection = Duration::from_secs(20);
+        let auction1_t_bid_self_open = Duration::from_secs(60);
         let auction1_pp = HouseAuctionParams {
             auction_pp: AuctionParams {
-                t_bid_collection: Duration::from_secs(20),
-                t_bid_self_open: Duration::from_secs(60),
+                timing: PhaseTiming::WallClock {
+                    t_bid_collection: auction1_t_bid_collection,
+                    t_bid_self_open: auction1_t_bid_self_open,
+                },
                 time_pp: time_pp.clone(),
                 ped_pp: ped_pp.clone(),
             },
             reward_self_open: 200,
             reward_force_open: 300,
+            seller_bond: 0,
+            bundle: Vec::new(),
+            reclaim_deadline: None,
+            max_bid_bits: BID_BITS,
         };
         let auction2_pp = HouseAuctionParams {
             auction_pp: AuctionParams {
-                t_bid_collection: Duration::from_secs(200),
-                t_bid_self_open: Duration::from_secs(20),
+                timing: PhaseTiming::WallClock {
+                    t_bid_collection: Duration::from_secs(200),
+                    t_bid_self_open: Duration::from_secs(20),
+                },
                 time_pp: time_pp.clone(),
                 ped_pp: ped_pp.clone(),
             },
             reward_self_open: 200,
             reward_force_open: 300,
+            seller_bond: 0,
+            bundle: Vec::new(),
+            reclaim_deadline: None,
+            max_bid_bits: BID_BITS,
         };
 
         let house_pp = HouseParams {
@@ -712,9 +2456,15 @@ mod tests {
             .collect::<Vec<TestUser>>();
 
         // Start auctions
-        let auction1_id = auction_house.new_auction(&house_pp, &auction1_pp);
-        let auction2_id = auction_house.new_auction(&house_pp, &auction2_pp);
-        let auction3_id = auction_house.new_auction(&house_pp, &auction2_pp);
+        let auction1_id = auction_house
+            .new_auction(&house_pp, &auction1_pp, 0, None, None)
+            .unwrap();
+        let auction2_id = auction_house
+            .new_auction(&house_pp, &auction2_pp, 0, None, None)
+            .unwrap();
+        let auction3_id = auction_house
+            .new_auction(&house_pp, &auction2_pp, 0, None, None)
+            .unwrap();
 
         // Bid on auction 1
         let auction1_openings = users
@@ -723,7 +2473,7 @@ mod tests {
             .map(|(uid, user)| {
                 let bid = (uid as u32 + 1) * 100;
                 let (proposal, opening) = user
-                    .propose_bid(&mut rng, &house_pp, &auction1_pp, bid)
+                    .propose_bid(&mut rng, &house_pp, &auction1_pp, bid_value(bid))
                     .unwrap();
                 println!("Auction 1 bid: uid: {}", uid);
                 auction_house
@@ -749,7 +2499,7 @@ mod tests {
             .map(|(uid, user)| {
                 let bid = (uid as u32 + 1) * 200;
                 let (proposal, opening) = user
-                    .propose_bid(&mut rng, &house_pp, &auction2_pp, bid)
+                    .propose_bid(&mut rng, &house_pp, &auction2_pp, bid_value(bid))
                     .unwrap();
                 println!("Auction 2 bid: uid: {}", uid);
                 auction_house
@@ -818,7 +2568,7 @@ mod tests {
         assert!(users
             .get(9)
             .unwrap()
-            .propose_bid(&mut rng, &house_pp, &auction2_pp, 1600,)
+            .propose_bid(&mut rng, &house_pp, &auction2_pp, bid_value(1600),)
             .is_err());
 
         users
@@ -829,7 +2579,7 @@ mod tests {
         let invalid_bid_proposal = users
             .get(9)
             .unwrap()
-            .propose_bid(&mut rng, &house_pp, &auction2_pp, 1600)
+            .propose_bid(&mut rng, &house_pp, &auction2_pp, bid_value(1600))
             .unwrap();
         assert!(auction_house
             .account_bid(
@@ -849,9 +2599,9 @@ mod tests {
         // Return self-opening rewards of auction 1
         println!(
             "Sleeping for Auction 1 bid collection: {} seconds",
-            auction1_pp.auction_pp.t_bid_collection.as_secs()
+            auction1_t_bid_collection.as_secs()
         );
-        thread::sleep(auction1_pp.auction_pp.t_bid_collection);
+        thread::sleep(auction1_t_bid_collection);
 
         users
             .iter_mut()
@@ -879,7 +2629,7 @@ mod tests {
         let bid_proposal = users
             .get(9)
             .unwrap()
-            .propose_bid(&mut rng, &house_pp, &auction2_pp, 1600)
+            .propose_bid(&mut rng, &house_pp, &auction2_pp, bid_value(1600))
             .unwrap();
         auction_house
             .account_bid(&house_pp, &auction2_pp, auction3_id, 9, &bid_proposal.0)
@@ -902,9 +2652,9 @@ mod tests {
         // Complete auction 1
         println!(
             "Sleeping for Auction 1 bid self-open: {} seconds",
-            auction1_pp.auction_pp.t_bid_self_open.as_secs()
+            auction1_t_bid_self_open.as_secs()
         );
-        thread::sleep(auction1_pp.auction_pp.t_bid_self_open);
+        thread::sleep(auction1_t_bid_self_open);
 
         assert_eq!(auction_house.accounts.get(&0).unwrap().balance, 9000);
         assert_eq!(users.get(0).unwrap().sum_active_bids, 300);
@@ -970,7 +2720,7 @@ mod tests {
         let bid_proposal = users
             .get(8)
             .unwrap()
-            .propose_bid(&mut rng, &house_pp, &auction2_pp, 6000)
+            .propose_bid(&mut rng, &house_pp, &auction2_pp, bid_value(6000))
             .unwrap();
         auction_house
             .account_bid(&house_pp, &auction2_pp, auction3_id, 8, &bid_proposal.0)
@@ -993,7 +2743,7 @@ mod tests {
         let bid_proposal = users
             .get(1)
             .unwrap()
-            .propose_bid(&mut rng, &house_pp, &auction2_pp, 8500)
+            .propose_bid(&mut rng, &house_pp, &auction2_pp, bid_value(8500))
             .unwrap();
         auction_house
             .account_bid(&house_pp, &auction2_pp, auction3_id, 1, &bid_proposal.0)
@@ -1013,4 +2763,2506 @@ mod tests {
         assert_eq!(auction_house.accounts.get(&1).unwrap().balance, 9000);
         assert_eq!(users.get(1).unwrap().sum_active_bids, 8900);
     }
+
+    #[test]
+    fn deterministic_rng_propose_bid_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut user = TestUser::new();
+        user.confirm_deposit(&house_pp, 10000).unwrap();
+
+        let stream = (0..64u8).collect::<Vec<u8>>();
+        let (proposal1, _) = user
+            .propose_bid(
+                &mut DeterministicRng::new(stream.clone()),
+                &house_pp,
+                &auction_pp,
+                bid_value(100),
+            )
+            .unwrap();
+        let (proposal2, _) = user
+            .propose_bid(
+                &mut DeterministicRng::new(stream),
+                &house_pp,
+                &auction_pp,
+                bid_value(100),
+            )
+            .unwrap();
+        assert_eq!(proposal1.comm_bid, proposal2.comm_bid);
+
+        let (proposal3, _) = user
+            .propose_bid(
+                &mut DeterministicRng::new(vec![1u8; 64]),
+                &house_pp,
+                &auction_pp,
+                bid_value(100),
+            )
+            .unwrap();
+        assert_ne!(proposal1.comm_bid, proposal3.comm_bid);
+    }
+
+    #[test]
+    fn new_accounts_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, _) = setup_test_params(&mut rng);
+        let mut house = TestAuctionHouse::new(&house_pp);
+
+        let accounts = house.new_accounts(&house_pp, 5);
+        assert_eq!(
+            accounts.iter().map(|(uid, _)| *uid).collect::<Vec<_>>(),
+            (0..5).collect::<Vec<u32>>()
+        );
+        for (uid, user) in accounts.iter() {
+            assert_eq!(house.accounts.get(uid), Some(&user.public_summary));
+        }
+        let (uid, _) = house.new_account(&house_pp);
+        assert_eq!(uid, 5);
+    }
+
+    #[test]
+    fn withdrawal_underflow_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, _) = setup_test_params(&mut rng);
+
+        let mut user = TestUser::new();
+        user.confirm_deposit(&house_pp, 100).unwrap();
+
+        let err = user
+            .propose_withdrawal(&mut rng, &house_pp, 200)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::BalanceUnderflow)
+        ));
+        let err = user.confirm_withdrawal(&house_pp, 200).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::BalanceUnderflow)
+        ));
+        assert_eq!(user.public_summary.balance, 100);
+
+        let err = user.confirm_deposit(&house_pp, u32::MAX).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::BalanceOverflow)
+        ));
+        assert_eq!(user.public_summary.balance, 100);
+    }
+
+    #[test]
+    fn bid_out_of_range_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        for max_bits in [1, 8, 31] {
+            let max_bid = (1u32 << max_bits) - 1;
+            assert!(check_bid_bits(max_bid, max_bits).is_ok());
+            let err = check_bid_bits(max_bid + 1, max_bits).err().unwrap();
+            assert!(matches!(
+                err.downcast_ref::<AuctionError>(),
+                Some(AuctionError::BidOutOfRange { value, max_bits: bits })
+                    if *value == max_bid + 1 && *bits == max_bits
+            ));
+        }
+        assert!(check_bid_bits(u32::MAX, 32).is_ok());
+
+        // Widest bid the auction accepts is proposed and accounted, one more is rejected
+        let (house_pp, mut auction_pp, mut auction_house, users) =
+            setup_seller_bond_test(&mut rng, 0);
+        auction_pp.max_bid_bits = 8;
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+        let (proposal, _) = users[1]
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(255))
+            .unwrap();
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, 1, &proposal)
+            .unwrap();
+        let err = users[2]
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(256))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::BidOutOfRange {
+                value: 256,
+                max_bits: 8
+            })
+        ));
+
+        // Largest bid passes the range check and is rejected on balance instead
+        let (house_pp, mut auction_pp) = setup_test_params(&mut rng);
+        auction_pp.reward_self_open = 0;
+        auction_pp.reward_force_open = 0;
+        let mut user = MockUser::new();
+        user.confirm_deposit(&house_pp, 100).unwrap();
+        let err = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(u32::MAX))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidBid)
+        ));
+    }
+
+    #[test]
+    fn bid_value_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        assert_eq!(BidValue::new(u32::MAX).value(), u32::MAX);
+
+        let (house_pp, auction_pp, mut auction_house, mut users) =
+            setup_seller_bond_test(&mut rng, 1000);
+        let bond_proof = users[0]
+            .propose_seller_bond(&mut rng, &house_pp, &auction_pp)
+            .unwrap();
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, Some(&bond_proof), None)
+            .unwrap();
+
+        let bid = BidValue::new(300);
+        let (proposal, opening) = users[1]
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid)
+            .unwrap();
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, 1, &proposal)
+            .unwrap();
+        users[1]
+            .confirm_bid(
+                &house_pp,
+                &auction_pp,
+                auction_id,
+                bid.value(),
+                &proposal,
+                &opening,
+            )
+            .unwrap();
+        auction_house
+            .account_self_open(&house_pp, &auction_pp, auction_id, 1, bid.value(), &opening)
+            .unwrap();
+        users[1]
+            .confirm_bid_self_open(&house_pp, &auction_pp)
+            .unwrap();
+        assert_eq!(auction_house.accounts.get(&1).unwrap().balance, 10000);
+        assert_eq!(users[1].public_summary.balance, 10000);
+    }
+
+    fn setup_seller_bond_test(
+        rng: &mut StdRng,
+        seller_bond: u32,
+    ) -> (
+        HouseParams<G>,
+        HouseAuctionParams<G, TestRsaParams>,
+        TestAuctionHouse,
+        Vec<TestUser>,
+    ) {
+        let (house_pp, mut auction_pp) = setup_test_params(rng);
+        auction_pp.seller_bond = seller_bond;
+
+        // uid 0 is the seller, uids 1 and 2 are bidders
+        let mut auction_house = TestAuctionHouse::new(&house_pp);
+        let users = [1000, 10000, 10000]
+            .iter()
+            .map(|amt| {
+                let mut user = TestUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house.account_deposit(&house_pp, uid, *amt).unwrap();
+                user.confirm_deposit(&house_pp, *amt).unwrap();
+                user
+            })
+            .collect::<Vec<TestUser>>();
+        (house_pp, auction_pp, auction_house, users)
+    }
+
+    #[test]
+    fn seller_bond_insufficient_balance_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, auction_pp, mut auction_house, mut users) =
+            setup_seller_bond_test(&mut rng, 1500);
+
+        assert!(users[0]
+            .propose_seller_bond(&mut rng, &house_pp, &auction_pp)
+            .is_err());
+        assert!(auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .is_err());
+
+        // Proof from inflated local balance is rejected by the house
+        users[0].confirm_deposit(&house_pp, 1000).unwrap();
+        let bond_proof = users[0]
+            .propose_seller_bond(&mut rng, &house_pp, &auction_pp)
+            .unwrap();
+        let err = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, Some(&bond_proof), None)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::BalanceUnderflow)
+        ));
+        assert_eq!(auction_house.accounts.get(&0).unwrap().balance, 1000);
+        assert!(auction_house.active_auctions.is_empty());
+    }
+
+    #[test]
+    fn seller_bond_returned_on_completion_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, auction_pp, mut auction_house, mut users) =
+            setup_seller_bond_test(&mut rng, 1000);
+
+        let bond_proof = users[0]
+            .propose_seller_bond(&mut rng, &house_pp, &auction_pp)
+            .unwrap();
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, Some(&bond_proof), None)
+            .unwrap();
+        users[0]
+            .confirm_seller_bond(&house_pp, &auction_pp)
+            .unwrap();
+        assert_eq!(auction_house.accounts.get(&0).unwrap().balance, 0);
+        assert_eq!(users[0].public_summary.balance, 0);
+
+        for uid in 1..3u32 {
+            let bid = uid * 100;
+            let user = users.get_mut(uid as usize).unwrap();
+            let (proposal, opening) = user
+                .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(bid))
+                .unwrap();
+            auction_house
+                .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                .unwrap();
+            user.confirm_bid(&house_pp, &auction_pp, auction_id, bid, &proposal, &opening)
+                .unwrap();
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, uid, bid, &opening)
+                .unwrap();
+            user.confirm_bid_self_open(&house_pp, &auction_pp).unwrap();
+        }
+
+        let (price, winners) = auction_house
+            .complete_kplusone_price_auction(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!(price, 100);
+        assert_eq!(winners, vec![2]);
+        assert_eq!(auction_house.accounts.get(&0).unwrap().balance, 1000);
+        users[0]
+            .confirm_seller_bond_return(&house_pp, &auction_pp)
+            .unwrap();
+        assert_eq!(users[0].public_summary.balance, 1000);
+    }
+
+    #[test]
+    fn bundle_auction_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, mut auction_pp, mut auction_house, mut users) =
+            setup_seller_bond_test(&mut rng, 0);
+        let bundle = vec![
+            NftToken {
+                contract: [1u8; 20],
+                token_id: 7,
+            },
+            NftToken {
+                contract: [2u8; 20],
+                token_id: 7,
+            },
+        ];
+        auction_pp.bundle = bundle.clone();
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+
+        for uid in 1..3u32 {
+            let bid = uid * 100;
+            let user = users.get_mut(uid as usize).unwrap();
+            let (proposal, opening) = user
+                .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(bid))
+                .unwrap();
+            auction_house
+                .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                .unwrap();
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, uid, bid, &opening)
+                .unwrap();
+        }
+
+        // Bundle goes to a single winner as a whole
+        let err = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 0)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::BundleSplit)
+        ));
+        assert_eq!(auction_house.accounts.get(&2).unwrap().balance, 10000);
+
+        let report = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!(report.winners, vec![2]);
+        assert_eq!(report.bundle_recipient, Some(2));
+        assert_eq!(auction_house.accounts.get(&2).unwrap().balance, 9900);
+
+        // Unsold bundle returns to the seller
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+        let report = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!(report.outcome, CompletionOutcome::NoBids);
+        assert_eq!(report.bundle_recipient, Some(0));
+    }
+
+    #[test]
+    fn seller_bond_forfeited_on_cancel_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, auction_pp, mut auction_house, mut users) =
+            setup_seller_bond_test(&mut rng, 1000);
+
+        let bond_proof = users[0]
+            .propose_seller_bond(&mut rng, &house_pp, &auction_pp)
+            .unwrap();
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, Some(&bond_proof), None)
+            .unwrap();
+        users[0]
+            .confirm_seller_bond(&house_pp, &auction_pp)
+            .unwrap();
+
+        let (proposal, opening) = users[1]
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(100))
+            .unwrap();
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, 1, &proposal)
+            .unwrap();
+        users[1]
+            .confirm_bid(&house_pp, &auction_pp, auction_id, 100, &proposal, &opening)
+            .unwrap();
+        assert_eq!(auction_house.accounts.get(&1).unwrap().balance, 9500);
+
+        auction_house
+            .cancel_auction(&house_pp, &auction_pp, auction_id)
+            .unwrap();
+        users[1]
+            .confirm_auction_cancel(&house_pp, &auction_pp, auction_id)
+            .unwrap();
+
+        // Bidder is made whole, seller bond is not returned
+        assert_eq!(auction_house.accounts.get(&0).unwrap().balance, 0);
+        assert_eq!(auction_house.accounts.get(&1).unwrap().balance, 10000);
+        assert_eq!(
+            auction_house.accounts.get(&1).unwrap().comm_active_bids,
+            G::zero()
+        );
+        assert_eq!(users[1].public_summary.balance, 10000);
+        assert_eq!(users[1].sum_active_bids, 0);
+        assert!(auction_house
+            .cancel_auction(&house_pp, &auction_pp, auction_id)
+            .is_err());
+    }
+
+    #[test]
+    fn balance_conservation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, auction_pp, mut auction_house, mut users) =
+            setup_seller_bond_test(&mut rng, 1000);
+        assert!(auction_house.check_invariants().is_ok());
+
+        let bond_proof = users[0]
+            .propose_seller_bond(&mut rng, &house_pp, &auction_pp)
+            .unwrap();
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, Some(&bond_proof), None)
+            .unwrap();
+        let (proposal, _) = users[1]
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(100))
+            .unwrap();
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, 1, &proposal)
+            .unwrap();
+        assert!(auction_house.check_invariants().is_ok());
+
+        // Forfeited bond stays with the house
+        auction_house
+            .cancel_auction(&house_pp, &auction_pp, auction_id)
+            .unwrap();
+        assert!(auction_house.check_invariants().is_ok());
+
+        auction_house.accounts.get_mut(&2).unwrap().balance += 1;
+        assert_eq!(
+            auction_house.check_invariants(),
+            Err(InvariantViolation::Conservation {
+                held: 21001,
+                deposited: 21000,
+                withdrawn: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn self_open_reward_overflow_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, auction_pp, mut auction_house, users) = setup_seller_bond_test(&mut rng, 0);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+        let (proposal, opening) = users[1]
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(100))
+            .unwrap();
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, 1, &proposal)
+            .unwrap();
+        let balance = auction_house.accounts[&1].balance;
+
+        // Reward that does not fit the balance is rejected before the opening is accepted
+        auction_house.accounts.get_mut(&1).unwrap().balance = u32::MAX;
+        let err = auction_house
+            .account_self_open(&house_pp, &auction_pp, auction_id, 1, 100, &opening)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::BalanceOverflow)
+        ));
+        assert_eq!(auction_house.accounts[&1].balance, u32::MAX);
+
+        auction_house.accounts.get_mut(&1).unwrap().balance = balance;
+        auction_house
+            .account_self_open(&house_pp, &auction_pp, auction_id, 1, 100, &opening)
+            .unwrap();
+        assert_eq!(auction_house.accounts[&1].balance, 10000);
+    }
+
+    // Insecure scheme that reveals the bid and opening in the commitment, used to exercise the
+    // house over a sealed bid scheme other than LazyTC
+    pub struct MockSealedBid;
+
+    #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+    pub struct MockComm {
+        ped_comm: G,
+        m: Vec<u8>,
+        ped_opening: <G as ProjectiveCurve>::ScalarField,
+    }
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct MockOpening {
+        m: Option<Vec<u8>>,
+        ped_opening: <G as ProjectiveCurve>::ScalarField,
+    }
+
+    impl SealedBidScheme<G, TestRsaParams> for MockSealedBid {
+        type Comm = MockComm;
+        type Opening = MockOpening;
+
+        fn commit<R: CryptoRng + Rng>(
+            rng: &mut R,
+            _time_pp: &TimeParams<TestRsaParams>,
+            ped_pp: &PedersenParams<G>,
+            m: &[u8],
+        ) -> Result<(Self::Comm, Self::Opening), Error> {
+            let (ped_comm, ped_opening) = PedersenComm::<G>::commit(rng, ped_pp, m)?;
+            Ok((
+                MockComm {
+                    ped_comm,
+                    m: m.to_vec(),
+                    ped_opening,
+                },
+                MockOpening {
+                    m: Some(m.to_vec()),
+                    ped_opening,
+                },
+            ))
+        }
+
+        fn check_comm(
+            _time_pp: &TimeParams<TestRsaParams>,
+            _comm: &Self::Comm,
+        ) -> Result<bool, Error> {
+            Ok(true)
+        }
+
+        fn force_open(
+            _time_pp: &TimeParams<TestRsaParams>,
+            _ped_pp: &PedersenParams<G>,
+            comm: &Self::Comm,
+        ) -> Result<(Option<Vec<u8>>, Self::Opening), Error> {
+            Ok((
+                Some(comm.m.clone()),
+                MockOpening {
+                    m: Some(comm.m.clone()),
+                    ped_opening: comm.ped_opening,
+                },
+            ))
+        }
+
+        fn ver_open(
+            _time_pp: &TimeParams<TestRsaParams>,
+            ped_pp: &PedersenParams<G>,
+            comm: &Self::Comm,
+            m: &Option<Vec<u8>>,
+            opening: &Self::Opening,
+        ) -> Result<bool, Error> {
+            match (m, &opening.m) {
+                (Some(m), Some(opening_m)) => Ok(m == opening_m
+                    && *m == comm.m
+                    && PedersenComm::<G>::ver_open(
+                        ped_pp,
+                        &comm.ped_comm,
+                        m,
+                        &opening.ped_opening,
+                    )?),
+                _ => Ok(false),
+            }
+        }
+
+        fn ped_comm(comm: &Self::Comm) -> G {
+            comm.ped_comm
+        }
+
+        fn ped_opening(opening: &Self::Opening) -> <G as ProjectiveCurve>::ScalarField {
+            opening.ped_opening
+        }
+
+        fn opened_message(opening: &Self::Opening) -> Option<Vec<u8>> {
+            opening.m.clone()
+        }
+
+        fn write_comm<W: Write>(comm: &Self::Comm, mut writer: W) -> Result<(), Error> {
+            comm.ped_comm.into_affine().serialize(&mut writer)?;
+            comm.m.serialize(&mut writer)?;
+            comm.ped_opening.serialize(&mut writer)?;
+            Ok(())
+        }
+
+        fn read_comm<R: Read>(mut reader: R) -> Result<Self::Comm, Error> {
+            Ok(MockComm {
+                ped_comm: <G as ProjectiveCurve>::Affine::deserialize(&mut reader)?
+                    .into_projective(),
+                m: Vec::<u8>::deserialize(&mut reader)?,
+                ped_opening: <G as ProjectiveCurve>::ScalarField::deserialize(&mut reader)?,
+            })
+        }
+
+        fn write_opening<W: Write>(opening: &Self::Opening, mut writer: W) -> Result<(), Error> {
+            opening.m.is_some().serialize(&mut writer)?;
+            opening
+                .m
+                .clone()
+                .unwrap_or_default()
+                .serialize(&mut writer)?;
+            opening.ped_opening.serialize(&mut writer)?;
+            Ok(())
+        }
+
+        fn read_opening<R: Read>(mut reader: R) -> Result<Self::Opening, Error> {
+            let opened = bool::deserialize(&mut reader)?;
+            let m = Vec::<u8>::deserialize(&mut reader)?;
+            Ok(MockOpening {
+                m: if opened { Some(m) } else { None },
+                ped_opening: <G as ProjectiveCurve>::ScalarField::deserialize(&mut reader)?,
+            })
+        }
+    }
+
+    pub type MockAuctionHouse = AuctionHouse<
+        G,
+        TestPoEParams,
+        TestRsaParams,
+        Keccak256,
+        PocklingtonHash<TestPocklingtonParams, Keccak256>,
+        MockSealedBid,
+    >;
+
+    // Token balances outside the house, recording every transfer as a signed amount
+    #[derive(Clone, PartialEq, Eq, Debug, Default)]
+    pub struct MockCoin {
+        balances: HashMap<u32, u32>,
+        transfers: Vec<(u32, i64)>, // (user_id, amount credited to the user)
+        fail_credit: bool,
+    }
+
+    impl CoinBackend for MockCoin {
+        fn debit(&mut self, user_id: u32, amt: u32) -> Result<(), Error> {
+            let balance = self.balances.entry(user_id).or_insert(0);
+            *balance = sub_balance(*balance, amt)?;
+            self.transfers.push((user_id, -(amt as i64)));
+            Ok(())
+        }
+
+        fn credit(&mut self, user_id: u32, amt: u32) -> Result<(), Error> {
+            if self.fail_credit {
+                return Err(Box::new(AuctionError::InvalidID));
+            }
+            let balance = self.balances.entry(user_id).or_insert(0);
+            *balance = add_balance(*balance, amt)?;
+            self.transfers.push((user_id, amt as i64));
+            Ok(())
+        }
+    }
+
+    pub type MockCoinAuctionHouse = AuctionHouse<
+        G,
+        TestPoEParams,
+        TestRsaParams,
+        Keccak256,
+        PocklingtonHash<TestPocklingtonParams, Keccak256>,
+        MockSealedBid,
+        MockCoin,
+    >;
+
+    pub type MockUser = AccountPrivateState<
+        G,
+        TestPoEParams,
+        TestRsaParams,
+        Keccak256,
+        PocklingtonHash<TestPocklingtonParams, Keccak256>,
+        MockSealedBid,
+    >;
+
+    #[test]
+    fn mock_sealed_bid_auction_house_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let mut users = (0..4)
+            .map(|_| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                user
+            })
+            .collect::<Vec<MockUser>>();
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+
+        let openings = users
+            .iter_mut()
+            .enumerate()
+            .map(|(uid, user)| {
+                let bid = (uid as u32 + 1) * 100;
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(bid))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid as u32, &proposal)
+                    .unwrap();
+                user.confirm_bid(&house_pp, &auction_pp, auction_id, bid, &proposal, &opening)
+                    .unwrap();
+                opening
+            })
+            .collect::<Vec<_>>();
+
+        // uid 0 is force opened by uid 3, all other bids are self opened
+        for (uid, (user, opening)) in users.iter_mut().zip(openings.iter()).enumerate().skip(1) {
+            let bid = (uid as u32 + 1) * 100;
+            // Opening with the wrong bid is rejected
+            assert!(auction_house
+                .account_self_open(
+                    &house_pp,
+                    &auction_pp,
+                    auction_id,
+                    uid as u32,
+                    bid + 1,
+                    opening,
+                )
+                .is_err());
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, uid as u32, bid, opening)
+                .unwrap();
+            user.confirm_bid_self_open(&house_pp, &auction_pp).unwrap();
+        }
+        let (bid, force_opening) = auction_house
+            .active_auctions
+            .get(&auction_id)
+            .unwrap()
+            .0
+            .force_open_bid(&auction_pp.auction_pp, 0)
+            .unwrap();
+        assert_eq!(bid, Some(100));
+        auction_house
+            .account_force_open(
+                &house_pp,
+                &auction_pp,
+                auction_id,
+                3,
+                0,
+                bid,
+                &force_opening,
+            )
+            .unwrap();
+        users[3]
+            .confirm_bid_force_open(&house_pp, &auction_pp)
+            .unwrap();
+
+        let (price, winners) = auction_house
+            .complete_kplusone_price_auction(&house_pp, &auction_pp, auction_id, 2)
+            .unwrap();
+        assert_eq!(price, 200);
+        assert_eq!(winners.len(), 2);
+        assert!(winners.contains(&2));
+        assert!(winners.contains(&3));
+
+        let expected_balances = [9500, 10000, 9800, 10100];
+        for uid in 0..4u32 {
+            let user = users.get_mut(uid as usize).unwrap();
+            if winners.contains(&uid) {
+                user.confirm_auction_win(&house_pp, &auction_pp, auction_id, price)
+                    .unwrap();
+            } else {
+                user.confirm_auction_loss(&house_pp, &auction_pp, auction_id)
+                    .unwrap();
+            }
+            let summary = auction_house.accounts.get(&uid).unwrap();
+            assert_eq!(summary.balance, expected_balances[uid as usize]);
+            assert_eq!(user.public_summary, *summary);
+            assert_eq!(user.sum_active_bids, 0);
+        }
+    }
+
+    #[test]
+    fn forfeit_corrupt_bid_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        let bids = [300, 900, 500, 700];
+        let openings = bids
+            .iter()
+            .enumerate()
+            .map(|(i, bid)| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                let (mut proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(*bid))
+                    .unwrap();
+                // Trailing bytes leave the Pedersen value, and so the range proofs, unchanged
+                if i == 1 {
+                    proposal.comm_bid.m.extend_from_slice(&[0u8; 4]);
+                }
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                (uid, *bid, opening)
+            })
+            .collect::<Vec<_>>();
+        for (uid, bid, opening) in openings.iter().filter(|(_, bid, _)| *bid != 900) {
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, *uid, *bid, opening)
+                .unwrap();
+        }
+
+        let corrupt_uid = openings[1].0;
+        let opener_uid = openings[0].0;
+        let bid_id = {
+            let (_, bid_map) = auction_house.active_auctions.get(&auction_id).unwrap();
+            bid_map[&corrupt_uid]
+        };
+        let (bid, force_opening) = {
+            let (auction, _) = auction_house.active_auctions.get(&auction_id).unwrap();
+            auction
+                .force_open_bid(&auction_pp.auction_pp, bid_id as usize)
+                .unwrap()
+        };
+        assert_eq!(bid, None);
+        // Claiming the truncated bid is rejected
+        assert!(auction_house
+            .account_force_open(
+                &house_pp,
+                &auction_pp,
+                auction_id,
+                opener_uid,
+                bid_id,
+                Some(900),
+                &force_opening,
+            )
+            .is_err());
+        auction_house
+            .account_force_open(
+                &house_pp,
+                &auction_pp,
+                auction_id,
+                opener_uid,
+                bid_id,
+                bid,
+                &force_opening,
+            )
+            .unwrap();
+        let states = auction_house
+            .iter_bids(auction_id)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert!(states.contains(&(corrupt_uid, BidState::Forfeited)));
+
+        let report = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!(report.price, 500);
+        assert_eq!(report.winners, vec![openings[3].0]);
+        assert!(report.losers.contains(&corrupt_uid));
+        assert!(report.gaps.contains(&(corrupt_uid, ClearingGap::Unopened)));
+        // Escrowed rewards go to the opener and the house, nothing is charged for the bid
+        assert_eq!(
+            auction_house.accounts.get(&corrupt_uid).unwrap().balance,
+            10000 - 500
+        );
+    }
+
+    #[test]
+    fn iter_bids_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+        assert!(auction_house.iter_bids(auction_id + 1).is_err());
+
+        // Bids are placed in descending uid order to check the iterator sorts by uid
+        let uids = (0..4)
+            .map(|_| {
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                uid
+            })
+            .collect::<Vec<u32>>();
+        let mut bids = uids
+            .into_iter()
+            .rev()
+            .map(|uid| {
+                let mut user = MockUser::new();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                let bid = (uid + 1) * 100;
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(bid))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                (uid, bid, proposal.comm_bid, opening)
+            })
+            .collect::<Vec<_>>();
+        bids.reverse();
+
+        let states = auction_house
+            .iter_bids(auction_id)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            bids.iter()
+                .map(|(uid, _, comm, _)| (*uid, BidState::Sealed(comm)))
+                .collect::<Vec<_>>()
+        );
+
+        // uids 1 and 2 self open, uid 0 is force opened by uid 3, uid 3 stays sealed
+        for (uid, bid, _, opening) in bids[1..3].iter() {
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, *uid, *bid, opening)
+                .unwrap();
+        }
+        let bid_id = auction_house.active_auctions.get(&auction_id).unwrap().1[&bids[0].0];
+        let (bid, force_opening) = auction_house
+            .active_auctions
+            .get(&auction_id)
+            .unwrap()
+            .0
+            .force_open_bid(&auction_pp.auction_pp, bid_id as usize)
+            .unwrap();
+        auction_house
+            .account_force_open(
+                &house_pp,
+                &auction_pp,
+                auction_id,
+                bids[3].0,
+                bid_id,
+                bid,
+                &force_opening,
+            )
+            .unwrap();
+
+        let states = auction_house
+            .iter_bids(auction_id)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            vec![
+                (bids[0].0, BidState::ForceOpened(bids[0].1)),
+                (bids[1].0, BidState::SelfOpened(bids[1].1)),
+                (bids[2].0, BidState::SelfOpened(bids[2].1)),
+                (bids[3].0, BidState::Sealed(&bids[3].2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn per_auction_rewards_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let (opener_id, _) = auction_house.new_account(&house_pp);
+        let rewards = AuctionRewards {
+            reward_self_open: 50,
+            reward_force_open: 70,
+        };
+        let default_id = auction_house
+            .new_auction(&house_pp, &auction_pp, opener_id, None, None)
+            .unwrap();
+        let override_id = auction_house
+            .new_auction(&house_pp, &auction_pp, opener_id, None, Some(rewards))
+            .unwrap();
+        assert_eq!(
+            auction_house.auction_rewards(default_id).unwrap(),
+            auction_pp.rewards()
+        );
+        assert_eq!(auction_house.auction_rewards(override_id).unwrap(), rewards);
+
+        // One bidder per auction, neither self opens and both are force opened
+        for auction_id in [default_id, override_id] {
+            let bidder_pp =
+                auction_pp.with_rewards(&auction_house.auction_rewards(auction_id).unwrap());
+            let mut user = MockUser::new();
+            let (uid, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .account_deposit(&house_pp, uid, 10000)
+                .unwrap();
+            user.confirm_deposit(&house_pp, 10000).unwrap();
+            let (proposal, opening) = user
+                .propose_bid(&mut rng, &house_pp, &bidder_pp, bid_value(100))
+                .unwrap();
+            auction_house
+                .account_bid(&house_pp, &bidder_pp, auction_id, uid, &proposal)
+                .unwrap();
+            user.confirm_bid(&house_pp, &bidder_pp, auction_id, 100, &proposal, &opening)
+                .unwrap();
+
+            let opener_balance = auction_house.accounts.get(&opener_id).unwrap().balance;
+            let bid_id = auction_house.active_auctions.get(&auction_id).unwrap().1[&uid];
+            let (bid, force_opening) = auction_house
+                .active_auctions
+                .get(&auction_id)
+                .unwrap()
+                .0
+                .force_open_bid(&bidder_pp.auction_pp, bid_id as usize)
+                .unwrap();
+            auction_house
+                .account_force_open(
+                    &house_pp,
+                    &bidder_pp,
+                    auction_id,
+                    opener_id,
+                    bid_id,
+                    bid,
+                    &force_opening,
+                )
+                .unwrap();
+
+            let debit = bidder_pp.reward_self_open + bidder_pp.reward_force_open;
+            assert_eq!(
+                auction_house.accounts.get(&uid).unwrap().balance,
+                10000 - debit
+            );
+            assert_eq!(user.public_summary.balance, 10000 - debit);
+            assert_eq!(
+                auction_house.accounts.get(&opener_id).unwrap().balance,
+                opener_balance + bidder_pp.reward_force_open
+            );
+        }
+        assert_eq!(
+            auction_house.accounts.get(&opener_id).unwrap().balance,
+            auction_pp.reward_force_open + rewards.reward_force_open
+        );
+    }
+
+    #[test]
+    fn balance_history_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut user = MockUser::new();
+        user.confirm_deposit(&house_pp, 10000).unwrap();
+        for (auction_id, bid) in [(0, 1000), (1, 2000)] {
+            let (proposal, opening) = user
+                .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(bid))
+                .unwrap();
+            user.confirm_bid(&house_pp, &auction_pp, auction_id, bid, &proposal, &opening)
+                .unwrap();
+        }
+        user.confirm_bid_self_open(&house_pp, &auction_pp).unwrap();
+        user.confirm_withdrawal(&house_pp, 500).unwrap();
+        user.confirm_bid_force_open(&house_pp, &auction_pp).unwrap();
+        user.confirm_auction_win(&house_pp, &auction_pp, 0, 800)
+            .unwrap();
+        user.confirm_auction_loss(&house_pp, &auction_pp, 1)
+            .unwrap();
+        // Rejected operations are not recorded
+        assert!(user.confirm_withdrawal(&house_pp, 100000).is_err());
+
+        let history = user.balance_history();
+        assert_eq!(
+            history.iter().map(|e| e.kind).collect::<Vec<_>>(),
+            vec![
+                BalanceEventKind::Deposit,
+                BalanceEventKind::Bid,
+                BalanceEventKind::Bid,
+                BalanceEventKind::OpenReward,
+                BalanceEventKind::Withdrawal,
+                BalanceEventKind::OpenReward,
+                BalanceEventKind::Win,
+                BalanceEventKind::Loss,
+            ]
+        );
+        assert!(history.iter().enumerate().all(|(i, e)| e.seq == i as u64));
+        assert_eq!(
+            user.public_summary.balance,
+            10000 - 1000 + 500 - 500 + 300 - 800
+        );
+        assert_eq!(
+            history.iter().map(|e| e.amount).sum::<i64>(),
+            user.public_summary.balance as i64
+        );
+    }
+
+    #[test]
+    fn completion_report_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        let bidder_ids = (0..5)
+            .map(|i| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                let bid = (i + 1) * 100;
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(bid))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                // Lowest bid is never opened
+                if i > 0 {
+                    auction_house
+                        .account_self_open(&house_pp, &auction_pp, auction_id, uid, bid, &opening)
+                        .unwrap();
+                }
+                uid
+            })
+            .collect::<Vec<u32>>();
+
+        let report = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 2)
+            .unwrap();
+        assert_eq!(report.price, 300);
+        assert_eq!(
+            report.seller_proceeds,
+            report.price * report.winners.len() as u32
+        );
+
+        let mut winners = report.winners.clone();
+        winners.sort_unstable();
+        assert_eq!(winners, bidder_ids[3..].to_vec());
+        assert_eq!(report.losers, bidder_ids[..3].to_vec());
+        let mut all = [report.winners.clone(), report.losers.clone()].concat();
+        all.sort_unstable();
+        assert_eq!(all, bidder_ids);
+    }
+
+    #[test]
+    fn completion_report_gaps_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        // Second bidder never opens
+        let bids = [250, 900, 400, 700];
+        let bidder_ids = bids
+            .iter()
+            .enumerate()
+            .map(|(i, bid)| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(*bid))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                if i != 1 {
+                    auction_house
+                        .account_self_open(&house_pp, &auction_pp, auction_id, uid, *bid, &opening)
+                        .unwrap();
+                }
+                uid
+            })
+            .collect::<Vec<u32>>();
+
+        let report = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!(report.price, 400);
+        assert_eq!(report.winners, vec![bidder_ids[3]]);
+        assert_eq!(
+            report.gaps,
+            vec![
+                (bidder_ids[0], ClearingGap::Opened(150)),
+                (bidder_ids[1], ClearingGap::Unopened),
+                (bidder_ids[2], ClearingGap::Opened(0)),
+                (bidder_ids[3], ClearingGap::Opened(-300)),
+            ]
+        );
+        for (uid, gap) in report.gaps.iter() {
+            if report.winners.contains(uid) {
+                assert!(matches!(gap, ClearingGap::Opened(g) if *g <= 0));
+            }
+        }
+    }
+
+    #[test]
+    fn deposit_and_bid_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let (seller_id, _) = auction_house.new_account(&house_pp);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+            .unwrap();
+
+        // Bid proposed against a larger deposit than is made is rejected
+        let (uid, _) = auction_house.new_account(&house_pp);
+        let mut user = MockUser::new();
+        user.confirm_deposit(&house_pp, 2000).unwrap();
+        let (proposal, _) = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(1500))
+            .unwrap();
+        assert!(auction_house
+            .deposit_and_bid(&house_pp, &auction_pp, auction_id, uid, 1000, &proposal)
+            .is_err());
+        assert_eq!(auction_house.accounts.get(&uid).unwrap().balance, 0);
+        assert_eq!(
+            auction_house.accounts.get(&uid).unwrap().comm_active_bids,
+            G::zero()
+        );
+
+        // Bid on unknown auction is rejected
+        assert!(auction_house
+            .deposit_and_bid(&house_pp, &auction_pp, auction_id + 1, uid, 2000, &proposal)
+            .is_err());
+        assert_eq!(auction_house.accounts.get(&uid).unwrap().balance, 0);
+
+        let receipt = auction_house
+            .deposit_and_bid(&house_pp, &auction_pp, auction_id, uid, 2000, &proposal)
+            .unwrap();
+        assert_eq!(receipt.auction_id, auction_id);
+        assert_eq!(receipt.bid_id, 0);
+        assert_eq!(receipt.balance, 1500);
+        assert_eq!(auction_house.accounts.get(&uid).unwrap().balance, 1500);
+    }
+
+    #[test]
+    fn dry_run_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let (seller_id, _) = auction_house.new_account(&house_pp);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+            .unwrap();
+        let (uid, _) = auction_house.new_account(&house_pp);
+        auction_house.account_deposit(&house_pp, uid, 2000).unwrap();
+        let mut user = MockUser::new();
+        user.confirm_deposit(&house_pp, 2000).unwrap();
+
+        let (proposal, _) = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(1000))
+            .unwrap();
+        assert!(auction_house
+            .account_bid_dry_run(&house_pp, &auction_pp, auction_id, uid, &proposal)
+            .is_ok());
+        assert!(auction_house
+            .account_bid_dry_run(&house_pp, &auction_pp, auction_id + 1, uid, &proposal)
+            .is_err());
+        let (auction, bid_map) = auction_house.active_auctions.get(&auction_id).unwrap();
+        assert_eq!(auction.bid_comms_i.len(), 0);
+        assert!(bid_map.is_empty());
+        assert_eq!(auction_house.accounts.get(&uid).unwrap().balance, 2000);
+
+        let withdrawal_proof = user.propose_withdrawal(&mut rng, &house_pp, 500).unwrap();
+        assert!(auction_house
+            .account_withdrawal_dry_run(&house_pp, uid, 500, &withdrawal_proof)
+            .is_ok());
+        assert!(auction_house
+            .account_withdrawal_dry_run(&house_pp, uid, 2500, &withdrawal_proof)
+            .is_err());
+        assert_eq!(auction_house.accounts.get(&uid).unwrap().balance, 2000);
+
+        // Real bid after a successful dry run is accepted
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+            .unwrap();
+        let (auction, _) = auction_house.active_auctions.get(&auction_id).unwrap();
+        assert_eq!(auction.bid_comms_i.len(), 1);
+    }
+
+    #[test]
+    fn verify_withdrawal_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, _, auction_house, mut users) = setup_seller_bond_test(&mut rng, 0);
+
+        let proof = users[1]
+            .propose_withdrawal(&mut rng, &house_pp, 4000)
+            .unwrap();
+        assert!(auction_house
+            .verify_withdrawal(&house_pp, 1, 4000, &proof)
+            .unwrap());
+        assert!(!auction_house
+            .verify_withdrawal(&house_pp, 1, 5000, &proof)
+            .unwrap());
+        assert!(!auction_house
+            .verify_withdrawal(&house_pp, 1, 20000, &proof)
+            .unwrap());
+
+        // Proof from an inflated local balance
+        users[1].confirm_deposit(&house_pp, 5000).unwrap();
+        let over_proof = users[1]
+            .propose_withdrawal(&mut rng, &house_pp, 12000)
+            .unwrap();
+        assert!(!auction_house
+            .verify_withdrawal(&house_pp, 1, 12000, &over_proof)
+            .unwrap());
+        assert!(auction_house
+            .verify_withdrawal(&house_pp, 3, 4000, &proof)
+            .is_err());
+
+        assert_eq!(auction_house.accounts.get(&1).unwrap().balance, 10000);
+        assert!(auction_house.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn unopened_bidders_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        let bids = [250, 900, 400, 700, 300];
+        let mut openings = bids
+            .iter()
+            .map(|bid| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(*bid))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                (uid, *bid, opening)
+            })
+            .collect::<Vec<_>>();
+        let all_uids = openings.iter().map(|(uid, _, _)| *uid).collect::<Vec<_>>();
+        assert_eq!(
+            auction_house.unopened_bidders(auction_id).unwrap(),
+            all_uids
+        );
+
+        // Second and fourth bidders never self open
+        let sealed = vec![openings.remove(3), openings.remove(1)];
+        for (uid, bid, opening) in openings.iter() {
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, *uid, *bid, opening)
+                .unwrap();
+        }
+        assert_eq!(
+            auction_house.unopened_bidders(auction_id).unwrap(),
+            vec![sealed[1].0, sealed[0].0]
+        );
+        assert!(auction_house.unopened_bidders(auction_id + 1).is_err());
+    }
+
+    #[test]
+    fn audit_auction_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        let openings = [250, 900, 400]
+            .iter()
+            .map(|bid| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(*bid))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                (uid, *bid, opening)
+            })
+            .collect::<Vec<_>>();
+        // Last bidder stays sealed
+        for (uid, bid, opening) in openings[..2].iter() {
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, *uid, *bid, opening)
+                .unwrap();
+        }
+        let audit = auction_house
+            .audit_auction(&house_pp, &auction_pp, auction_id)
+            .unwrap();
+        assert_eq!(audit.len(), 3);
+        assert!(audit.values().all(|result| result.passed()));
+        assert_eq!(audit[&openings[0].0].opening, Some(true));
+        assert_eq!(audit[&openings[2].0].opening, None);
+
+        // Swap in the balance proof of another bidder
+        let (bid_0, bid_1) = {
+            let (_, bid_map) = auction_house.active_auctions.get(&auction_id).unwrap();
+            (bid_map[&openings[0].0], bid_map[&openings[1].0])
+        };
+        let records = auction_house.bid_records.get_mut(&auction_id).unwrap();
+        let proof = records[&bid_0].range_proof_balance.clone();
+        records.get_mut(&bid_1).unwrap().range_proof_balance = proof;
+        let audit = auction_house
+            .audit_auction(&house_pp, &auction_pp, auction_id)
+            .unwrap();
+        let failing = audit
+            .iter()
+            .filter(|(_, result)| !result.passed())
+            .map(|(uid, _)| *uid)
+            .collect::<Vec<_>>();
+        assert_eq!(failing, vec![openings[1].0]);
+        assert_eq!(
+            audit[&openings[1].0],
+            AuditResult {
+                range_proof_bid: true,
+                range_proof_balance: false,
+                opening: Some(true),
+            }
+        );
+        assert!(auction_house
+            .audit_auction(&house_pp, &auction_pp, auction_id + 1)
+            .is_err());
+
+        // Bid without recorded proofs fails the audit instead of being skipped
+        auction_house
+            .bid_records
+            .get_mut(&auction_id)
+            .unwrap()
+            .remove(&bid_1);
+        let err = auction_house
+            .audit_auction(&house_pp, &auction_pp, auction_id)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::MissingBidRecord(bid_id)) if *bid_id == bid_1
+        ));
+        auction_house.bid_records.remove(&auction_id);
+        assert!(auction_house
+            .audit_auction(&house_pp, &auction_pp, auction_id)
+            .is_err());
+    }
+
+    #[test]
+    fn bid_commitment_mismatch_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let (seller_id, _) = auction_house.new_account(&house_pp);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+            .unwrap();
+        let (uid, _) = auction_house.new_account(&house_pp);
+        auction_house.account_deposit(&house_pp, uid, 5000).unwrap();
+        let mut user = MockUser::new();
+        user.confirm_deposit(&house_pp, 5000).unwrap();
+
+        // Range proof of a small bid attached to the commitment of a larger one
+        let (small, _) = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(100))
+            .unwrap();
+        let (large, _) = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(4000))
+            .unwrap();
+        let mismatched = BidProposal {
+            comm_bid: large.comm_bid.clone(),
+            comm_range_bid: small.comm_range_bid,
+            range_proof_bid: small.range_proof_bid.clone(),
+            range_proof_balance: large.range_proof_balance.clone(),
+        };
+        let err = auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, uid, &mismatched)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::BidCommitmentMismatch)
+        ));
+        // Honest value commitment with a proof that does not verify for it
+        let invalid = BidProposal {
+            comm_bid: large.comm_bid.clone(),
+            comm_range_bid: large.comm_range_bid,
+            range_proof_bid: small.range_proof_bid.clone(),
+            range_proof_balance: large.range_proof_balance.clone(),
+        };
+        let err = auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, uid, &invalid)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidBid)
+        ));
+        let (auction, _) = auction_house.active_auctions.get(&auction_id).unwrap();
+        assert_eq!(auction.bid_comms_i.len(), 0);
+
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, uid, &large)
+            .unwrap();
+    }
+
+    #[test]
+    fn bound_bid_proof_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, auction_pp, mut auction_house, users) = setup_seller_bond_test(&mut rng, 0);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+
+        let (bound, _) = users[1]
+            .propose_bid_bound(&mut rng, &house_pp, &auction_pp, bid_value(100))
+            .unwrap();
+        let (other, _) = users[1]
+            .propose_bid_bound(&mut rng, &house_pp, &auction_pp, bid_value(4000))
+            .unwrap();
+
+        // Proof transplanted onto the same Pedersen point time-locking a different bid. The
+        // value commitment matches, but the binding to the timed layer does not verify.
+        let transplanted = BidProposal {
+            comm_bid: TCComm {
+                ped_comm: bound.comm_bid.ped_comm,
+                tc_comm: other.comm_bid.tc_comm.clone(),
+            },
+            comm_range_bid: bound.comm_range_bid,
+            range_proof_bid: bound.range_proof_bid.clone(),
+            range_proof_balance: bound.range_proof_balance.clone(),
+        };
+        let err = auction_house
+            .account_bid_bound(&house_pp, &auction_pp, auction_id, 1, &transplanted)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidBid)
+        ));
+        // Proof of another bid's value commitment
+        let mismatched = BidProposal {
+            comm_bid: other.comm_bid.clone(),
+            comm_range_bid: bound.comm_range_bid,
+            range_proof_bid: bound.range_proof_bid.clone(),
+            range_proof_balance: other.range_proof_balance.clone(),
+        };
+        let err = auction_house
+            .account_bid_bound(&house_pp, &auction_pp, auction_id, 1, &mismatched)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::BidCommitmentMismatch)
+        ));
+        // Bound proofs do not verify as plain ones
+        assert!(auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, 1, &bound)
+            .is_err());
+        let (auction, _) = auction_house.active_auctions.get(&auction_id).unwrap();
+        assert_eq!(auction.bid_comms_i.len(), 0);
+
+        auction_house
+            .account_bid_bound(&house_pp, &auction_pp, auction_id, 1, &bound)
+            .unwrap();
+    }
+
+    #[test]
+    fn bid_proposal_wire_format_test() {
+        type Proposal = BidProposal<G, TCComm<G, TestRsaParams>>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, auction_pp, mut auction_house, users) = setup_seller_bond_test(&mut rng, 0);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+        let (proposal, _) = users[1]
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(100))
+            .unwrap();
+
+        let mut bytes = Vec::new();
+        proposal.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), proposal.serialized_size());
+        let relayed = Proposal::deserialize(&bytes[..]).unwrap();
+        assert_eq!(relayed.comm_bid, proposal.comm_bid);
+        assert_eq!(relayed.comm_range_bid, proposal.comm_range_bid);
+        assert_eq!(relayed.range_proof_bid, proposal.range_proof_bid);
+        assert_eq!(relayed.range_proof_balance, proposal.range_proof_balance);
+        assert!(Proposal::deserialize(&bytes[..bytes.len() - 1]).is_err());
+
+        // Inner product argument with a missing round
+        let mut truncated_proof = proposal.range_proof_bid.clone();
+        truncated_proof.comm_ipa.pop();
+        let truncated = Proposal {
+            comm_bid: proposal.comm_bid.clone(),
+            comm_range_bid: proposal.comm_range_bid,
+            range_proof_bid: truncated_proof,
+            range_proof_balance: proposal.range_proof_balance.clone(),
+        };
+        let mut bytes = Vec::new();
+        truncated.serialize(&mut bytes).unwrap();
+        assert!(matches!(
+            Proposal::deserialize(&bytes[..]),
+            Err(SerializationError::InvalidData)
+        ));
+
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, 1, &relayed)
+            .unwrap();
+    }
+
+    #[test]
+    fn save_load_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        // Second bidder is left to force open
+        let bids = [250, 900, 400, 700];
+        let bidder_ids = bids
+            .iter()
+            .enumerate()
+            .map(|(i, bid)| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(*bid))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                if i != 1 {
+                    auction_house
+                        .account_self_open(&house_pp, &auction_pp, auction_id, uid, *bid, &opening)
+                        .unwrap();
+                }
+                uid
+            })
+            .collect::<Vec<u32>>();
+
+        let mut saved = Vec::new();
+        auction_house.save(&mut saved).unwrap();
+        let mut restored = MockAuctionHouse::load(&saved[..]).unwrap();
+        assert_eq!(restored.accounts, auction_house.accounts);
+        assert_eq!(restored.bid_records, auction_house.bid_records);
+        assert_eq!(
+            restored.unopened_bidders(auction_id).unwrap(),
+            vec![bidder_ids[1]]
+        );
+
+        let reports = [&mut auction_house, &mut restored]
+            .into_iter()
+            .map(|house| {
+                let bid_id = *house.active_auctions[&auction_id]
+                    .1
+                    .get(&bidder_ids[1])
+                    .unwrap();
+                let (bid, opening) = house.active_auctions[&auction_id]
+                    .0
+                    .force_open_bid(&auction_pp.auction_pp, bid_id as usize)
+                    .unwrap();
+                assert_eq!(bid, Some(900));
+                house
+                    .account_force_open(
+                        &house_pp,
+                        &auction_pp,
+                        auction_id,
+                        bidder_ids[1],
+                        bid_id,
+                        bid,
+                        &opening,
+                    )
+                    .unwrap();
+                house
+                    .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 1)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(reports[0].price, 700);
+        assert_eq!(reports[0].winners, vec![bidder_ids[1]]);
+        assert_eq!(reports[0], reports[1]);
+        assert_eq!(restored.accounts, auction_house.accounts);
+
+        // Openings accepted before the reload are recorded and audited like the others
+        let mut saved = Vec::new();
+        restored.save(&mut saved).unwrap();
+        let restored = MockAuctionHouse::load(&saved[..]).unwrap();
+        assert_eq!(restored.bid_records, auction_house.bid_records);
+        let audit = restored
+            .audit_auction(&house_pp, &auction_pp, auction_id)
+            .unwrap();
+        assert_eq!(audit.len(), bids.len());
+        assert!(audit
+            .values()
+            .all(|result| result.passed() && result.opening == Some(true)));
+    }
+
+    #[test]
+    fn lazy_tc_opening_encoding_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (_, auction_pp) = setup_test_params(&mut rng);
+        let pp = &auction_pp.auction_pp;
+
+        let (comm, self_opening) =
+            TC::commit(&mut rng, &pp.time_pp, &pp.ped_pp, &500u32.to_le_bytes()).unwrap();
+        let (_, force_opening) = TC::force_open(&pp.time_pp, &pp.ped_pp, &comm).unwrap();
+        assert!(!force_opening.is_self_opening());
+        for opening in [self_opening, force_opening] {
+            let mut bytes = Vec::new();
+            <TC as SealedBidScheme<G, TestRsaParams>>::write_opening(&opening, &mut bytes).unwrap();
+            let decoded =
+                <TC as SealedBidScheme<G, TestRsaParams>>::read_opening(&bytes[..]).unwrap();
+            assert_eq!(decoded, opening);
+            assert!(TC::ver_open(
+                &pp.time_pp,
+                &pp.ped_pp,
+                &comm,
+                &Some(500u32.to_le_bytes().to_vec()),
+                &decoded
+            )
+            .unwrap());
+
+            // Truncated encoding is rejected
+            assert!(<TC as SealedBidScheme<G, TestRsaParams>>::read_opening(
+                &bytes[..bytes.len() - 1]
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn duplicate_bid_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        let mut user = MockUser::new();
+        let (uid, _) = auction_house.new_account(&house_pp);
+        auction_house
+            .account_deposit(&house_pp, uid, 10000)
+            .unwrap();
+        user.confirm_deposit(&house_pp, 10000).unwrap();
+
+        let (proposal, opening) = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(300))
+            .unwrap();
+        // Nothing to replace yet
+        let err = auction_house
+            .account_replace_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidBid)
+        ));
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+            .unwrap();
+        user.confirm_bid(&house_pp, &auction_pp, auction_id, 300, &proposal, &opening)
+            .unwrap();
+        let bid_id = *auction_house.active_auctions[&auction_id]
+            .1
+            .get(&uid)
+            .unwrap();
+
+        let (proposal, _) = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(500))
+            .unwrap();
+        let err = auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::DuplicateBid)
+        ));
+
+        // User releases the prior bid locally before proposing its replacement
+        user.confirm_auction_cancel(&house_pp, &auction_pp, auction_id)
+            .unwrap();
+        let (proposal, replaced_opening) = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(500))
+            .unwrap();
+        auction_house
+            .account_replace_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+            .unwrap();
+        user.confirm_bid(
+            &house_pp,
+            &auction_pp,
+            auction_id,
+            500,
+            &proposal,
+            &replaced_opening,
+        )
+        .unwrap();
+        assert_eq!(auction_house.accounts[&uid], user.public_summary);
+        assert_eq!(
+            *auction_house.active_auctions[&auction_id]
+                .1
+                .get(&uid)
+                .unwrap(),
+            bid_id
+        );
+
+        // Only the replacement opens
+        assert!(auction_house
+            .account_self_open(&house_pp, &auction_pp, auction_id, uid, 300, &opening)
+            .is_err());
+        auction_house
+            .account_self_open(
+                &house_pp,
+                &auction_pp,
+                auction_id,
+                uid,
+                500,
+                &replaced_opening,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn balance_breakdown_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        let other_auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        // Two accounts with the same deposits, so only the binding tells their breakdowns apart
+        let mut users = (0..2)
+            .map(|_| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                for amt in [4000, 2500, 700] {
+                    auction_house.account_deposit(&house_pp, uid, amt).unwrap();
+                    user.confirm_deposit(&house_pp, amt).unwrap();
+                }
+                (uid, user)
+            })
+            .collect::<Vec<_>>();
+        let other_uid = users[1].0;
+        let (uid, user) = &mut users[0];
+        let uid = *uid;
+
+        let (proposal, opening, breakdown) = user
+            .propose_bid_with_balance_breakdown(
+                &mut rng,
+                &house_pp,
+                &auction_pp,
+                auction_id,
+                uid,
+                bid_value(500),
+            )
+            .unwrap();
+        assert!(auction_house
+            .verify_balance_breakdown(&house_pp, auction_id, uid, &proposal, &breakdown)
+            .unwrap());
+
+        // Misreported deposit
+        let ad = breakdown_binding(uid, auction_id, &proposal.comm_range_bid).unwrap();
+        let misreported =
+            MockUser::balance_breakdown(&mut rng, &house_pp, &[4000, 2000, 700], &ad).unwrap();
+        assert!(!auction_house
+            .verify_balance_breakdown(&house_pp, auction_id, uid, &proposal, &misreported)
+            .unwrap());
+
+        // Breakdown moved to another account, auction or proposal
+        assert!(!auction_house
+            .verify_balance_breakdown(&house_pp, auction_id, other_uid, &proposal, &breakdown)
+            .unwrap());
+        assert!(!auction_house
+            .verify_balance_breakdown(&house_pp, other_auction_id, uid, &proposal, &breakdown)
+            .unwrap());
+        let (other_proposal, _) = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(500))
+            .unwrap();
+        assert!(!auction_house
+            .verify_balance_breakdown(&house_pp, auction_id, uid, &other_proposal, &breakdown)
+            .unwrap());
+
+        // Still balances once the bid escrows its rewards
+        auction_house
+            .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+            .unwrap();
+        user.confirm_bid(&house_pp, &auction_pp, auction_id, 500, &proposal, &opening)
+            .unwrap();
+        let (proposal, _, breakdown) = user
+            .propose_bid_with_balance_breakdown(
+                &mut rng,
+                &house_pp,
+                &auction_pp,
+                other_auction_id,
+                uid,
+                bid_value(500),
+            )
+            .unwrap();
+        assert!(auction_house
+            .verify_balance_breakdown(&house_pp, other_auction_id, uid, &proposal, &breakdown)
+            .unwrap());
+        assert!(!auction_house
+            .verify_balance_breakdown(&house_pp, auction_id, uid, &proposal, &misreported)
+            .unwrap());
+    }
+
+    #[test]
+    fn propose_bids_parallel_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut user = MockUser::new();
+        user.confirm_deposit(&house_pp, 10000).unwrap();
+        let values = [100, 250, 900, 4000, 7].map(bid_value);
+        let seeds = [11u64, 12, 13, 14, 15];
+        let proposals = user
+            .propose_bids_parallel(&seeds, &house_pp, &auction_pp, &values)
+            .unwrap();
+        assert_eq!(proposals.len(), values.len());
+        for ((seed, bid), (proposal, opening)) in seeds.iter().zip(values.iter()).zip(proposals) {
+            let (serial_proposal, serial_opening) = user
+                .propose_bid(
+                    &mut StdRng::seed_from_u64(*seed),
+                    &house_pp,
+                    &auction_pp,
+                    *bid,
+                )
+                .unwrap();
+            assert_eq!(proposal.comm_bid, serial_proposal.comm_bid);
+            assert_eq!(proposal.range_proof_bid, serial_proposal.range_proof_bid);
+            assert_eq!(
+                proposal.range_proof_balance,
+                serial_proposal.range_proof_balance
+            );
+            assert_eq!(opening, serial_opening);
+        }
+
+        // Any bid the balance cannot cover fails the batch
+        let err = user
+            .propose_bids_parallel(
+                &seeds[..2],
+                &house_pp,
+                &auction_pp,
+                &[bid_value(100), bid_value(20000)],
+            )
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidBid)
+        ));
+        let err = user
+            .propose_bids_parallel(&seeds, &house_pp, &auction_pp, &values[..2])
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidBid)
+        ));
+    }
+
+    #[test]
+    fn compute_clearing_price_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        let bids = [250, 900, 400, 700, 300];
+        let openings = bids
+            .iter()
+            .map(|bid| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(*bid))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                (uid, *bid, opening)
+            })
+            .collect::<Vec<_>>();
+
+        // Not enough bids opened to set a price
+        assert!(auction_house.compute_clearing_price(auction_id, 2).is_err());
+        for (uid, bid, opening) in openings.iter() {
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, *uid, *bid, opening)
+                .unwrap();
+        }
+
+        let accounts = auction_house.accounts.clone();
+        let preview = auction_house.compute_clearing_price(auction_id, 2).unwrap();
+        assert_eq!(preview, 400);
+        assert_eq!(auction_house.accounts, accounts);
+        assert_eq!(
+            auction_house.compute_clearing_price(auction_id, 2).unwrap(),
+            preview
+        );
+        assert!(auction_house
+            .compute_clearing_price(auction_id + 1, 2)
+            .is_err());
+
+        let (price, _) = auction_house
+            .complete_kplusone_price_auction(&house_pp, &auction_pp, auction_id, 2)
+            .unwrap();
+        assert_eq!(price, preview);
+    }
+
+    #[test]
+    fn complete_no_bids_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, mut auction_pp) = setup_test_params(&mut rng);
+        auction_pp.seller_bond = 1000;
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let mut seller = MockUser::new();
+        let (seller_id, _) = auction_house.new_account(&house_pp);
+        auction_house
+            .account_deposit(&house_pp, seller_id, 5000)
+            .unwrap();
+        seller.confirm_deposit(&house_pp, 5000).unwrap();
+        let (bidder_id, _) = auction_house.new_account(&house_pp);
+        auction_house
+            .account_deposit(&house_pp, bidder_id, 5000)
+            .unwrap();
+        let accounts = auction_house.accounts.clone();
+
+        let bond_proof = seller
+            .propose_seller_bond(&mut rng, &house_pp, &auction_pp)
+            .unwrap();
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, seller_id, Some(&bond_proof), None)
+            .unwrap();
+        assert_eq!(auction_house.accounts[&seller_id].balance, 4000);
+
+        let report = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!(report.outcome, CompletionOutcome::NoBids);
+        assert_eq!(report.price, 0);
+        assert!(report.winners.is_empty());
+        assert_eq!(report.seller_proceeds, 0);
+        // Seller keeps the item and the bond, no other balance moves
+        assert_eq!(auction_house.accounts, accounts);
+
+        let report = auction_house
+            .complete_fixed_price_report(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!(report.outcome, CompletionOutcome::NoBids);
+        assert!(report.winners.is_empty());
+        assert_eq!(auction_house.accounts, accounts);
+    }
+
+    #[test]
+    fn complete_twice_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, auction_pp, mut auction_house, users) = setup_seller_bond_test(&mut rng, 0);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+        for uid in 1..3u32 {
+            let bid = uid * 100;
+            let (proposal, opening) = users[uid as usize]
+                .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(bid))
+                .unwrap();
+            auction_house
+                .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                .unwrap();
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, uid, bid, &opening)
+                .unwrap();
+        }
+        let report = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!(report.price, 100);
+        assert_eq!(report.winners, vec![2]);
+
+        // Second completion would charge the winner again
+        let accounts = auction_house.accounts.clone();
+        let seller_proceeds = auction_house.seller_proceeds;
+        let err = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 1)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidPhase)
+        ));
+        assert_eq!(auction_house.accounts, accounts);
+        assert_eq!(auction_house.seller_proceeds, seller_proceeds);
+
+        // Completion survives a reload
+        let mut saved = Vec::new();
+        auction_house.save(&mut saved).unwrap();
+        let mut restored = TestAuctionHouse::load(&saved[..]).unwrap();
+        assert!(restored
+            .complete_kplusone_price_auction(&house_pp, &auction_pp, auction_id, 1)
+            .is_err());
+        assert_eq!(restored.accounts, accounts);
+        assert!(auction_house.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn complete_fixed_price_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (house_pp, auction_pp, mut auction_house, users) = setup_seller_bond_test(&mut rng, 0);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, 0, None, None)
+            .unwrap();
+
+        let mut openings = Vec::new();
+        for uid in 1..3u32 {
+            let bid = uid * 100;
+            let (proposal, opening) = users[uid as usize]
+                .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(bid))
+                .unwrap();
+            auction_house
+                .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                .unwrap();
+            openings.push((uid, bid, opening));
+        }
+
+        // Bids placed but none opened
+        let err = auction_house
+            .complete_fixed_price(&house_pp, &auction_pp, auction_id, 1)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidPhase)
+        ));
+
+        for (uid, bid, opening) in openings.iter() {
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, *uid, *bid, opening)
+                .unwrap();
+        }
+        let accounts = auction_house.accounts.clone();
+        let report = auction_house
+            .complete_fixed_price_report(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!(report.outcome, CompletionOutcome::Cleared);
+        assert_eq!(report.price, 0);
+        assert_eq!(report.winners, vec![2]);
+        assert_eq!(report.losers, vec![1]);
+        assert_eq!(auction_house.accounts, accounts);
+    }
+
+    #[test]
+    fn sweep_expired_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, mut auction_pp) = setup_test_params(&mut rng);
+        auction_pp.reclaim_deadline = Some(10);
+
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let auction_id = {
+            let (seller_id, _) = auction_house.new_account(&house_pp);
+            auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap()
+        };
+        let mut users = (0..4)
+            .map(|i| {
+                let mut user = MockUser::new();
+                let (uid, _) = auction_house.new_account(&house_pp);
+                auction_house
+                    .account_deposit(&house_pp, uid, 10000)
+                    .unwrap();
+                user.confirm_deposit(&house_pp, 10000).unwrap();
+                let bid = (i + 1) * 100;
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(bid))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                user.confirm_bid(&house_pp, &auction_pp, auction_id, bid, &proposal, &opening)
+                    .unwrap();
+                // Lowest bid is never opened, so its rewards stay escrowed
+                if i > 0 {
+                    auction_house
+                        .account_self_open(&house_pp, &auction_pp, auction_id, uid, bid, &opening)
+                        .unwrap();
+                    user.confirm_bid_self_open(&house_pp, &auction_pp).unwrap();
+                }
+                (uid, user)
+            })
+            .collect::<Vec<_>>();
+
+        // Not completed yet
+        let err = auction_house
+            .sweep_expired(auction_id, u64::MAX)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidPhase)
+        ));
+
+        auction_house.set_block_number(100);
+        let report = auction_house
+            .complete_kplusone_price_auction_report(&house_pp, &auction_pp, auction_id, 2)
+            .unwrap();
+        let balances = users
+            .iter()
+            .map(|(uid, _)| auction_house.accounts[uid].balance)
+            .collect::<Vec<_>>();
+
+        // Nowhere to credit the escrow until a treasury is set
+        let err = auction_house.sweep_expired(auction_id, 110).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::NoTreasury)
+        ));
+        assert!(auction_house.set_treasury(u32::MAX).is_err());
+        let (treasury_id, _) = auction_house.new_account(&house_pp);
+        auction_house.set_treasury(treasury_id).unwrap();
+
+        // Saved deadline and treasury survive a reload
+        let mut saved = Vec::new();
+        auction_house.save(&mut saved).unwrap();
+        let mut auction_house = MockAuctionHouse::load(&saved[..]).unwrap();
+
+        let err = auction_house.sweep_expired(auction_id, 109).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::ReclaimNotExpired(110))
+        ));
+        assert_eq!(auction_house.sweep_expired(auction_id, 110).unwrap(), 1);
+        auction_house.check_invariants().unwrap();
+
+        // Sealed bid's escrow goes to the treasury, opened bids were refunded when they opened
+        assert_eq!(auction_house.accounts[&treasury_id].balance, 500);
+        for (i, (uid, _)) in users.iter().enumerate() {
+            assert_eq!(auction_house.accounts[uid].balance, balances[i]);
+        }
+        let (sealed_uid, sealed_user) = &mut users[0];
+        sealed_user
+            .confirm_auction_loss(&house_pp, &auction_pp, auction_id)
+            .unwrap();
+        assert_eq!(
+            sealed_user.public_summary.balance,
+            auction_house.accounts[sealed_uid].balance
+        );
+        assert_eq!(report.losers[0], users[0].0);
+
+        // Auction is closed
+        let err = auction_house.sweep_expired(auction_id, 110).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<AuctionError>(),
+            Some(AuctionError::InvalidID)
+        ));
+    }
+
+    #[test]
+    fn auctions_in_phase_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, wall_clock_pp) = setup_test_params(&mut rng);
+        let mut auction_house = MockAuctionHouse::new(&house_pp);
+        let (seller_id, _) = auction_house.new_account(&house_pp);
+        let mut user = MockUser::new();
+        let (uid, _) = auction_house.new_account(&house_pp);
+        auction_house
+            .account_deposit(&house_pp, uid, 10000)
+            .unwrap();
+        user.confirm_deposit(&house_pp, 10000).unwrap();
+
+        // 10 blocks of collection and of self opening, from each start block
+        for (start_block, sealed_bid) in [(0, false), (185, true), (195, true), (200, false)] {
+            let mut auction_pp = wall_clock_pp.clone();
+            auction_pp.auction_pp.timing = PhaseTiming::BlockBased {
+                collection_blocks: 10,
+                self_open_blocks: 10,
+                start_block,
+            };
+            let auction_id = auction_house
+                .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+                .unwrap();
+            if sealed_bid {
+                let (proposal, opening) = user
+                    .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(100))
+                    .unwrap();
+                auction_house
+                    .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                    .unwrap();
+                user.confirm_bid(&house_pp, &auction_pp, auction_id, 100, &proposal, &opening)
+                    .unwrap();
+            }
+        }
+
+        let now = Instant::now();
+        assert_eq!(
+            auction_house.auctions_in_phase(AuctionPhase::BidCollection, now, 0),
+            vec![0, 1, 2, 3]
+        );
+        let phases = [
+            (AuctionPhase::BidCollection, vec![3]),
+            (AuctionPhase::BidSelfOpening, vec![2]),
+            (AuctionPhase::BidForceOpening, vec![1]),
+            (AuctionPhase::Complete, vec![0]),
+        ];
+        for (phase, auction_ids) in phases.iter() {
+            assert_eq!(
+                &auction_house.auctions_in_phase(*phase, now, 205),
+                auction_ids
+            );
+        }
+
+        // Timings survive a reload
+        let mut saved = Vec::new();
+        auction_house.save(&mut saved).unwrap();
+        let restored = MockAuctionHouse::load(&saved[..]).unwrap();
+        for (phase, auction_ids) in phases.iter() {
+            assert_eq!(&restored.auctions_in_phase(*phase, now, 205), auction_ids);
+        }
+    }
+
+    #[test]
+    fn coin_backend_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut coin = MockCoin::default();
+        coin.balances.insert(1, 5000);
+        coin.balances.insert(2, 5000);
+        let mut auction_house = MockCoinAuctionHouse::with_coin(&house_pp, coin);
+        let (seller_id, _) = auction_house.new_account(&house_pp);
+        let auction_id = auction_house
+            .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+            .unwrap();
+
+        let mut bidders = Vec::new();
+        for bid in [400, 700].iter() {
+            let mut user = MockUser::new();
+            let (uid, _) = auction_house.new_account(&house_pp);
+            auction_house.account_deposit(&house_pp, uid, 3000).unwrap();
+            user.confirm_deposit(&house_pp, 3000).unwrap();
+            let (proposal, opening) = user
+                .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(*bid))
+                .unwrap();
+            auction_house
+                .account_bid(&house_pp, &auction_pp, auction_id, uid, &proposal)
+                .unwrap();
+            user.confirm_bid(
+                &house_pp,
+                &auction_pp,
+                auction_id,
+                *bid,
+                &proposal,
+                &opening,
+            )
+            .unwrap();
+            bidders.push((uid, *bid, opening, user));
+        }
+
+        // Deposit over the coin balance is rejected without touching the house balance
+        assert!(auction_house.account_deposit(&house_pp, 1, 2001).is_err());
+        assert_eq!(auction_house.accounts[&1].balance, 2500);
+        assert_eq!(auction_house.coin().balances[&1], 2000);
+
+        for (uid, bid, opening, user) in bidders.iter_mut() {
+            auction_house
+                .account_self_open(&house_pp, &auction_pp, auction_id, *uid, *bid, opening)
+                .unwrap();
+            user.confirm_bid_self_open(&house_pp, &auction_pp).unwrap();
+        }
+        let (price, winners) = auction_house
+            .complete_kplusone_price_auction(&house_pp, &auction_pp, auction_id, 1)
+            .unwrap();
+        assert_eq!((price, winners), (400, vec![2]));
+        bidders[0]
+            .3
+            .confirm_auction_loss(&house_pp, &auction_pp, auction_id)
+            .unwrap();
+        bidders[1]
+            .3
+            .confirm_auction_win(&house_pp, &auction_pp, auction_id, price)
+            .unwrap();
+
+        // Both bidders withdraw their remaining house balance
+        for (uid, _, _, user) in bidders.iter() {
+            let amt = user.public_summary.balance;
+            let proof = user.propose_withdrawal(&mut rng, &house_pp, amt).unwrap();
+            auction_house
+                .account_withdrawal(&house_pp, *uid, amt, &proof)
+                .unwrap();
+        }
+        assert_eq!(
+            auction_house.coin().transfers,
+            vec![(1, -3000), (2, -3000), (1, 3000), (2, 2600)]
+        );
+        assert_eq!(auction_house.coin().balances[&1], 5000);
+        assert_eq!(auction_house.coin().balances[&2], 4600);
+        assert!(auction_house.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn deposit_and_bid_refund_failure_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let (house_pp, auction_pp) = setup_test_params(&mut rng);
+
+        let mut coin = MockCoin::default();
+        coin.balances.insert(0, 5000);
+        coin.fail_credit = true;
+        let mut auction_house = MockCoinAuctionHouse::with_coin(&house_pp, coin);
+        let (uid, _) = auction_house.new_account(&house_pp);
+        let mut user = MockUser::new();
+        user.confirm_deposit(&house_pp, 2000).unwrap();
+        let (proposal, _) = user
+            .propose_bid(&mut rng, &house_pp, &auction_pp, bid_value(100))
+            .unwrap();
+
+        // Bid on an unknown auction is still reported when refunding the deposit fails
+        let err = auction_house
+            .deposit_and_bid(&house_pp, &auction_pp, 0, uid, 2000, &proposal)
+            .err()
+            .unwrap();
+        match err.downcast_ref::<AuctionError>() {
+            Some(AuctionError::RefundFailed { bid, .. }) => {
+                assert_eq!(bid, &AuctionError::InvalidID.to_string())
+            }
+            _ => panic!("expected a failed refund, got {}", err),
+        }
+        assert_eq!(auction_house.accounts[&uid].balance, 0);
+        assert_eq!(auction_house.coin().balances[&0], 3000);
+    }
 }
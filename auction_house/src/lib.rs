@@ -9,8 +9,11 @@ pub mod baseline_house;
 pub mod house;
 pub mod rp_auction;
 pub mod rp_house;
+pub mod sealed_bid;
 pub mod snark_auction;
 pub mod snark_house;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
 pub type Error = Box<dyn ErrorTrait>;
 
@@ -19,6 +22,16 @@ pub enum AuctionError {
     InvalidPhase,
     InvalidBid,
     InvalidID,
+    BalanceUnderflow,
+    BalanceOverflow,
+    BidOutOfRange { value: u32, max_bits: u32 },
+    BidCommitmentMismatch,
+    DuplicateBid,
+    BundleSplit,
+    ReclaimNotExpired(u64), // block from which the auction can be swept
+    MissingBidRecord(u32),  // bid_id without recorded proofs to audit
+    NoTreasury,
+    RefundFailed { bid: String, refund: String }, // rejected bid whose deposit could not be refunded
 }
 
 impl ErrorTrait for AuctionError {
@@ -33,6 +46,29 @@ impl fmt::Display for AuctionError {
             AuctionError::InvalidBid => format!("invalid bid"),
             AuctionError::InvalidPhase => format!("invalid phase"),
             AuctionError::InvalidID => format!("invalid id"),
+            AuctionError::BalanceUnderflow => format!("balance underflow"),
+            AuctionError::BalanceOverflow => format!("balance overflow"),
+            AuctionError::BidOutOfRange { value, max_bits } => {
+                format!("bid {} does not fit in {} bits", value, max_bits)
+            }
+            AuctionError::BidCommitmentMismatch => {
+                format!("bid range proof does not match the bid commitment")
+            }
+            AuctionError::DuplicateBid => format!("account already has a bid in the auction"),
+            AuctionError::BundleSplit => format!("bundle cannot be split between winners"),
+            AuctionError::ReclaimNotExpired(block) => {
+                format!("unreclaimed rewards can be swept from block {}", block)
+            }
+            AuctionError::MissingBidRecord(bid_id) => {
+                format!("no recorded proofs to audit bid {}", bid_id)
+            }
+            AuctionError::NoTreasury => format!("no treasury account to credit swept escrow"),
+            AuctionError::RefundFailed { bid, refund } => {
+                format!(
+                    "bid rejected: {}, refunding its deposit failed: {}",
+                    bid, refund
+                )
+            }
         };
         write!(f, "{}", msg)
     }
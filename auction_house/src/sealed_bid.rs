@@ -0,0 +1,300 @@
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use digest::Digest;
+use rand::{CryptoRng, Rng};
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    io::{Read, Write},
+};
+
+use crate::Error;
+use rsa::{
+    bigint::BigInt,
+    hash_to_prime::{
+        pocklington::{PocklingtonCert, StepCert},
+        HashToPrime,
+    },
+    hog::{RsaGroupParams, RsaHiddenOrderGroup},
+    poe::{PoEParams, Proof as PoEProof},
+};
+use timed_commitments::{
+    basic_tc::{Comm as BasicTCComm, Opening as BasicTCOpening, TimeParams},
+    lazy_tc::{Comm as TCComm, LazyTC, Opening as TCOpening},
+    PedersenParams,
+};
+
+// Timed commitment scheme used to seal bids. Commitments must expose a Pedersen commitment to the
+// bid so that the auction house can range prove against account balances.
+pub trait SealedBidScheme<G: ProjectiveCurve, RsaP: RsaGroupParams> {
+    type Comm: Clone + PartialEq + Eq + Hash + Debug;
+    type Opening: Clone + PartialEq + Eq + Debug;
+
+    fn commit<R: CryptoRng + Rng>(
+        rng: &mut R,
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        m: &[u8],
+    ) -> Result<(Self::Comm, Self::Opening), Error>;
+
+    // Cheap well-formedness check run before accepting a bid
+    fn check_comm(time_pp: &TimeParams<RsaP>, comm: &Self::Comm) -> Result<bool, Error>;
+
+    fn force_open(
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        comm: &Self::Comm,
+    ) -> Result<(Option<Vec<u8>>, Self::Opening), Error>;
+
+    fn ver_open(
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        comm: &Self::Comm,
+        m: &Option<Vec<u8>>,
+        opening: &Self::Opening,
+    ) -> Result<bool, Error>;
+
+    fn ped_comm(comm: &Self::Comm) -> G;
+
+    fn ped_opening(opening: &Self::Opening) -> G::ScalarField;
+
+    // Committed message revealed by an opening, `None` if it did not recover one
+    fn opened_message(opening: &Self::Opening) -> Option<Vec<u8>>;
+
+    // Encoding of commitments for persisting auction house state
+    fn write_comm<W: Write>(comm: &Self::Comm, writer: W) -> Result<(), Error>;
+
+    fn read_comm<R: Read>(reader: R) -> Result<Self::Comm, Error>;
+
+    // Encoding of accepted openings, kept with the bid records for `audit_auction`
+    fn write_opening<W: Write>(opening: &Self::Opening, writer: W) -> Result<(), Error>;
+
+    fn read_opening<R: Read>(reader: R) -> Result<Self::Opening, Error>;
+}
+
+// Encoding of hash to prime certificates, needed to persist the PoE proof of a force opening
+pub trait CertEncoding: Sized {
+    fn write_cert<W: Write>(&self, writer: W) -> Result<(), SerializationError>;
+
+    fn read_cert<R: Read>(reader: R) -> Result<Self, SerializationError>;
+}
+
+impl CertEncoding for u32 {
+    fn write_cert<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.serialize(writer)
+    }
+
+    fn read_cert<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        u32::deserialize(reader)
+    }
+}
+
+impl CertEncoding for PocklingtonCert {
+    fn write_cert<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        (self.step_certificates.len() as u64).serialize(&mut writer)?;
+        for step in self.step_certificates.iter() {
+            for n in [&step.f, &step.a, &step.bu, &step.bv] {
+                write_bigint(n, &mut writer)?;
+            }
+            step.n.serialize(&mut writer)?;
+            step.n2.serialize(&mut writer)?;
+            for n in [
+                &step.v,
+                &step.s,
+                &step.expr_sqrt,
+                &step.p_less_one_div_f,
+                &step.p_less_one_div_two,
+                &step.b_p_div_f1,
+                &step.b_p_div_f2,
+                &step.b_p_div_two1,
+                &step.b_p_div_two2,
+            ] {
+                n.is_some().serialize(&mut writer)?;
+                if let Some(n) = n {
+                    write_bigint(n, &mut writer)?;
+                }
+            }
+        }
+        self.nonce.serialize(&mut writer)?;
+        Ok(())
+    }
+
+    fn read_cert<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut step_certificates = Vec::new();
+        for _ in 0..u64::deserialize(&mut reader)? {
+            step_certificates.push(StepCert {
+                f: read_bigint(&mut reader)?,
+                a: read_bigint(&mut reader)?,
+                bu: read_bigint(&mut reader)?,
+                bv: read_bigint(&mut reader)?,
+                n: u32::deserialize(&mut reader)?,
+                n2: u32::deserialize(&mut reader)?,
+                v: read_optional_bigint(&mut reader)?,
+                s: read_optional_bigint(&mut reader)?,
+                expr_sqrt: read_optional_bigint(&mut reader)?,
+                p_less_one_div_f: read_optional_bigint(&mut reader)?,
+                p_less_one_div_two: read_optional_bigint(&mut reader)?,
+                b_p_div_f1: read_optional_bigint(&mut reader)?,
+                b_p_div_f2: read_optional_bigint(&mut reader)?,
+                b_p_div_two1: read_optional_bigint(&mut reader)?,
+                b_p_div_two2: read_optional_bigint(&mut reader)?,
+            });
+        }
+        Ok(PocklingtonCert {
+            step_certificates,
+            nonce: u32::deserialize(&mut reader)?,
+        })
+    }
+}
+
+fn write_bigint<W: Write>(n: &BigInt, writer: W) -> Result<(), SerializationError> {
+    n.to_signed_bytes_be().serialize(writer)
+}
+
+fn read_bigint<R: Read>(reader: R) -> Result<BigInt, SerializationError> {
+    let bytes = Vec::<u8>::deserialize(reader)?;
+    Ok(BigInt::from_signed_bytes_be(&bytes))
+}
+
+fn read_optional_bigint<R: Read>(mut reader: R) -> Result<Option<BigInt>, SerializationError> {
+    match bool::deserialize(&mut reader)? {
+        true => Ok(Some(read_bigint(&mut reader)?)),
+        false => Ok(None),
+    }
+}
+
+impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
+    SealedBidScheme<G, RsaP> for LazyTC<G, PoEP, RsaP, H, H2P>
+where
+    H2P::Certificate: CertEncoding,
+{
+    type Comm = TCComm<G, RsaP>;
+    type Opening = TCOpening<G, RsaP, H2P>;
+
+    fn commit<R: CryptoRng + Rng>(
+        rng: &mut R,
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        m: &[u8],
+    ) -> Result<(Self::Comm, Self::Opening), Error> {
+        LazyTC::<G, PoEP, RsaP, H, H2P>::commit(rng, time_pp, ped_pp, m)
+    }
+
+    fn check_comm(time_pp: &TimeParams<RsaP>, comm: &Self::Comm) -> Result<bool, Error> {
+        LazyTC::<G, PoEP, RsaP, H, H2P>::check_comm(time_pp, comm)
+    }
+
+    fn force_open(
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        comm: &Self::Comm,
+    ) -> Result<(Option<Vec<u8>>, Self::Opening), Error> {
+        LazyTC::<G, PoEP, RsaP, H, H2P>::force_open(time_pp, ped_pp, comm)
+    }
+
+    fn ver_open(
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        comm: &Self::Comm,
+        m: &Option<Vec<u8>>,
+        opening: &Self::Opening,
+    ) -> Result<bool, Error> {
+        LazyTC::<G, PoEP, RsaP, H, H2P>::ver_open(time_pp, ped_pp, comm, m, opening)
+    }
+
+    fn ped_comm(comm: &Self::Comm) -> G {
+        comm.ped_comm
+    }
+
+    fn ped_opening(opening: &Self::Opening) -> G::ScalarField {
+        opening.get_ped_opening()
+    }
+
+    fn opened_message(opening: &Self::Opening) -> Option<Vec<u8>> {
+        opening.message_bytes().map(|m| m.to_vec())
+    }
+
+    fn write_comm<W: Write>(comm: &Self::Comm, writer: W) -> Result<(), Error> {
+        Ok(write_lazy_tc_comm(comm, writer)?)
+    }
+
+    fn read_comm<R: Read>(reader: R) -> Result<Self::Comm, Error> {
+        Ok(read_lazy_tc_comm(reader)?)
+    }
+
+    fn write_opening<W: Write>(opening: &Self::Opening, mut writer: W) -> Result<(), Error> {
+        opening.tc_m.is_some().serialize(&mut writer)?;
+        if let Some(tc_m) = &opening.tc_m {
+            tc_m.serialize(&mut writer)?;
+        }
+        match &opening.tc_opening {
+            BasicTCOpening::SELF(r) => {
+                0u8.serialize(&mut writer)?;
+                write_bigint(r, &mut writer)?;
+            }
+            BasicTCOpening::FORCE(y, proof) => {
+                1u8.serialize(&mut writer)?;
+                y.to_bytes_be().serialize(&mut writer)?;
+                proof.q.to_bytes_be().serialize(&mut writer)?;
+                write_bigint(&proof.l, &mut writer)?;
+                proof.cert.write_cert(&mut writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_opening<R: Read>(mut reader: R) -> Result<Self::Opening, Error> {
+        let tc_m = match bool::deserialize(&mut reader)? {
+            true => Some(Vec::<u8>::deserialize(&mut reader)?),
+            false => None,
+        };
+        let tc_opening = match u8::deserialize(&mut reader)? {
+            0 => BasicTCOpening::SELF(read_bigint(&mut reader)?),
+            1 => {
+                let y = RsaHiddenOrderGroup::from_bytes_be(&Vec::<u8>::deserialize(&mut reader)?)?;
+                let q = RsaHiddenOrderGroup::from_bytes_be(&Vec::<u8>::deserialize(&mut reader)?)?;
+                let proof = PoEProof {
+                    q,
+                    l: read_bigint(&mut reader)?,
+                    cert: <H2P::Certificate as CertEncoding>::read_cert(&mut reader)?,
+                };
+                BasicTCOpening::FORCE(y, proof)
+            }
+            _ => return Err(Box::new(SerializationError::InvalidData)),
+        };
+        Ok(TCOpening::from_parts(tc_opening, tc_m))
+    }
+}
+
+// Encoding of a `LazyTC` commitment, shared by `write_comm` and the `BidProposal` wire format
+pub fn write_lazy_tc_comm<G: ProjectiveCurve, RsaP: RsaGroupParams, W: Write>(
+    comm: &TCComm<G, RsaP>,
+    mut writer: W,
+) -> Result<(), SerializationError> {
+    comm.ped_comm.into_affine().serialize(&mut writer)?;
+    comm.tc_comm.x.to_bytes_be().serialize(&mut writer)?;
+    comm.tc_comm.ct.serialize(&mut writer)?;
+    Ok(())
+}
+
+pub fn lazy_tc_comm_size<G: ProjectiveCurve, RsaP: RsaGroupParams>(
+    comm: &TCComm<G, RsaP>,
+) -> usize {
+    comm.ped_comm.into_affine().serialized_size()
+        + comm.tc_comm.x.to_bytes_be().serialized_size()
+        + comm.tc_comm.ct.serialized_size()
+}
+
+pub fn read_lazy_tc_comm<G: ProjectiveCurve, RsaP: RsaGroupParams, R: Read>(
+    mut reader: R,
+) -> Result<TCComm<G, RsaP>, SerializationError> {
+    let ped_comm = G::Affine::deserialize(&mut reader)?.into_projective();
+    let x = RsaHiddenOrderGroup::from_bytes_be(&Vec::<u8>::deserialize(&mut reader)?)
+        .map_err(|_| SerializationError::InvalidData)?;
+    let ct = Vec::<u8>::deserialize(&mut reader)?;
+    Ok(TCComm {
+        ped_comm,
+        tc_comm: BasicTCComm { x, ct },
+    })
+}
@@ -0,0 +1,52 @@
+use digest::Digest;
+use rand::{rngs::StdRng, CryptoRng, Error as RngError, RngCore, SeedableRng};
+use sha3::Keccak256;
+
+/// Rng that replays a supplied byte stream, e.g. a recorded or fuzzer-provided input.
+/// Once the stream is exhausted, output continues from a StdRng seeded by the hash of the
+/// stream so that rejection sampling on a short stream cannot loop forever.
+pub struct DeterministicRng {
+    bytes: Vec<u8>,
+    pos: usize,
+    fallback: StdRng,
+}
+
+impl DeterministicRng {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&Keccak256::digest(&bytes));
+        Self {
+            bytes,
+            pos: 0,
+            fallback: StdRng::from_seed(seed),
+        }
+    }
+}
+
+impl RngCore for DeterministicRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u8; 4];
+        self.fill_bytes(&mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        self.fill_bytes(&mut buf);
+        u64::from_le_bytes(buf)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let n_replay = dest.len().min(self.bytes.len() - self.pos);
+        dest[..n_replay].copy_from_slice(&self.bytes[self.pos..self.pos + n_replay]);
+        self.pos += n_replay;
+        self.fallback.fill_bytes(&mut dest[n_replay..]);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for DeterministicRng {}
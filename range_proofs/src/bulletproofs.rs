@@ -7,9 +7,12 @@ use num_traits::{One, Zero};
 use rand::{CryptoRng, Rng};
 
 use crate::Error;
-use std::{marker::PhantomData, ops::Neg};
+use std::{error::Error as ErrorTrait, fmt, marker::PhantomData, ops::Neg};
 
-use rsa::{bigint::BigInt, hash_to_prime::hash_to_variable_output_length};
+use rsa::{
+    bigint::{nat_to_f, BigInt},
+    hash_to_prime::hash_to_variable_output_length,
+};
 
 
 pub use timed_commitments::{PedersenComm, PedersenParams};
@@ -40,6 +43,97 @@ pub struct Proof<G: ProjectiveCurve> {
     pub base_b: G::ScalarField,
 }
 
+/// Range proofs for v - min and max - v against commitments shifted by g^min and g^max
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BoundedProof<G: ProjectiveCurve> {
+    pub lower: Proof<G>,
+    pub upper: Proof<G>,
+}
+
+/// Proof that the values in a vector Pedersen commitment over the range proof generators sum to
+/// a public total
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SumProof<G: ProjectiveCurve> {
+    pub comm_mask: G,
+    pub sum_mask: G::ScalarField,
+    pub z_values: Vec<G::ScalarField>,
+    pub z_opening: G::ScalarField,
+}
+
+/// Fiat-Shamir transcript shared by prover and verifier. Each challenge hashes the previous
+/// challenge digest together with the messages appended since, matching the solidity verifier.
+pub struct Transcript<D: Digest> {
+    digest: Vec<u8>,
+    pending: Vec<u8>,
+    _hash: PhantomData<D>,
+}
+
+impl<D: Digest> Default for Transcript<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Digest> Transcript<D> {
+    pub fn new() -> Self {
+        Self {
+            digest: Vec::new(),
+            pending: Vec::new(),
+            _hash: PhantomData,
+        }
+    }
+
+    /// Transcript whose last challenge digest was `digest`, e.g. the parameter fingerprint the
+    /// solidity verifier starts from
+    pub fn from_digest(digest: &[u8]) -> Self {
+        Self {
+            digest: digest.to_vec(),
+            pending: Vec::new(),
+            _hash: PhantomData,
+        }
+    }
+
+    pub fn append_bytes(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+    }
+
+    pub fn append_point<G: ProjectiveCurve>(&mut self, g: &G) {
+        self.pending.append(&mut serialize_group_elem(g));
+    }
+
+    pub fn append_scalar<F: PrimeField>(&mut self, f: &F) {
+        self.pending.append(&mut serialize_field_elem(f));
+    }
+
+    /// Returns the digest of the previous challenge, i.e. the solidity transcript state
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    pub fn challenge_bytes(&mut self) -> Vec<u8> {
+        let mut hash_input = std::mem::take(&mut self.digest);
+        hash_input.append(&mut self.pending);
+        self.digest = hash_to_variable_output_length::<D>(&hash_input, 32);
+        self.digest.clone()
+    }
+
+    pub fn challenge_scalar<F: PrimeField>(&mut self) -> F {
+        let mut chal = self.challenge_bytes();
+        chal.reverse(); // Reverse to match solidity reading of bytes
+        F::from_random_bytes(&chal[..16]).unwrap()
+    }
+
+    /// Two challenges from the low and high halves of a single digest
+    pub fn challenge_scalar_pair<F: PrimeField>(&mut self) -> (F, F) {
+        let mut chal = self.challenge_bytes();
+        chal.reverse(); // Reverse to match solidity reading of bytes
+        (
+            F::from_random_bytes(&chal[..16]).unwrap(),
+            F::from_random_bytes(&chal[16..]).unwrap(),
+        )
+    }
+}
+
 impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
     pub fn gen_params<R: CryptoRng + Rng>(rng: &mut R, n: u64) -> Params<G> {
         Params {
@@ -49,6 +143,24 @@ impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
         }
     }
 
+    /// Hash of the public parameters embedded as `pp_hash` in the solidity verifier.
+    /// Matches the deployed contract when instantiated with Keccak256.
+    pub fn params_fingerprint(pp: &Params<G>, ped_pp: &PedersenParams<G>) -> [u8; 32] {
+        let mut hash_input = Vec::<u8>::new();
+        hash_input.append(&mut serialize_group_elem(&ped_pp.g));
+        hash_input.append(&mut serialize_group_elem(&ped_pp.h));
+        for g in pp.g.iter() {
+            hash_input.append(&mut serialize_group_elem(g));
+        }
+        for h in pp.h.iter() {
+            hash_input.append(&mut serialize_group_elem(h));
+        }
+        hash_input.append(&mut serialize_group_elem(&pp.u));
+        let mut fingerprint = [0u8; 32];
+        fingerprint.copy_from_slice(&hash_to_variable_output_length::<D>(&hash_input, 32));
+        fingerprint
+    }
+
     /// Prove comm = g^v * h^opening AND v \in [0, 2^n)
     pub fn prove_range<R: CryptoRng + Rng>(
         rng: &mut R,
@@ -58,6 +170,22 @@ impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
         v: &BigInt,
         opening: &G::ScalarField,
         n: u64,
+    ) -> Result<Proof<G>, Error> {
+        Self::prove_range_with_ad(rng, pp, ped_pp, comm, v, opening, n, &[])
+    }
+
+    /// Like `prove_range`, additionally binding the transcript to `ad` of any length. The proof
+    /// only verifies with `verify_range_with_ad` given the same `ad`; an empty `ad` leaves the
+    /// transcript of `prove_range` unchanged.
+    pub fn prove_range_with_ad<R: CryptoRng + Rng>(
+        rng: &mut R,
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        v: &BigInt,
+        opening: &G::ScalarField,
+        n: u64,
+        ad: &[u8],
     ) -> Result<Proof<G>, Error> {
         // Check validity of statement
         // TODO: Support padding n to a power of 2
@@ -111,34 +239,10 @@ impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
             .map(|((s, s_minus), (g, h))| g.mul(&s.into_repr()) + h.mul(&s_minus.into_repr()))
             .fold(pp.u.mul(&r_blind.into_repr()), |acc, g| acc + g);
 
-        let pp_hash = {
-            let mut hash_input = Vec::<u8>::new();
-            hash_input.append(&mut serialize_group_elem(&ped_pp.g));
-            hash_input.append(&mut serialize_group_elem(&ped_pp.h));
-            for g in pp.g.iter() {
-                hash_input.append(&mut serialize_group_elem(g));
-            }
-            for h in pp.h.iter() {
-                hash_input.append(&mut serialize_group_elem(h));
-            }
-            hash_input.append(&mut serialize_group_elem(&pp.u));
-            hash_to_variable_output_length::<D>(&hash_input, 32)
-        };
-        let (chal_y, chal_z, fs_aux) = {
-            let mut hash_input = Vec::<u8>::new();
-            hash_input.extend_from_slice(&pp_hash);
-            hash_input.append(&mut serialize_group_elem(comm));
-            hash_input.extend_from_slice(&n.to_be_bytes());
-            hash_input.append(&mut serialize_group_elem(&comm_bits));
-            hash_input.append(&mut serialize_group_elem(&comm_blind));
-            let mut chal = hash_to_variable_output_length::<D>(&hash_input, 32);
-            chal.reverse();  // Reverse to match solidity reading of bytes
-            let chal_y = G::ScalarField::from_random_bytes(&chal[..16]).unwrap();
-            let chal_z = G::ScalarField::from_random_bytes(&chal[16..]).unwrap();
-            chal.reverse();
-            let fs_aux = chal;
-            (chal_y, chal_z, fs_aux)
-        };
+        let mut transcript = Self::statement_transcript(pp, ped_pp, comm, n, ad);
+        transcript.append_point(&comm_bits);
+        transcript.append_point(&comm_blind);
+        let (chal_y, chal_z) = transcript.challenge_scalar_pair::<G::ScalarField>();
 
         // Commit to linear combination coefficients
         let chal_y_powers = scalar_powers(n, &chal_y);
@@ -197,18 +301,9 @@ impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
         let comm_lc1 = ped_pp.g.mul(&t_1.into_repr()) + ped_pp.h.mul(&r_lc1.into_repr());
         let comm_lc2 = ped_pp.g.mul(&t_2.into_repr()) + ped_pp.h.mul(&r_lc2.into_repr());
 
-        let (chal_x, fs_aux) = {
-            let mut hash_input = Vec::<u8>::new();
-            hash_input.extend_from_slice(&fs_aux);
-            hash_input.append(&mut serialize_group_elem(&comm_lc1));
-            hash_input.append(&mut serialize_group_elem(&comm_lc2));
-            let mut chal = hash_to_variable_output_length::<D>(&hash_input, 32);
-            chal.reverse();  // Reverse to match solidity reading of bytes
-            let chal_x = G::ScalarField::from_random_bytes(&chal[..16]).unwrap();
-            chal.reverse();
-            let fs_aux = chal;
-            (chal_x, fs_aux)
-        };
+        transcript.append_point(&comm_lc1);
+        transcript.append_point(&comm_lc2);
+        let chal_x = transcript.challenge_scalar::<G::ScalarField>();
 
         let a_vec = a_0
             .iter()
@@ -237,17 +332,10 @@ impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
         let mut g = pp.g.clone();
         let mut h = h_shift;
         let mut recurse_commitments = Vec::<(G, G)>::new();
-        let (mut fs_aux, chal_u) = {
-            let mut hash_input = fs_aux;
-            hash_input.append(&mut serialize_field_elem(&t_x));
-            hash_input.append(&mut serialize_field_elem(&r_t_x));
-            hash_input.append(&mut serialize_field_elem(&r_comm_bits));
-            let mut chal = hash_to_variable_output_length::<D>(&hash_input, 32);
-            chal.reverse();  // Reverse to match solidity reading of bytes
-            let chal_u = G::ScalarField::from_random_bytes(&chal[..16]).unwrap();
-            chal.reverse();
-            (chal, chal_u)
-        };
+        transcript.append_scalar(&t_x);
+        transcript.append_scalar(&r_t_x);
+        transcript.append_scalar(&r_comm_bits);
+        let chal_u = transcript.challenge_scalar::<G::ScalarField>();
 
         'recurse: loop {
             // TODO: Increase base case to avoid small recursions
@@ -301,18 +389,9 @@ impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
                             .into_repr(),
                     );
 
-                let chal_x = {
-                    let mut hash_input = Vec::<u8>::new();
-                    hash_input.extend_from_slice(&fs_aux);
-                    hash_input.append(&mut serialize_group_elem(&comm_1));
-                    hash_input.append(&mut serialize_group_elem(&comm_2));
-                    let mut chal = hash_to_variable_output_length::<D>(&hash_input, 32);
-                    chal.reverse();  // Reverse to match solidity reading of bytes
-                    let chal_x = G::ScalarField::from_random_bytes(&chal[..16]).unwrap();
-                    chal.reverse();
-                    fs_aux = chal;
-                    chal_x
-                };
+                transcript.append_point(&comm_1);
+                transcript.append_point(&comm_2);
+                let chal_x = transcript.challenge_scalar::<G::ScalarField>();
                 let chal_x_inv = chal_x.inverse().unwrap();
 
                 a = a_1
@@ -362,77 +441,224 @@ impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
         n: u64,
         proof: &Proof<G>,
     ) -> Result<bool, Error> {
-        // Verify range encoding to inner product argument
-        let pp_hash = {
-            let mut hash_input = Vec::<u8>::new();
-            hash_input.append(&mut serialize_group_elem(&ped_pp.g));
-            hash_input.append(&mut serialize_group_elem(&ped_pp.h));
-            for g in pp.g.iter() {
-                hash_input.append(&mut serialize_group_elem(g));
-            }
-            for h in pp.h.iter() {
-                hash_input.append(&mut serialize_group_elem(h));
-            }
-            hash_input.append(&mut serialize_group_elem(&pp.u));
-            hash_to_variable_output_length::<D>(&hash_input, 32)
-        };
-        let (chal_y, chal_z, fs_aux) = {
-            let mut hash_input = Vec::<u8>::new();
-            hash_input.extend_from_slice(&pp_hash);
-            hash_input.append(&mut serialize_group_elem(comm));
-            hash_input.extend_from_slice(&n.to_be_bytes());
-            hash_input.append(&mut serialize_group_elem(&proof.comm_bits));
-            hash_input.append(&mut serialize_group_elem(&proof.comm_blind));
-            let mut chal = hash_to_variable_output_length::<D>(&hash_input, 32);
-            chal.reverse();  // Reverse to match solidity reading of bytes
-            let chal_y = G::ScalarField::from_random_bytes(&chal[..16]).unwrap();
-            let chal_z = G::ScalarField::from_random_bytes(&chal[16..]).unwrap();
-            chal.reverse();
-            let fs_aux = chal;
-            (chal_y, chal_z, fs_aux)
-        };
+        Self::verify_range_inner(pp, ped_pp, comm, n, proof, &[], false)
+    }
 
-        let (chal_x, fs_aux) = {
-            let mut hash_input = Vec::<u8>::new();
-            hash_input.extend_from_slice(&fs_aux);
-            hash_input.append(&mut serialize_group_elem(&proof.comm_lc1));
-            hash_input.append(&mut serialize_group_elem(&proof.comm_lc2));
-            let mut chal = hash_to_variable_output_length::<D>(&hash_input, 32);
-            chal.reverse();  // Reverse to match solidity reading of bytes
-            let chal_x = G::ScalarField::from_random_bytes(&chal[..16]).unwrap();
-            chal.reverse();
-            let fs_aux = chal;
-            (chal_x, fs_aux)
-        };
+    /// Verify a proof from `prove_range_with_ad` against the same `ad`
+    pub fn verify_range_with_ad(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        n: u64,
+        proof: &Proof<G>,
+        ad: &[u8],
+    ) -> Result<bool, Error> {
+        Self::verify_range_inner(pp, ped_pp, comm, n, proof, ad, false)
+    }
 
-        // Verify inner product argument
-        let (mut fs_aux, chal_u) = {
-            let mut hash_input = fs_aux;
-            hash_input.append(&mut serialize_field_elem(&proof.t_x));
-            hash_input.append(&mut serialize_field_elem(&proof.r_t_x));
-            hash_input.append(&mut serialize_field_elem(&proof.r_ab));
-            let mut chal = hash_to_variable_output_length::<D>(&hash_input, 32);
-            chal.reverse();  // Reverse to match solidity reading of bytes
-            let chal_u = G::ScalarField::from_random_bytes(&chal[..16]).unwrap();
-            chal.reverse();
-            (chal, chal_u)
-        };
-        let mut recursive_challenges = Vec::new();
-        for (comm_1, comm_2) in proof.comm_ipa.iter() {
-            let chal_x = {
-                let mut hash_input = Vec::<u8>::new();
-                hash_input.extend_from_slice(&fs_aux);
-                hash_input.append(&mut serialize_group_elem(comm_1));
-                hash_input.append(&mut serialize_group_elem(comm_2));
-                let mut chal = hash_to_variable_output_length::<D>(&hash_input, 32);
-                chal.reverse();  // Reverse to match solidity reading of bytes
-                let chal_x = G::ScalarField::from_random_bytes(&chal[..16]).unwrap();
-                chal.reverse();
-                fs_aux = chal;
-                chal_x
-            };
-            recursive_challenges.push(chal_x);
+    /// Verify range proof using the exact transcript of the solidity BulletproofsVerifier.
+    /// Matches on-chain verification when instantiated with BN254 G1 and Keccak256.
+    pub fn verify_range_evm_equivalent(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        n: u64,
+        proof: &Proof<G>,
+    ) -> Result<bool, Error> {
+        Self::verify_range_inner(pp, ped_pp, comm, n, proof, &[], true)
+    }
+
+    /// Prove comm = g^v * h^opening AND v \in [min, max], where max - min < 2^n
+    pub fn prove_bounded<R: CryptoRng + Rng>(
+        rng: &mut R,
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        v: &BigInt,
+        opening: &G::ScalarField,
+        min: &BigInt,
+        max: &BigInt,
+        n: u64,
+    ) -> Result<BoundedProof<G>, Error> {
+        if v < min || v > max || (max - min).bits() > n {
+            return Err(Box::new(RangeProofError::OutOfBounds));
+        }
+        let (comm_lower, comm_upper) = Self::bounded_comms(ped_pp, comm, min, max)?;
+        let lower = Self::prove_range(rng, pp, ped_pp, &comm_lower, &(v - min), opening, n)?;
+        let upper = Self::prove_range(rng, pp, ped_pp, &comm_upper, &(max - v), &opening.neg(), n)?;
+        Ok(BoundedProof { lower, upper })
+    }
+
+    /// Verify comm = g^v * h^opening AND v \in [min, max]
+    pub fn verify_bounded(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        min: &BigInt,
+        max: &BigInt,
+        n: u64,
+        proof: &BoundedProof<G>,
+    ) -> Result<bool, Error> {
+        let (comm_lower, comm_upper) = Self::bounded_comms(ped_pp, comm, min, max)?;
+        let lower_valid = Self::verify_range(pp, ped_pp, &comm_lower, n, &proof.lower)?;
+        let upper_valid = Self::verify_range(pp, ped_pp, &comm_upper, n, &proof.upper)?;
+        Ok(lower_valid && upper_valid)
+    }
+
+    /// Vector Pedersen commitment g_1^v_1 * ... * g_n^v_n * h^opening over the range proof
+    /// generators
+    pub fn commit_vector(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        values: &[G::ScalarField],
+        opening: &G::ScalarField,
+    ) -> Result<G, Error> {
+        if values.len() > pp.g.len() {
+            return Err(Box::new(RangeProofError::VectorTooLong {
+                len: values.len(),
+                max: pp.g.len(),
+            }));
+        }
+        Ok(Self::vector_comm(pp, ped_pp, values, opening))
+    }
+
+    /// Prove comm = commit_vector(values, opening) AND sum(values) = sum, binding the transcript
+    /// to `ad` as `prove_range_with_ad` does
+    pub fn prove_sum<R: CryptoRng + Rng>(
+        rng: &mut R,
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        values: &[G::ScalarField],
+        opening: &G::ScalarField,
+        ad: &[u8],
+    ) -> Result<SumProof<G>, Error> {
+        let sum = values.iter().fold(G::ScalarField::zero(), |acc, v| acc + v);
+        let masks = (0..values.len())
+            .map(|_| G::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let opening_mask = G::ScalarField::rand(rng);
+        let comm_mask = Self::commit_vector(pp, ped_pp, &masks, &opening_mask)?;
+        let sum_mask = masks.iter().fold(G::ScalarField::zero(), |acc, m| acc + m);
+
+        let chal = Self::sum_challenge(
+            pp,
+            ped_pp,
+            comm,
+            &sum,
+            values.len(),
+            ad,
+            &comm_mask,
+            &sum_mask,
+        );
+        Ok(SumProof {
+            comm_mask,
+            sum_mask,
+            z_values: masks
+                .iter()
+                .zip(values.iter())
+                .map(|(m, v)| *m + &(chal * v))
+                .collect(),
+            z_opening: opening_mask + &(chal * opening),
+        })
+    }
+
+    /// Verify comm commits to a vector of values summing to sum, given the `ad` of `prove_sum`
+    pub fn verify_sum(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        sum: &G::ScalarField,
+        proof: &SumProof<G>,
+        ad: &[u8],
+    ) -> Result<bool, Error> {
+        if proof.z_values.len() > pp.g.len() {
+            return Ok(false);
+        }
+        let chal = Self::sum_challenge(
+            pp,
+            ped_pp,
+            comm,
+            sum,
+            proof.z_values.len(),
+            ad,
+            &proof.comm_mask,
+            &proof.sum_mask,
+        );
+        let z_sum = proof
+            .z_values
+            .iter()
+            .fold(G::ScalarField::zero(), |acc, z| acc + z);
+        let comm_valid = Self::vector_comm(pp, ped_pp, &proof.z_values, &proof.z_opening)
+            == proof.comm_mask + &comm.mul(&chal.into_repr());
+        Ok(comm_valid && z_sum == proof.sum_mask + &(chal * sum))
+    }
+
+    fn vector_comm(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        values: &[G::ScalarField],
+        opening: &G::ScalarField,
+    ) -> G {
+        pp.g.iter()
+            .zip(values.iter())
+            .fold(ped_pp.h.mul(&opening.into_repr()), |acc, (g, v)| {
+                acc + &g.mul(&v.into_repr())
+            })
+    }
+
+    fn sum_challenge(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        sum: &G::ScalarField,
+        len: usize,
+        ad: &[u8],
+        comm_mask: &G,
+        sum_mask: &G::ScalarField,
+    ) -> G::ScalarField {
+        let mut transcript = Transcript::<D>::new();
+        transcript.append_point(&ped_pp.h);
+        for g in pp.g.iter().take(len) {
+            transcript.append_point(g);
         }
+        transcript.append_point(comm);
+        transcript.append_scalar(sum);
+        transcript.append_bytes(&(len as u64).to_be_bytes());
+        transcript.append_bytes(ad);
+        transcript.append_point(comm_mask);
+        transcript.append_scalar(sum_mask);
+        transcript.challenge_scalar::<G::ScalarField>()
+    }
+
+    // Commitments to v - min and max - v
+    fn bounded_comms(
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        min: &BigInt,
+        max: &BigInt,
+    ) -> Result<(G, G), Error> {
+        let f_min = nat_to_f::<G::ScalarField>(min)?;
+        let f_max = nat_to_f::<G::ScalarField>(max)?;
+        Ok((
+            *comm - &ped_pp.g.mul(&f_min.into_repr()),
+            ped_pp.g.mul(&f_max.into_repr()) - comm,
+        ))
+    }
+
+    fn verify_range_inner(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        n: u64,
+        proof: &Proof<G>,
+        ad: &[u8],
+        evm_transcript: bool,
+    ) -> Result<bool, Error> {
+        // Replay prover transcript
+        let (mut transcript, challenges) =
+            Self::verifier_challenges(pp, ped_pp, comm, n, proof, ad);
+        let (chal_y, chal_z, chal_x, chal_u, recursive_challenges) = challenges;
 
         // Prepare single variable base multiexponentiation verification check
         let inverse_y_powers = scalar_powers(n, &chal_y.inverse().unwrap());
@@ -440,12 +666,19 @@ impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
         let two_powers = scalar_powers(n, &G::ScalarField::from(2u128));
 
         // Compute final challenge combination
-        let mut hash_input = Vec::<u8>::new();
-        fs_aux.serialize(&mut hash_input)?;
-        proof.base_a.serialize(&mut hash_input)?;
-        proof.base_b.serialize(&mut hash_input)?;
-        let chal = hash_to_variable_output_length::<D>(&hash_input, 16);
-        let chal_c = G::ScalarField::from_random_bytes(&chal[..]).unwrap();
+        let chal_c = if evm_transcript {
+            // Solidity continues the transcript: keccak256(digest, baseA, baseB)
+            transcript.append_scalar(&proof.base_a);
+            transcript.append_scalar(&proof.base_b);
+            transcript.challenge_scalar::<G::ScalarField>()
+        } else {
+            let mut hash_input = Vec::<u8>::new();
+            transcript.digest().to_vec().serialize(&mut hash_input)?;
+            proof.base_a.serialize(&mut hash_input)?;
+            proof.base_b.serialize(&mut hash_input)?;
+            let chal = hash_to_variable_output_length::<D>(&hash_input, 16);
+            G::ScalarField::from_random_bytes(&chal[..]).unwrap()
+        };
 
         // Linear combination check
         let delta = (chal_z.clone() - chal_z.clone() * &chal_z) * &chal_y_powers.iter().sum()
@@ -512,6 +745,69 @@ impl<G: ProjectiveCurve, D: Digest> Bulletproofs<G, D> {
         );
         Ok(final_check == G::zero())
     }
+
+    /// Transcript absorbing the public parameters and statement
+    fn statement_transcript(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        n: u64,
+        ad: &[u8],
+    ) -> Transcript<D> {
+        // Same state as a fresh transcript after absorbing the parameters and taking a challenge
+        let mut transcript = Transcript::from_digest(&Self::params_fingerprint(pp, ped_pp));
+        transcript.append_point(comm);
+        transcript.append_bytes(&n.to_be_bytes());
+        transcript.append_bytes(ad);
+        transcript
+    }
+
+    /// Returns the transcript and challenges (y, z, x, u, inner product challenges) for a proof
+    fn verifier_challenges(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        n: u64,
+        proof: &Proof<G>,
+        ad: &[u8],
+    ) -> (
+        Transcript<D>,
+        (
+            G::ScalarField,
+            G::ScalarField,
+            G::ScalarField,
+            G::ScalarField,
+            Vec<G::ScalarField>,
+        ),
+    ) {
+        let mut transcript = Self::statement_transcript(pp, ped_pp, comm, n, ad);
+        transcript.append_point(&proof.comm_bits);
+        transcript.append_point(&proof.comm_blind);
+        let (chal_y, chal_z) = transcript.challenge_scalar_pair::<G::ScalarField>();
+
+        transcript.append_point(&proof.comm_lc1);
+        transcript.append_point(&proof.comm_lc2);
+        let chal_x = transcript.challenge_scalar::<G::ScalarField>();
+
+        transcript.append_scalar(&proof.t_x);
+        transcript.append_scalar(&proof.r_t_x);
+        transcript.append_scalar(&proof.r_ab);
+        let chal_u = transcript.challenge_scalar::<G::ScalarField>();
+
+        let recursive_challenges = proof
+            .comm_ipa
+            .iter()
+            .map(|(comm_1, comm_2)| {
+                transcript.append_point(comm_1);
+                transcript.append_point(comm_2);
+                transcript.challenge_scalar::<G::ScalarField>()
+            })
+            .collect::<Vec<_>>();
+        (
+            transcript,
+            (chal_y, chal_z, chal_x, chal_u, recursive_challenges),
+        )
+    }
 }
 
 pub fn scalar_powers<F: PrimeField>(num: u64, s: &F) -> Vec<F> {
@@ -547,6 +843,30 @@ pub fn serialize_field_elem<F: PrimeField>(f: &F) -> Vec<u8> {
     bytes
 }
 
+#[derive(Debug)]
+pub enum RangeProofError {
+    OutOfBounds,
+    VectorTooLong { len: usize, max: usize },
+}
+
+impl ErrorTrait for RangeProofError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for RangeProofError {
+    fn fmt(self: &Self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            RangeProofError::OutOfBounds => format!("value outside of the provable window"),
+            RangeProofError::VectorTooLong { len, max } => {
+                format!("vector of length {} exceeds {} generators", len, max)
+            }
+        };
+        write!(f, "{}", msg)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -555,6 +875,31 @@ mod tests {
 
     use rand::{rngs::StdRng, SeedableRng};
 
+    #[test]
+    fn statement_transcript_fingerprint_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let pp = Bulletproofs::<G, Sha3_256>::gen_params(&mut rng, 8);
+        let comm = G::rand(&mut rng);
+
+        // Absorbing the parameters directly gives the transcript resumed from their fingerprint
+        let mut expected = Transcript::<Sha3_256>::new();
+        expected.append_point(&ped_pp.g);
+        expected.append_point(&ped_pp.h);
+        for g in pp.g.iter().chain(pp.h.iter()) {
+            expected.append_point(g);
+        }
+        expected.append_point(&pp.u);
+        expected.challenge_bytes();
+        expected.append_point(&comm);
+        expected.append_bytes(&8u64.to_be_bytes());
+        expected.append_bytes(b"ad");
+
+        let mut transcript =
+            Bulletproofs::<G, Sha3_256>::statement_transcript(&pp, &ped_pp, &comm, 8, b"ad");
+        assert_eq!(transcript.challenge_bytes(), expected.challenge_bytes());
+    }
+
     #[test]
     fn bulletproofs_verify_test() {
         let mut rng = StdRng::seed_from_u64(0u64);
@@ -571,4 +916,239 @@ mod tests {
             Bulletproofs::<G, Sha3_256>::verify_range(&pp, &ped_pp, &comm, 32, &proof).unwrap()
         );
     }
+
+    #[test]
+    fn bulletproofs_ad_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let pp = Bulletproofs::<G, Sha3_256>::gen_params(&mut rng, 32);
+        let v = BigInt::from(1000);
+        let (comm, opening) =
+            PedersenComm::<G>::commit(&mut rng, &ped_pp, &v.to_bytes_le().1).unwrap();
+        let ad = b"sealed bid".to_vec();
+        let proof = Bulletproofs::<G, Sha3_256>::prove_range_with_ad(
+            &mut rng, &pp, &ped_pp, &comm, &v, &opening, 32, &ad,
+        )
+        .unwrap();
+        assert!(Bulletproofs::<G, Sha3_256>::verify_range_with_ad(
+            &pp, &ped_pp, &comm, 32, &proof, &ad
+        )
+        .unwrap());
+        assert!(!Bulletproofs::<G, Sha3_256>::verify_range_with_ad(
+            &pp,
+            &ped_pp,
+            &comm,
+            32,
+            &proof,
+            b"sealed bie"
+        )
+        .unwrap());
+        assert!(
+            !Bulletproofs::<G, Sha3_256>::verify_range(&pp, &ped_pp, &comm, 32, &proof).unwrap()
+        );
+
+        // Empty ad matches the plain transcript
+        let plain = Bulletproofs::<G, Sha3_256>::prove_range(
+            &mut rng, &pp, &ped_pp, &comm, &v, &opening, 32,
+        )
+        .unwrap();
+        assert!(Bulletproofs::<G, Sha3_256>::verify_range_with_ad(
+            &pp,
+            &ped_pp,
+            &comm,
+            32,
+            &plain,
+            &[]
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn vector_sum_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let pp = Bulletproofs::<G, Sha3_256>::gen_params(&mut rng, 8);
+        type F = <G as ProjectiveCurve>::ScalarField;
+        let values = [300u64, 1200, 45]
+            .iter()
+            .map(|v| F::from(*v))
+            .collect::<Vec<_>>();
+        let opening = F::rand(&mut rng);
+        let comm =
+            Bulletproofs::<G, Sha3_256>::commit_vector(&pp, &ped_pp, &values, &opening).unwrap();
+        let ad = b"bid".to_vec();
+        let proof = Bulletproofs::<G, Sha3_256>::prove_sum(
+            &mut rng, &pp, &ped_pp, &comm, &values, &opening, &ad,
+        )
+        .unwrap();
+        assert!(Bulletproofs::<G, Sha3_256>::verify_sum(
+            &pp,
+            &ped_pp,
+            &comm,
+            &F::from(1545u64),
+            &proof,
+            &ad
+        )
+        .unwrap());
+        assert!(!Bulletproofs::<G, Sha3_256>::verify_sum(
+            &pp,
+            &ped_pp,
+            &comm,
+            &F::from(1546u64),
+            &proof,
+            &ad
+        )
+        .unwrap());
+        assert!(!Bulletproofs::<G, Sha3_256>::verify_sum(
+            &pp,
+            &ped_pp,
+            &comm,
+            &F::from(1545u64),
+            &proof,
+            b"other bid"
+        )
+        .unwrap());
+
+        let long_values = vec![F::one(); 9];
+        assert!(
+            Bulletproofs::<G, Sha3_256>::commit_vector(&pp, &ped_pp, &long_values, &opening)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn bulletproofs_bounded_test() {
+        type BP = Bulletproofs<G, Sha3_256>;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let pp = BP::gen_params(&mut rng, 8);
+        let (min, max) = (BigInt::from(100), BigInt::from(300));
+
+        for v in [100, 200, 300] {
+            let v = BigInt::from(v);
+            let (comm, opening) =
+                PedersenComm::<G>::commit(&mut rng, &ped_pp, &v.to_bytes_le().1).unwrap();
+            let proof =
+                BP::prove_bounded(&mut rng, &pp, &ped_pp, &comm, &v, &opening, &min, &max, 8)
+                    .unwrap();
+            assert!(BP::verify_bounded(&pp, &ped_pp, &comm, &min, &max, 8, &proof).unwrap());
+        }
+
+        let v = BigInt::from(99);
+        let (comm, opening) =
+            PedersenComm::<G>::commit(&mut rng, &ped_pp, &v.to_bytes_le().1).unwrap();
+        assert!(
+            BP::prove_bounded(&mut rng, &pp, &ped_pp, &comm, &v, &opening, &min, &max, 8).is_err()
+        );
+        // A proof for [99, 300] does not convince a verifier of [100, 300]
+        let proof = BP::prove_bounded(
+            &mut rng,
+            &pp,
+            &ped_pp,
+            &comm,
+            &v,
+            &opening,
+            &BigInt::from(99),
+            &max,
+            8,
+        )
+        .unwrap();
+        assert!(!BP::verify_bounded(&pp, &ped_pp, &comm, &min, &max, 8, &proof).unwrap());
+    }
+
+    // Challenges as derived by the inline hashing that predates Transcript
+    fn legacy_challenges(
+        pp: &Params<G>,
+        ped_pp: &PedersenParams<G>,
+        comm: &G,
+        n: u64,
+        proof: &Proof<G>,
+    ) -> Vec<<G as ProjectiveCurve>::ScalarField> {
+        fn chal<G: ProjectiveCurve>(
+            fs_aux: &mut Vec<u8>,
+            hash_input: Vec<u8>,
+        ) -> (G::ScalarField, G::ScalarField) {
+            let mut chal = hash_to_variable_output_length::<Sha3_256>(&hash_input, 32);
+            *fs_aux = chal.clone();
+            chal.reverse();
+            (
+                G::ScalarField::from_random_bytes(&chal[..16]).unwrap(),
+                G::ScalarField::from_random_bytes(&chal[16..]).unwrap(),
+            )
+        }
+
+        let mut hash_input = Vec::<u8>::new();
+        hash_input.append(&mut serialize_group_elem(&ped_pp.g));
+        hash_input.append(&mut serialize_group_elem(&ped_pp.h));
+        for g in pp.g.iter().chain(pp.h.iter()) {
+            hash_input.append(&mut serialize_group_elem(g));
+        }
+        hash_input.append(&mut serialize_group_elem(&pp.u));
+        let mut fs_aux = hash_to_variable_output_length::<Sha3_256>(&hash_input, 32);
+
+        let mut hash_input = fs_aux.clone();
+        hash_input.append(&mut serialize_group_elem(comm));
+        hash_input.extend_from_slice(&n.to_be_bytes());
+        hash_input.append(&mut serialize_group_elem(&proof.comm_bits));
+        hash_input.append(&mut serialize_group_elem(&proof.comm_blind));
+        let (chal_y, chal_z) = chal::<G>(&mut fs_aux, hash_input);
+        let mut challenges = vec![chal_y, chal_z];
+
+        let mut hash_input = fs_aux.clone();
+        hash_input.append(&mut serialize_group_elem(&proof.comm_lc1));
+        hash_input.append(&mut serialize_group_elem(&proof.comm_lc2));
+        challenges.push(chal::<G>(&mut fs_aux, hash_input).0);
+
+        let mut hash_input = fs_aux.clone();
+        hash_input.append(&mut serialize_field_elem(&proof.t_x));
+        hash_input.append(&mut serialize_field_elem(&proof.r_t_x));
+        hash_input.append(&mut serialize_field_elem(&proof.r_ab));
+        challenges.push(chal::<G>(&mut fs_aux, hash_input).0);
+
+        for (comm_1, comm_2) in proof.comm_ipa.iter() {
+            let mut hash_input = fs_aux.clone();
+            hash_input.append(&mut serialize_group_elem(comm_1));
+            hash_input.append(&mut serialize_group_elem(comm_2));
+            challenges.push(chal::<G>(&mut fs_aux, hash_input).0);
+        }
+        challenges
+    }
+
+    #[test]
+    fn transcript_replay_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let pp = Bulletproofs::<G, Sha3_256>::gen_params(&mut rng, 32);
+        let v = BigInt::from(1000);
+        let (comm, opening) =
+            PedersenComm::<G>::commit(&mut rng, &ped_pp, &v.to_bytes_le().1).unwrap();
+        let proof = Bulletproofs::<G, Sha3_256>::prove_range(
+            &mut rng, &pp, &ped_pp, &comm, &v, &opening, 32,
+        )
+        .unwrap();
+
+        let (_, (chal_y, chal_z, chal_x, chal_u, recursive_challenges)) =
+            Bulletproofs::<G, Sha3_256>::verifier_challenges(&pp, &ped_pp, &comm, 32, &proof, &[]);
+        let mut challenges = vec![chal_y, chal_z, chal_x, chal_u];
+        challenges.extend(recursive_challenges);
+        assert_eq!(challenges.len(), 4 + 5);
+        assert_eq!(challenges, legacy_challenges(&pp, &ped_pp, &comm, 32, &proof));
+
+        // Challenges depend on the prover messages
+        let mut tampered = proof.clone();
+        tampered.comm_ipa[4] = (tampered.comm_ipa[4].1, tampered.comm_ipa[4].0);
+        let (_, (.., tampered_challenges)) = Bulletproofs::<G, Sha3_256>::verifier_challenges(
+            &pp,
+            &ped_pp,
+            &comm,
+            32,
+            &tampered,
+            &[],
+        );
+        assert_eq!(tampered_challenges[..4], challenges[4..8]);
+        assert_ne!(tampered_challenges[4], challenges[8]);
+        assert!(
+            !Bulletproofs::<G, Sha3_256>::verify_range(&pp, &ped_pp, &comm, 32, &tampered).unwrap()
+        );
+    }
 }
@@ -1,8 +1,9 @@
 use ark_ff::fields::{FpParameters, PrimeField};
-use num_bigint::BigInt as NumBigInt;
+use num_bigint::{BigInt as NumBigInt, Sign};
 use num_integer::{ExtendedGcd, Integer};
+use rand::Rng;
 
-use num_traits::Signed;
+use num_traits::{One, Signed, ToPrimitive, Zero};
 use std::{
     borrow::Borrow,
     convert::TryInto,
@@ -21,6 +22,83 @@ pub fn extended_euclidean_gcd(a: &BigInt, b: &BigInt) -> ((BigInt, BigInt), BigI
     ((x, y), gcd)
 }
 
+/// Non-negative greatest common divisor, `gcd(0, 0) = 0`
+pub fn gcd(a: &BigInt, b: &BigInt) -> BigInt {
+    Integer::gcd(a, b)
+}
+
+/// Non-negative least common multiple, zero if either operand is zero
+pub fn lcm(a: &BigInt, b: &BigInt) -> BigInt {
+    Integer::lcm(a, b)
+}
+
+/// Number of bits in the magnitude of `n`, zero for zero
+pub fn bit_length(n: &BigInt) -> u64 {
+    n.bits()
+}
+
+/// Number of trailing zero bits in the magnitude of `n`, zero for zero
+pub fn trailing_zeros(n: &BigInt) -> u64 {
+    n.trailing_zeros().unwrap_or(0)
+}
+
+/// Floor of the square root of `n`, `None` for negative `n`
+pub fn isqrt(n: &BigInt) -> Option<BigInt> {
+    if n.is_negative() {
+        None
+    } else {
+        Some(n.sqrt())
+    }
+}
+
+/// Whether `n` is the square of an integer, false for negative `n`
+pub fn is_perfect_square(n: &BigInt) -> bool {
+    isqrt(n).map_or(false, |r| &r * &r == *n)
+}
+
+/// Jacobi symbol `(a / n)` for odd positive `n`
+pub fn jacobi(a: &BigInt, n: &BigInt) -> i32 {
+    assert!(n.is_positive() && n.is_odd());
+    let low_bits = |x: &BigInt| (x & BigInt::from(7)).to_u8().unwrap();
+    let mut a = a.mod_floor(n);
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        let s = trailing_zeros(&a);
+        a >>= s;
+        // (2 / n) = -1 iff n = 3, 5 mod 8
+        if s % 2 == 1 && matches!(low_bits(&n), 3 | 5) {
+            result = -result;
+        }
+        // Quadratic reciprocity
+        if low_bits(&a) % 4 == 3 && low_bits(&n) % 4 == 3 {
+            result = -result;
+        }
+        std::mem::swap(&mut a, &mut n);
+        a = a.mod_floor(&n);
+    }
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+/// Uniform sample in `[0, bound)`, rejecting samples of the byte length of `bound` that are not
+/// below it, so there is no modulo bias. Takes under 256 draws in expectation. Panics for a
+/// non-positive `bound`.
+pub fn random_below<R: Rng>(rng: &mut R, bound: &BigInt) -> BigInt {
+    assert!(bound.is_positive());
+    let mut bytes = vec![0u8; ((bound.bits() + 7) / 8) as usize];
+    loop {
+        rng.fill(&mut bytes[..]);
+        let sample = BigInt::from_bytes_be(Sign::Plus, &bytes);
+        if &sample < bound {
+            return sample;
+        }
+    }
+}
+
 /// Convert a field element to a natural number
 pub fn f_to_nat<F: PrimeField>(f: &F) -> BigInt {
     BigInt::from(f.into_repr().into())
@@ -139,6 +217,154 @@ mod tests {
                           6373289912154831438167899885040445364023527381951378636564391212010397122822\
                           120720357";
 
+    #[test]
+    fn jacobi_test() {
+        // Compare against Euler's criterion over the prime factorization of small odd moduli
+        fn legendre(a: i64, p: i64) -> i32 {
+            match BigInt::from(a).modpow(&BigInt::from((p - 1) / 2), &BigInt::from(p)) {
+                r if r.is_zero() => 0,
+                r if r.is_one() => 1,
+                _ => -1,
+            }
+        }
+        for n in (3..200i64).step_by(2) {
+            let mut factors = vec![];
+            let (mut rem, mut p) = (n, 3);
+            while rem > 1 {
+                while rem % p == 0 {
+                    factors.push(p);
+                    rem /= p;
+                }
+                p += 2;
+            }
+            for a in -5..n {
+                let expected = factors
+                    .iter()
+                    .map(|p| legendre(a.rem_euclid(*p), *p))
+                    .product::<i32>();
+                assert_eq!(jacobi(&BigInt::from(a), &BigInt::from(n)), expected);
+            }
+        }
+        // Modulus is 5 mod 8
+        assert_eq!(
+            jacobi(&BigInt::from(2), &BigInt::from_str(RSA_MODULO).unwrap()),
+            -1
+        );
+    }
+
+    #[test]
+    fn gcd_lcm_test() {
+        let big = |n: i64| BigInt::from(n);
+        // Coprime
+        assert_eq!(gcd(&big(35), &big(64)), big(1));
+        assert_eq!(lcm(&big(35), &big(64)), big(2240));
+        // Sharing a factor, with negative operands
+        assert_eq!(gcd(&big(84), &big(-36)), big(12));
+        assert_eq!(gcd(&big(-84), &big(-36)), big(12));
+        assert_eq!(lcm(&big(-84), &big(36)), big(252));
+        // Zero operands
+        assert_eq!(gcd(&big(0), &big(0)), big(0));
+        assert_eq!(gcd(&big(0), &big(-15)), big(15));
+        assert_eq!(lcm(&big(0), &big(15)), big(0));
+        assert_eq!(lcm(&big(0), &big(0)), big(0));
+
+        let m = BigInt::from_str(RSA_MODULO).unwrap();
+        assert_eq!(gcd(&m, &(&m * 3)), m);
+        assert_eq!(gcd(&m, &(&m - 1)), big(1));
+    }
+
+    #[test]
+    fn bit_length_test() {
+        assert_eq!(bit_length(&BigInt::zero()), 0);
+        assert_eq!(trailing_zeros(&BigInt::zero()), 0);
+        for k in 0..300u64 {
+            let pow = BigInt::one() << k;
+            assert_eq!(bit_length(&pow), k + 1);
+            assert_eq!(bit_length(&(&pow - 1)), k);
+            assert_eq!(trailing_zeros(&pow), k);
+            assert_eq!(bit_length(&-&pow), k + 1);
+            assert_eq!(trailing_zeros(&-&pow), k);
+        }
+        assert_eq!(bit_length(&BigInt::from(255)), 8);
+        assert_eq!(bit_length(&BigInt::from(-256)), 9);
+        assert_eq!(trailing_zeros(&BigInt::from(96)), 5);
+        assert_eq!(trailing_zeros(&BigInt::from(-12345)), 0);
+        let m = BigInt::from_str(RSA_MODULO).unwrap();
+        assert_eq!(bit_length(&m), 2048);
+        assert_eq!(trailing_zeros(&(m << 7)), 7);
+    }
+
+    #[test]
+    fn isqrt_test() {
+        assert_eq!(isqrt(&BigInt::zero()), Some(BigInt::zero()));
+        assert!(is_perfect_square(&BigInt::zero()));
+        assert_eq!(isqrt(&BigInt::from(-1)), None);
+        assert!(!is_perfect_square(&BigInt::from(-4)));
+        for r in 1..200i64 {
+            let sq = BigInt::from(r * r);
+            assert_eq!(isqrt(&sq), Some(BigInt::from(r)));
+            assert!(is_perfect_square(&sq));
+            // Non-squares up to the next square floor to `r`
+            for n in r * r + 1..(r + 1) * (r + 1) {
+                assert_eq!(isqrt(&BigInt::from(n)), Some(BigInt::from(r)));
+                assert!(!is_perfect_square(&BigInt::from(n)));
+            }
+        }
+        let m = BigInt::from_str(RSA_MODULO).unwrap();
+        let m_sq = &m * &m;
+        assert_eq!(isqrt(&m_sq), Some(m.clone()));
+        assert!(is_perfect_square(&m_sq));
+        assert_eq!(isqrt(&(&m_sq - 1)), Some(&m - 1));
+        assert!(!is_perfect_square(&(&m_sq + 1)));
+    }
+
+    #[test]
+    fn random_below_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        assert!((0..10).all(|_| random_below(&mut rng, &BigInt::one()).is_zero()));
+        let m = BigInt::from_str(RSA_MODULO).unwrap();
+        for _ in 0..100 {
+            let sample = random_below(&mut rng, &m);
+            assert!(!sample.is_negative() && sample < m);
+        }
+
+        // Most two byte draws are rejected for this bound, buckets should still be even
+        let bound = BigInt::from(1000);
+        let mut buckets = [0usize; 10];
+        for _ in 0..10000 {
+            let sample = random_below(&mut rng, &bound);
+            assert!(!sample.is_negative() && sample < bound);
+            buckets[(sample / 100).to_usize().unwrap()] += 1;
+        }
+        // Expected 1000 per bucket with standard deviation 30
+        assert!(buckets.iter().all(|n| (850..1150).contains(n)));
+    }
+
+    #[test]
+    fn primitive_conversion_test() {
+        // `BigInt` is num-bigint's, which provides these conversions for all primitive widths
+        for n in [u64::MIN, 1, u64::MAX] {
+            assert_eq!(u64::try_from(BigInt::from(n)).unwrap(), n);
+        }
+        for n in [i64::MIN, -1, 0, i64::MAX] {
+            assert_eq!(i64::try_from(BigInt::from(n)).unwrap(), n);
+        }
+        for n in [u128::MIN, 1, u128::MAX] {
+            assert_eq!(u128::try_from(BigInt::from(n)).unwrap(), n);
+        }
+        for n in [i128::MIN, -1, 0, i128::MAX] {
+            assert_eq!(i128::try_from(BigInt::from(n)).unwrap(), n);
+        }
+        assert_eq!(
+            BigInt::from(u128::MAX),
+            (BigInt::one() << 128) - BigInt::one()
+        );
+        assert_eq!(BigInt::from(i128::MIN), -(BigInt::one() << 127));
+        assert!(u64::try_from(BigInt::from(u64::MAX) + 1).is_err());
+        assert!(u64::try_from(BigInt::from(-1)).is_err());
+        assert!(i64::try_from(BigInt::from(i64::MIN) - 1).is_err());
+    }
+
     #[test]
     fn convert_to_field_test() {
         let mut rng = StdRng::seed_from_u64(0u64);
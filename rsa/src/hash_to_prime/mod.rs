@@ -16,12 +16,28 @@ pub trait HashToPrime: Clone + Eq {
 
     fn hash_to_prime(entropy: usize, input: &[u8]) -> Result<(BigInt, Self::Certificate), Error>;
 
+    /// Same as `hash_to_prime`, also returning the number of candidates hashed
+    fn hash_to_prime_counted(
+        entropy: usize,
+        input: &[u8],
+    ) -> Result<(BigInt, Self::Certificate, usize), Error>;
+
     fn verify_hash_to_prime(
         entropy: usize,
         input: &[u8],
         p: &BigInt,
         cert: &Self::Certificate,
     ) -> Result<bool, Error>;
+
+    /// Bit length of the candidates hashed for the given entropy
+    fn candidate_bits(entropy: usize) -> usize;
+
+    /// Expected number of candidates hashed before one is accepted, from the density of primes
+    /// among odd candidates. Schemes that also reject some primes need more on average.
+    fn expected_iterations(entropy: usize) -> f64 {
+        // An odd n bit integer is prime with probability ~ 2 / (n ln 2)
+        Self::candidate_bits(entropy) as f64 * 2f64.ln() / 2f64
+    }
 }
 
 pub struct MillerRabinRejectionSample<D: Digest> {
@@ -49,6 +65,14 @@ impl<D: Digest> HashToPrime for MillerRabinRejectionSample<D> {
         hash_to_prime::<D>(input, Self::prime_bits(entropy))
     }
 
+    fn hash_to_prime_counted(
+        entropy: usize,
+        input: &[u8],
+    ) -> Result<(BigInt, Self::Certificate, usize), Error> {
+        let (p, nonce) = Self::hash_to_prime(entropy, input)?;
+        Ok((p, nonce, nonce as usize + 1))
+    }
+
     fn verify_hash_to_prime(
         entropy: usize,
         input: &[u8],
@@ -60,6 +84,10 @@ impl<D: Digest> HashToPrime for MillerRabinRejectionSample<D> {
         let p_comp = hash_to_integer::<D>(&input, Self::prime_bits(entropy));
         Ok(p == &p_comp && miller_rabin(p, 30))
     }
+
+    fn candidate_bits(entropy: usize) -> usize {
+        Self::prime_bits(entropy)
+    }
 }
 
 impl<D: Digest> MillerRabinRejectionSample<D> {
@@ -137,8 +165,11 @@ pub fn hash_to_prime<D: Digest>(inputs: &[u8], n_bits: usize) -> Result<(BigInt,
     Err(Box::new(HashToPrimeError::NoValidNonce))
 }
 
+/// Returns an odd integer in `[2^(n_bits - 1), 2^n_bits)`, i.e. with exactly `n_bits` bits.
+/// The hash output is rounded up to whole bytes, so the excess high order bits are masked off
+/// rather than reduced modulo the range to keep the candidate uniform over the range.
 pub fn hash_to_integer<D: Digest>(inputs: &[u8], n_bits: usize) -> BigInt {
-    assert!(n_bits > 0);
+    assert!(n_bits > 1);
     let mut n = BigInt::from_bytes_be(
         Sign::Plus,
         &hash_to_variable_output_length::<D>(inputs, ((n_bits - 1) / 8) + 1),
@@ -147,6 +178,8 @@ pub fn hash_to_integer<D: Digest>(inputs: &[u8], n_bits: usize) -> BigInt {
     let mask = (BigInt::one() << n_bits) - BigInt::one();
     n &= mask;
     n.set_bit(n_bits as u64 - 1, true);
+    // Even candidates are never prime
+    n.set_bit(0, true);
     n
 }
 
@@ -210,6 +243,27 @@ mod tests {
         assert!(h < BigInt::one() << 13);
     }
 
+    #[test]
+    fn hash_to_integer_range_test() {
+        // Non byte aligned sizes, including the 277 bit candidates verified in solidity
+        for n_bits in [13usize, 21, 277] {
+            let low = BigInt::one() << (n_bits - 1);
+            let high = BigInt::one() << n_bits;
+            // Bucket candidates by the 3 bits below the fixed high bit
+            let mut buckets = [0usize; 8];
+            for seed in 0..2000u32 {
+                let h = hash_to_integer::<Sha3_256>(&seed.to_le_bytes(), n_bits);
+                assert!(h >= low);
+                assert!(h < high);
+                assert!(h.is_odd());
+                let bucket: usize = ((&h - &low) >> (n_bits - 4)).try_into().unwrap();
+                buckets[bucket] += 1;
+            }
+            // Expect 250 per bucket, allow wide statistical margin
+            assert!(buckets.iter().all(|b| *b > 150 && *b < 350));
+        }
+    }
+
     #[test]
     fn miller_rabin_rejection_sample_prime_test() {
         let (h, cert) =
@@ -222,6 +276,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn expected_iterations_test() {
+        let entropy = 64;
+        let n_inputs = 500u32;
+        let total = (0..n_inputs)
+            .map(|seed| {
+                let (_, nonce, count) =
+                    MillerRabinRejectionSample::<Sha3_256>::hash_to_prime_counted(
+                        entropy,
+                        &seed.to_le_bytes(),
+                    )
+                    .unwrap();
+                assert_eq!(count, nonce as usize + 1);
+                count
+            })
+            .sum::<usize>();
+        let mean = total as f64 / n_inputs as f64;
+        let expected = MillerRabinRejectionSample::<Sha3_256>::expected_iterations(entropy);
+        assert!(mean > expected / 2f64 && mean < expected * 2f64);
+    }
+
     #[test]
     fn miller_rabin_32b_test() {
         let p = BigInt::from_str("42589817").unwrap();
@@ -1,5 +1,5 @@
 use crate::{
-    bigint::{extended_euclidean_gcd, BigInt},
+    bigint::{extended_euclidean_gcd, is_perfect_square, isqrt, BigInt},
     hash_to_prime::{
         hash_to_integer, miller_rabin, miller_rabin_32b, HashToPrime, HashToPrimeError,
     },
@@ -20,6 +20,9 @@ pub trait PocklingtonCertParams: Clone + Eq + Debug + Send + Sync {
     const INCLUDE_SOLIDITY_WITNESSES: bool; // flag to include witnesses for solidity verification
 }
 
+/// Hash to prime with a Pocklington certificate of primality. Any digest works off-chain, but the
+/// Solidity PoE verifier recomputes candidates with keccak256, so certificates generated with
+/// another digest such as SHA-256 will not verify against it.
 pub struct PocklingtonHash<P: PocklingtonCertParams, D: Digest> {
     _params: PhantomData<P>,
     _hash: PhantomData<D>,
@@ -112,6 +115,15 @@ impl<P: PocklingtonCertParams, D: Digest> HashToPrime for PocklingtonHash<P, D>
         Err(Box::new(HashToPrimeError::NoValidNonce))
     }
 
+    fn hash_to_prime_counted(
+        entropy: usize,
+        input: &[u8],
+    ) -> Result<(BigInt, Self::Certificate, usize), Error> {
+        let (p, cert) = Self::hash_to_prime(entropy, input)?;
+        let count = cert.nonce as usize + 1;
+        Ok((p, cert, count))
+    }
+
     fn verify_hash_to_prime(
         entropy: usize,
         input: &[u8],
@@ -142,6 +154,10 @@ impl<P: PocklingtonCertParams, D: Digest> HashToPrime for PocklingtonHash<P, D>
         let check3 = p_last.bits() < 32 && miller_rabin_32b(&p_last);
         Ok(check1 && check2 && check3)
     }
+
+    fn candidate_bits(entropy: usize) -> usize {
+        Self::prime_bits(entropy)
+    }
 }
 
 impl<P: PocklingtonCertParams, D: Digest> PocklingtonHash<P, D> {
@@ -279,16 +295,11 @@ impl<P: PocklingtonCertParams, D: Digest> PocklingtonHash<P, D> {
         let s = v.div_floor(&(BigInt::from(2) * &u));
 
         let expr = r.pow(2) - BigInt::from(8) * &s;
-        let expr_sqrt = if expr >= BigInt::zero() {
-            expr.sqrt()
-        } else {
-            BigInt::zero()
-        };
+        let expr_sqrt = isqrt(&expr).unwrap_or_else(BigInt::zero);
         let test1 = &((&u + BigInt::one())
             * (BigInt::from(2) * u.pow(2) + (&r - BigInt::one()) * &u + BigInt::one()))
             > p;
-        let test2 =
-            (s == BigInt::zero()) || (expr >= BigInt::zero() && !(expr_sqrt.pow(2) == expr));
+        let test2 = (s == BigInt::zero()) || (expr >= BigInt::zero() && !is_perfect_square(&expr));
         if test1 && test2 {
             Some((f.clone(), n, u, v, s, expr_sqrt))
         } else {
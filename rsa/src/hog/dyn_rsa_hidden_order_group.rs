@@ -0,0 +1,152 @@
+use crate::bigint::{extended_euclidean_gcd, gcd, BigInt};
+use crate::hog::RsaHOGError;
+use num_traits::{One, Signed, Zero};
+
+use std::{cmp::min, sync::Arc};
+
+use crate::Error;
+
+// Runtime counterpart of `RsaGroupParams`, e.g. for a modulus loaded from a trusted setup file
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DynRsaGroupParams {
+    pub g: BigInt, // generator
+    pub m: BigInt, // modulus
+}
+
+impl DynRsaGroupParams {
+    pub fn new(g: BigInt, m: BigInt) -> Result<Arc<Self>, Error> {
+        if m <= BigInt::from(2) || g <= BigInt::zero() || g >= m {
+            return Err(Box::new(RsaHOGError::OutOfRange));
+        }
+        Ok(Arc::new(Self { g, m }))
+    }
+}
+
+// Same group as `RsaHiddenOrderGroup`, with the parameters carried by each element
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DynRsaHiddenOrderGroup { // the group QR_M^+ := { |x| : x \in QR_M }
+    pub n: BigInt,
+    params: Arc<DynRsaGroupParams>,
+}
+
+impl DynRsaHiddenOrderGroup {
+    fn reduce(params: &Arc<DynRsaGroupParams>, a: BigInt) -> Self { // canonical min(a, M-a) of a in [0, M)
+        let ma = &params.m - &a;
+        DynRsaHiddenOrderGroup {
+            n: min(a, ma),
+            params: params.clone(),
+        }
+    }
+
+    pub fn from_nat(params: &Arc<DynRsaGroupParams>, n: BigInt) -> Self { // return group element from BigInt
+        assert!(n > BigInt::zero());
+        Self::reduce(params, n % &params.m)
+    }
+
+    pub fn params(&self) -> &Arc<DynRsaGroupParams> {
+        &self.params
+    }
+
+    pub fn op(&self, other: &Self) -> Self { // operation between two group elements
+        debug_assert_eq!(self.params, other.params);
+        Self::reduce(&self.params, (&self.n * &other.n) % &self.params.m)
+    }
+
+    pub fn identity(params: &Arc<DynRsaGroupParams>) -> Self { // return 1
+        DynRsaHiddenOrderGroup {
+            n: BigInt::one(),
+            params: params.clone(),
+        }
+    }
+
+    pub fn generator(params: &Arc<DynRsaGroupParams>) -> Self { // return generator
+        Self::from_nat(params, params.g.clone())
+    }
+
+    // Assumes `e` is non-negative; use `try_power` for possibly negative exponents
+    pub fn power(&self, e: &BigInt) -> Self { // return n^e
+        Self::reduce(&self.params, self.n.modpow(e, &self.params.m))
+    }
+
+    pub fn try_power(&self, e: &BigInt) -> Result<Self, Error> { // return n^e, inverting for e < 0
+        if e.is_negative() {
+            Ok(self.inverse()?.power(&e.abs()))
+        } else {
+            Ok(self.power(e))
+        }
+    }
+
+    pub fn inverse(&self) -> Result<Self, Error> {
+        if !gcd(&self.n, &self.params.m).is_one() {
+            return Err(Box::new(RsaHOGError::NotInvertible));
+        }
+        let ((mut inv, _), _) = extended_euclidean_gcd(&self.n, &self.params.m);
+        if inv < BigInt::zero() {
+            inv += &self.params.m;
+        }
+        Ok(Self::from_nat(&self.params, inv))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hog::{RsaGroupParams, RsaHiddenOrderGroup};
+    use once_cell::sync::Lazy;
+    use std::{ops::Deref, str::FromStr};
+
+    const RSA_MODULO: &str = "2519590847565789349402718324004839857142928212620403202777713783604366202070\
+                          7595556264018525880784406918290641249515082189298559149176184502808489120072\
+                          8449926873928072877767359714183472702618963750149718246911650776133798590957\
+                          0009733045974880842840179742910064245869181719511874612151517265463228221686\
+                          9987549182422433637259085141865462043576798423387184774447920739934236584823\
+                          8242811981638150106748104516603773060562016196762561338441436038339044149526\
+                          3443219011465754445417842402092461651572335077870774981712577246796292638635\
+                          6373289912154831438167899885040445364023527381951378636564391212010397122822\
+                          120720357";
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct TestRsaParams;
+
+    impl RsaGroupParams for TestRsaParams {
+        const G: Lazy<BigInt> = Lazy::new(|| BigInt::from(2));
+        const M: Lazy<BigInt> = Lazy::new(|| BigInt::from_str(RSA_MODULO).unwrap());
+    }
+
+    pub type Hog = RsaHiddenOrderGroup<TestRsaParams>;
+
+    #[test]
+    fn dyn_group_test() {
+        let params =
+            DynRsaGroupParams::new(BigInt::from(2), BigInt::from_str(RSA_MODULO).unwrap()).unwrap();
+        let a = DynRsaHiddenOrderGroup::from_nat(&params, BigInt::from(30));
+        let b = DynRsaHiddenOrderGroup::from_nat(&params, BigInt::from(-7) + &params.m);
+        let inv_a = a.inverse().unwrap();
+        assert_eq!(a.op(&inv_a), DynRsaHiddenOrderGroup::identity(&params));
+        assert_eq!(a.op(&b), b.op(&a));
+
+        // Agrees with the const-parameter group over the same modulus
+        let e = BigInt::from(12345);
+        let g = DynRsaHiddenOrderGroup::generator(&params);
+        assert_eq!(g.power(&e).n, Hog::generator().power(&e).n);
+        let const_a = Hog::from_nat(BigInt::from(30));
+        assert_eq!(a.op(&b).n, const_a.op(&Hog::from_nat(BigInt::from(7))).n);
+        assert_eq!(
+            a.try_power(&-&e).unwrap().n,
+            const_a.try_power(&-&e).unwrap().n
+        );
+        assert_eq!(inv_a.n, const_a.inverse().unwrap().n);
+
+        // Small runtime modulus 3233 = 61 * 53
+        let small = DynRsaGroupParams::new(BigInt::from(4), BigInt::from(3233)).unwrap();
+        let x = DynRsaHiddenOrderGroup::from_nat(&small, BigInt::from(100));
+        assert_eq!(x.op(&x.inverse().unwrap()).n, BigInt::one());
+        assert!(DynRsaHiddenOrderGroup::from_nat(&small, BigInt::from(61))
+            .inverse()
+            .is_err());
+        assert_eq!(TestRsaParams::M.deref(), &params.m);
+
+        assert!(DynRsaGroupParams::new(BigInt::from(0), BigInt::from(3233)).is_err());
+        assert!(DynRsaGroupParams::new(BigInt::from(3233), BigInt::from(3233)).is_err());
+    }
+}
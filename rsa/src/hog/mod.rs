@@ -7,9 +7,11 @@ use std::{
 };
 
 pub mod constraints;
+pub mod dyn_rsa_hidden_order_group;
 pub mod rsa_hidden_order_group;
 pub mod unsigned_rsa_hidden_order_group;
 
+pub use dyn_rsa_hidden_order_group::{DynRsaGroupParams, DynRsaHiddenOrderGroup};
 pub use rsa_hidden_order_group::RsaHiddenOrderGroup;
 pub use unsigned_rsa_hidden_order_group::UnsignedRsaHiddenOrderGroup;
 
@@ -19,6 +21,12 @@ pub trait RsaGroupParams: Clone + Eq + Debug + Send + Sync {
     const M: Lazy<BigInt>; // modulus
 }
 
+// Parameters with a known factorization M = P * Q, for test and benchmark setups only
+pub trait RsaGroupParamsWithFactors: RsaGroupParams {
+    const P: Lazy<BigInt>;
+    const Q: Lazy<BigInt>;
+}
+
 pub trait UnsignedRsaGroupParams: Clone + Eq + Debug + Send + Sync {
     const G: Option<Lazy<BigInt>>; // generator (optional for cyclic groups)
     const M: Lazy<BigInt>; // modulus
@@ -28,6 +36,7 @@ pub trait UnsignedRsaGroupParams: Clone + Eq + Debug + Send + Sync {
 pub enum RsaHOGError {
     NotInvertible,
     NotCyclic,
+    OutOfRange,
 }
 
 impl ErrorTrait for RsaHOGError {
@@ -41,6 +50,7 @@ impl fmt::Display for RsaHOGError {
         let msg = match self {
             RsaHOGError::NotInvertible => format!("Group element not invertible"),
             RsaHOGError::NotCyclic => format!("Group is not cyclic, missing generator"),
+            RsaHOGError::OutOfRange => format!("Encoded integer not in range (0, M)"),
         };
         write!(f, "{}", msg)
     }
@@ -1,9 +1,13 @@
-use crate::bigint::{extended_euclidean_gcd, BigInt};
-use crate::hog::{RsaGroupParams, RsaHOGError};
+use crate::bigint::{bit_length, extended_euclidean_gcd, gcd, jacobi, random_below, BigInt};
+use crate::hog::{RsaGroupParams, RsaGroupParamsWithFactors, RsaHOGError};
+use num_bigint::Sign;
+use num_integer::Integer;
 use num_traits::{One, Signed, Zero};
+use rand::Rng;
+use subtle::{Choice, ConditionallySelectable};
 
 use std::{
-    cmp::min,
+    cmp::{min, Ordering},
     hash::{Hash, Hasher},
     marker::PhantomData,
     ops::Deref,
@@ -29,6 +33,18 @@ impl<P: RsaGroupParams> Hash for RsaHiddenOrderGroup<P> {
     }
 }
 
+impl<P: RsaGroupParams> PartialOrd for RsaHiddenOrderGroup<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: RsaGroupParams> Ord for RsaHiddenOrderGroup<P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.n.cmp(&other.n)
+    }
+}
+
 impl<P: RsaGroupParams> RsaHiddenOrderGroup<P> {
     pub fn from_nat(n: BigInt) -> Self { // return group element from BigInt
         let mut a = n;
@@ -42,6 +58,16 @@ impl<P: RsaGroupParams> RsaHiddenOrderGroup<P> {
         }
     }
 
+    pub fn random<R: Rng>(rng: &mut R) -> Self { // canonicalized r^2 for uniform nonzero r < M, in QR_M^+
+        let r = loop {
+            let r = random_below(rng, P::M.deref());
+            if !r.is_zero() {
+                break r;
+            }
+        };
+        Self::from_nat(&r * &r)
+    }
+
     pub fn op(&self, other: &Self) -> Self { // operation between two group elements
         let mut a = self.n.clone();
         a *= &other.n;
@@ -61,6 +87,10 @@ impl<P: RsaGroupParams> RsaHiddenOrderGroup<P> {
         }
     }
 
+    pub fn product(elems: &[Self]) -> Self { // fold op over elems, identity for an empty slice
+        elems.iter().fold(Self::identity(), |acc, e| acc.op(e))
+    }
+
     pub fn generator() -> Self { // return generator
         RsaHiddenOrderGroup {
             n: P::G.deref().clone(),
@@ -68,6 +98,7 @@ impl<P: RsaGroupParams> RsaHiddenOrderGroup<P> {
         }
     }
 
+    // Assumes `e` is non-negative; use `try_power` for possibly negative exponents
     pub fn power(&self, e: &BigInt) -> Self { // return n^e
         let r = self.n.modpow(e, P::M.deref());
         let mut mr = P::M.deref().clone();
@@ -78,12 +109,60 @@ impl<P: RsaGroupParams> RsaHiddenOrderGroup<P> {
         }
     }
 
+    pub fn try_power(&self, e: &BigInt) -> Result<Self, Error> { // return n^e, inverting for e < 0
+        if e.is_negative() {
+            Ok(self.inverse()?.power(&e.abs()))
+        } else {
+            Ok(self.power(e))
+        }
+    }
+
+    // Only valid if the order of the element divides `order`, e.g. a multiple of the group order
+    pub fn power_with_order(&self, e: &BigInt, order: &BigInt) -> Self { // return n^(e mod order)
+        self.power(&e.mod_floor(order))
+    }
+
+    pub fn to_bytes_be(&self) -> Vec<u8> { // fixed-width encoding, padded to modulus byte length
+        let n_bytes = ((bit_length(&P::M) + 7) / 8) as usize;
+        let bytes = self.n.to_bytes_be().1;
+        let mut out = vec![0u8; n_bytes - bytes.len()];
+        out.extend(bytes);
+        out
+    }
+
+    pub fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self { // a if choice = 0, b if choice = 1, without branching on choice
+        let bytes = a
+            .to_bytes_be()
+            .iter()
+            .zip(b.to_bytes_be().iter())
+            .map(|(x, y)| u8::conditional_select(x, y, choice))
+            .collect::<Vec<u8>>();
+        RsaHiddenOrderGroup {
+            n: BigInt::from_bytes_be(Sign::Plus, &bytes),
+            _params: PhantomData,
+        }
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, Error> { // decode, applying canonical min(a, M-a)
+        let n = BigInt::from_bytes_be(Sign::Plus, bytes);
+        if n <= BigInt::zero() || &n >= P::M.deref() {
+            return Err(Box::new(RsaHOGError::OutOfRange));
+        }
+        Ok(Self::from_nat(n))
+    }
+
+    // Necessary condition for membership in QR_M^+, checkable without the factorization of M
+    pub fn is_canonical_element(&self) -> bool {
+        let m = P::M.deref();
+        self.n > BigInt::zero() && &self.n + &self.n < *m && jacobi(&self.n, m) == 1
+    }
+
     //TODO: Optimization for only calculating needed Bezout coefficient
     pub fn inverse(&self) -> Result<Self, Error> {
-        let ((mut inv, _), gcd) = extended_euclidean_gcd(&self.n, P::M.deref());
-        if gcd.abs() > BigInt::one() {
+        if !gcd(&self.n, P::M.deref()).is_one() {
             return Err(Box::new(RsaHOGError::NotInvertible));
         }
+        let ((mut inv, _), _) = extended_euclidean_gcd(&self.n, P::M.deref());
         if inv < BigInt::zero() {
             inv += P::M.deref();
         }
@@ -91,6 +170,33 @@ impl<P: RsaGroupParams> RsaHiddenOrderGroup<P> {
     }
 }
 
+impl<P: RsaGroupParamsWithFactors> RsaHiddenOrderGroup<P> {
+    // Computes the same result as `power` by exponentiating mod P and Q and recombining
+    pub fn power_crt(&self, e: &BigInt) -> Self { // return n^e
+        fn power_mod_prime(n: &BigInt, e: &BigInt, p: &BigInt) -> BigInt {
+            let n_p = n.mod_floor(p);
+            if n_p.is_zero() {
+                return if e.is_zero() { BigInt::one() } else { n_p };
+            }
+            // Fermat: reduce exponent mod p - 1, keeping e > 0 distinguishable from e = 0
+            n_p.modpow(&e.mod_floor(&(p - BigInt::one())), p)
+        }
+
+        let (p, q) = (P::P.deref(), P::Q.deref());
+        let r_p = power_mod_prime(&self.n, e, p);
+        let r_q = power_mod_prime(&self.n, e, q);
+        let ((q_inv, _), _) = extended_euclidean_gcd(q, p);
+        let h = ((r_p - &r_q) * q_inv).mod_floor(p);
+        let r = r_q + h * q;
+        let mut mr = P::M.deref().clone();
+        mr -= &r;
+        RsaHiddenOrderGroup {
+            n: min(r, mr),
+            _params: PhantomData,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,4 +233,150 @@ mod tests {
         let inv_a = a.inverse().unwrap();
         assert_eq!(a.op(&inv_a).n, BigInt::from(1));
     }
+
+    #[test]
+    fn product_test() {
+        assert_eq!(Hog::product(&[]), Hog::identity());
+        let elems: Vec<Hog> = [30, 7, 40].iter().map(|x| Hog::from_nat(BigInt::from(*x))).collect();
+        let manual = elems[0].op(&elems[1]).op(&elems[2]);
+        assert_eq!(Hog::product(&elems), manual);
+        assert_eq!(Hog::product(&elems[..1]), elems[0]);
+    }
+
+    #[test]
+    fn conditional_select_test() {
+        let a = Hog::from_nat(BigInt::from(30));
+        let b = Hog::generator().power(&BigInt::from(12345));
+        assert_eq!(Hog::conditional_select(&a, &b, Choice::from(0)), a);
+        assert_eq!(Hog::conditional_select(&a, &b, Choice::from(1)), b);
+        assert_eq!(Hog::conditional_select(&b, &a, Choice::from(0)), b);
+        assert_eq!(Hog::conditional_select(&a, &a, Choice::from(1)), a);
+        let id = Hog::identity();
+        assert_eq!(Hog::conditional_select(&id, &b, Choice::from(0)), id);
+    }
+
+    #[test]
+    fn try_power_test() {
+        let a = Hog::from_nat(BigInt::from(30));
+        let e = BigInt::from(12345);
+        assert_eq!(a.try_power(&e).unwrap(), a.power(&e));
+        assert_eq!(a.try_power(&-&e).unwrap(), a.inverse().unwrap().power(&e));
+        assert_eq!(a.try_power(&-&e).unwrap().op(&a.power(&e)), Hog::identity());
+
+        // 23 is not invertible modulo 23 * 47
+        let b = RsaHiddenOrderGroup::<ToyRsaParams>::from_nat(BigInt::from(23));
+        assert!(b.try_power(&BigInt::from(-3)).is_err());
+        assert!(b.try_power(&BigInt::from(3)).is_ok());
+    }
+
+    #[test]
+    fn is_canonical_element_test() {
+        assert!(Hog::generator().power(&BigInt::from(2)).is_canonical_element());
+        // Squares have Jacobi symbol 1
+        let a = Hog::from_nat(BigInt::from(30)).power(&BigInt::from(2 * 12345));
+        assert!(a.is_canonical_element());
+        // M = 5 mod 8, so 2 has Jacobi symbol -1
+        assert!(!Hog::generator().is_canonical_element());
+        let non_canonical = Hog {
+            n: TestRsaParams::M.deref() - BigInt::from(4),
+            _params: PhantomData,
+        };
+        assert!(!non_canonical.is_canonical_element());
+    }
+
+    #[test]
+    fn sort_dedup_test() {
+        let a = Hog::from_nat(BigInt::from(30));
+        let b = Hog::from_nat(BigInt::from(7));
+        let c = Hog::from_nat(BigInt::from(-30) + TestRsaParams::M.deref()); // equal to a
+        let mut v = vec![a.clone(), b.clone(), c, Hog::identity()];
+        v.sort();
+        v.dedup();
+        assert_eq!(v, vec![Hog::identity(), b, a]);
+    }
+
+    #[test]
+    fn random_test() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let elems: Vec<Hog> = (0..10).map(|_| Hog::random(&mut rng)).collect();
+        for a in elems.iter() {
+            assert!(a.n > BigInt::zero());
+            assert!(&a.n + &a.n <= *TestRsaParams::M);
+            assert_eq!(Hog::from_nat(a.n.clone()), *a);
+            // Squares have Jacobi symbol 1, as does -1 since M = 1 mod 4
+            assert_eq!(jacobi(&a.n, &TestRsaParams::M), 1);
+        }
+        let mut distinct = elems.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), elems.len());
+
+        let a = Hog::random(&mut rng);
+        let b = Hog::random(&mut rng);
+        assert_eq!(a.op(&a.inverse().unwrap()), Hog::identity());
+        assert_eq!(a.op(&b).op(&b.inverse().unwrap()), a);
+    }
+
+    #[test]
+    fn bytes_round_trip_test() {
+        let a = Hog::from_nat(BigInt::from(30));
+        let bytes = a.to_bytes_be();
+        assert_eq!(bytes.len(), 256);
+        assert!(bytes[..255].iter().all(|b| *b == 0));
+        assert_eq!(bytes[255], 30);
+        assert_eq!(Hog::from_bytes_be(&bytes).unwrap(), a);
+
+        let neg_a = (TestRsaParams::M.deref() - BigInt::from(30))
+            .to_bytes_be()
+            .1;
+        assert_eq!(Hog::from_bytes_be(&neg_a).unwrap(), a);
+
+        assert!(Hog::from_bytes_be(&[0u8; 256]).is_err());
+        assert!(Hog::from_bytes_be(&TestRsaParams::M.to_bytes_be().1).is_err());
+    }
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct ToyRsaParams;
+
+    impl RsaGroupParams for ToyRsaParams {
+        const G: Lazy<BigInt> = Lazy::new(|| BigInt::from(2));
+        const M: Lazy<BigInt> = Lazy::new(|| BigInt::from(23 * 47));
+    }
+
+    impl RsaGroupParamsWithFactors for ToyRsaParams {
+        const P: Lazy<BigInt> = Lazy::new(|| BigInt::from(23));
+        const Q: Lazy<BigInt> = Lazy::new(|| BigInt::from(47));
+    }
+
+    #[test]
+    fn power_crt_test() {
+        use num_bigint::RandomBits;
+        use rand::{distributions::Distribution, rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        // Include elements sharing a factor with the modulus
+        for n in [2, 5, 23, 47, 100, 1080] {
+            let x = RsaHiddenOrderGroup::<ToyRsaParams>::from_nat(BigInt::from(n));
+            assert_eq!(x.power_crt(&BigInt::zero()), x.power(&BigInt::zero()));
+            for _ in 0..20 {
+                let e = BigInt::from_biguint(Sign::Plus, RandomBits::new(128).sample(&mut rng));
+                assert_eq!(x.power_crt(&e), x.power(&e));
+            }
+        }
+    }
+
+    #[test]
+    fn power_with_order_test() {
+        // Carmichael function of 23 * 47, a multiple of the order of every element
+        let order = BigInt::from(22 * 46 / 2);
+        let g = RsaHiddenOrderGroup::<ToyRsaParams>::generator();
+        let big_exp = (BigInt::one() << 300) + BigInt::from(12345);
+        assert_eq!(g.power_with_order(&big_exp, &order), g.power(&big_exp));
+        assert_eq!(
+            g.power_with_order(&(&order * BigInt::from(7)), &order),
+            RsaHiddenOrderGroup::<ToyRsaParams>::identity()
+        );
+    }
 }
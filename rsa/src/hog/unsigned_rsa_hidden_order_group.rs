@@ -1,8 +1,11 @@
-use crate::bigint::{extended_euclidean_gcd, BigInt};
+use crate::bigint::{bit_length, extended_euclidean_gcd, random_below, BigInt};
 use crate::hog::{UnsignedRsaGroupParams, RsaHOGError};
+use num_bigint::Sign;
+use rand::Rng;
 use num_traits::{One, Signed, Zero};
 
 use std::{
+    cmp::Ordering,
     hash::{Hash, Hasher},
     marker::PhantomData,
     ops::Deref,
@@ -28,6 +31,18 @@ impl<P: UnsignedRsaGroupParams> Hash for UnsignedRsaHiddenOrderGroup<P> {
     }
 }
 
+impl<P: UnsignedRsaGroupParams> PartialOrd for UnsignedRsaHiddenOrderGroup<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: UnsignedRsaGroupParams> Ord for UnsignedRsaHiddenOrderGroup<P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.n.cmp(&other.n)
+    }
+}
+
 impl<P: UnsignedRsaGroupParams> UnsignedRsaHiddenOrderGroup<P> {
     pub fn from_nat(n: BigInt) -> Self { // return group element from BigInt
         let mut a = n;
@@ -39,6 +54,15 @@ impl<P: UnsignedRsaGroupParams> UnsignedRsaHiddenOrderGroup<P> {
         }
     }
 
+    pub fn random<R: Rng>(rng: &mut R) -> Self { // uniform residue in [1, M)
+        loop {
+            let r = random_below(rng, P::M.deref());
+            if !r.is_zero() {
+                return Self::from_nat(r);
+            }
+        }
+    }
+
     pub fn op(&self, other: &Self) -> Self { // operation between two group elements
         let mut a = self.n.clone();
         a *= &other.n;
@@ -56,6 +80,10 @@ impl<P: UnsignedRsaGroupParams> UnsignedRsaHiddenOrderGroup<P> {
         }
     }
 
+    pub fn product(elems: &[Self]) -> Self { // fold op over elems, identity for an empty slice
+        elems.iter().fold(Self::identity(), |acc, e| acc.op(e))
+    }
+
     pub fn generator() -> Result<Self, Error> { // return generator
         match P::G {
             Some(g) => Ok(Self::from_nat(g.deref().clone())),
@@ -71,6 +99,22 @@ impl<P: UnsignedRsaGroupParams> UnsignedRsaHiddenOrderGroup<P> {
         }
     }
 
+    pub fn to_bytes_be(&self) -> Vec<u8> { // fixed-width encoding, padded to modulus byte length
+        let n_bytes = ((bit_length(&P::M) + 7) / 8) as usize;
+        let bytes = self.n.to_bytes_be().1;
+        let mut out = vec![0u8; n_bytes - bytes.len()];
+        out.extend(bytes);
+        out
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, Error> { // decode, rejecting values outside (0, M)
+        let n = BigInt::from_bytes_be(Sign::Plus, bytes);
+        if n <= BigInt::zero() || &n >= P::M.deref() {
+            return Err(Box::new(RsaHOGError::OutOfRange));
+        }
+        Ok(Self::from_nat(n))
+    }
+
     //TODO: Optimization for only calculating needed Bezout coefficient
     pub fn inverse(&self) -> Result<Self, Error> {
         let ((mut inv, _), gcd) = extended_euclidean_gcd(&self.n, P::M.deref());
@@ -148,10 +192,66 @@ mod tests {
         assert_eq!(c.n, TestJacobiRsaParams::M.deref() - BigInt::from(1200));
     }
 
+    #[test]
+    fn product_test() {
+        assert_eq!(Hog::product(&[]), Hog::identity());
+        let elems: Vec<Hog> = [30, 7, 40].iter().map(|x| Hog::from_nat(BigInt::from(*x))).collect();
+        let manual = elems[0].op(&elems[1]).op(&elems[2]);
+        assert_eq!(Hog::product(&elems), manual);
+        assert_eq!(Hog::product(&elems[..1]), elems[0]);
+    }
+
     #[test]
     fn unsigned_test() {
         let a = Hog::from_nat(BigInt::from(30));
         let b = Hog::from_nat(BigInt::from(-30) + TestRsaParams::M.deref());
         assert_ne!(a, b);
     }
+
+    #[test]
+    fn sort_dedup_test() {
+        let a = Hog::from_nat(BigInt::from(30));
+        let b = Hog::from_nat(BigInt::from(7));
+        let c = Hog::from_nat(BigInt::from(-30) + TestRsaParams::M.deref());
+        let mut v = vec![c.clone(), a.clone(), b.clone(), a.clone()];
+        v.sort();
+        v.dedup();
+        assert_eq!(v, vec![b, a, c]);
+    }
+
+    #[test]
+    fn random_test() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let elems: Vec<JHog> = (0..10).map(|_| JHog::random(&mut rng)).collect();
+        for a in elems.iter() {
+            assert!(a.n > BigInt::zero() && a.n < *TestJacobiRsaParams::M);
+        }
+        let mut distinct = elems.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), elems.len());
+
+        let a = JHog::random(&mut rng);
+        let b = JHog::random(&mut rng);
+        assert_eq!(a.op(&a.inverse().unwrap()), JHog::identity());
+        assert_eq!(a.op(&b).op(&b.inverse().unwrap()), a);
+    }
+
+    #[test]
+    fn bytes_round_trip_test() {
+        let a = JHog::from_nat(BigInt::from(30));
+        let bytes = a.to_bytes_be();
+        assert_eq!(bytes.len(), 256);
+        assert!(bytes[..255].iter().all(|b| *b == 0));
+        assert_eq!(JHog::from_bytes_be(&bytes).unwrap(), a);
+
+        let neg_a = JHog::from_nat(TestJacobiRsaParams::M.deref() - BigInt::from(30));
+        assert_eq!(JHog::from_bytes_be(&neg_a.to_bytes_be()).unwrap(), neg_a);
+        assert_ne!(neg_a, a);
+
+        assert!(JHog::from_bytes_be(&[0u8; 256]).is_err());
+        assert!(JHog::from_bytes_be(&TestJacobiRsaParams::M.to_bytes_be().1).is_err());
+    }
 }
@@ -1,10 +1,14 @@
 //! Implements Wesolowski's Proof of Exponentiation
+#[cfg(feature = "test-utils")]
+use crate::hash_to_prime::pocklington::{PocklingtonCertParams, PocklingtonHash};
 use crate::{
     bigint::BigInt,
     hash_to_prime::HashToPrime,
     hog::{RsaGroupParams, RsaHiddenOrderGroup},
     Error,
 };
+#[cfg(feature = "test-utils")]
+use digest::Digest;
 use num_traits::One;
 
 use num_integer::Integer;
@@ -33,12 +37,23 @@ pub struct Proof<P: RsaGroupParams, H: HashToPrime> {
 // v = u^{2^t}
 impl<P: PoEParams, RsaP: RsaGroupParams, H: HashToPrime> PoE<P, RsaP, H> {
     pub fn prove(u: &Hog<RsaP>, v: &Hog<RsaP>, t: u64) -> Result<Proof<RsaP, H>, Error> {
+        Self::prove_with_entropy(u, v, t, P::HASH_TO_PRIME_ENTROPY)
+    }
+
+    /// Overrides `PoEParams::HASH_TO_PRIME_ENTROPY` for the challenge prime of this proof. The
+    /// proof must be checked with `verify_with_entropy` and the same `entropy`.
+    pub fn prove_with_entropy(
+        u: &Hog<RsaP>,
+        v: &Hog<RsaP>,
+        t: u64,
+        entropy: usize,
+    ) -> Result<Proof<RsaP, H>, Error> {
         // Hash to challenge
         let mut hash_input = vec![];
         hash_input.append(&mut pad_to_32_byte_offset(u.n.to_bytes_be().1));
         hash_input.append(&mut pad_to_32_byte_offset(v.n.to_bytes_be().1));
         hash_input.extend_from_slice(&t.to_be_bytes());
-        let (l, cert) = H::hash_to_prime(P::HASH_TO_PRIME_ENTROPY, &hash_input)?;
+        let (l, cert) = H::hash_to_prime(entropy, &hash_input)?;
 
         // Compute quotient of exponent with challenge prime
         let q = BigInt::from(2).pow(t as u32).div_floor(&l);
@@ -70,11 +85,11 @@ impl<P: PoEParams, RsaP: RsaGroupParams, H: HashToPrime> PoE<P, RsaP, H> {
 
         // let q = (BigInt::one() << t).div_floor(&l).mod_floor(order);
 
-        let (_, q) = (BigInt::from(2).pow((t).try_into().unwrap()).div_floor(&l)).div_rem(&order);
+        let q = BigInt::from(2).pow((t).try_into().unwrap()).div_floor(&l);
 
         // Compute proof elements
         Ok(Proof {
-            q: u.power(&q),
+            q: u.power_with_order(&q, order),
             l,
             cert,
         })
@@ -85,17 +100,74 @@ impl<P: PoEParams, RsaP: RsaGroupParams, H: HashToPrime> PoE<P, RsaP, H> {
         v: &Hog<RsaP>,
         t: u64,
         proof: &Proof<RsaP, H>,
+    ) -> Result<bool, Error> {
+        Self::verify_with_entropy(u, v, t, proof, P::HASH_TO_PRIME_ENTROPY)
+    }
+
+    pub fn verify_with_entropy(
+        u: &Hog<RsaP>,
+        v: &Hog<RsaP>,
+        t: u64,
+        proof: &Proof<RsaP, H>,
+        entropy: usize,
+    ) -> Result<bool, Error> {
+        Ok(
+            Self::verify_challenge_with_entropy(u, v, t, proof, entropy)?
+                && Self::verify_quotient(u, v, t, proof),
+        )
+    }
+
+    // Checks the challenge prime `l` against its hash to prime certificate
+    pub fn verify_challenge(
+        u: &Hog<RsaP>,
+        v: &Hog<RsaP>,
+        t: u64,
+        proof: &Proof<RsaP, H>,
+    ) -> Result<bool, Error> {
+        Self::verify_challenge_with_entropy(u, v, t, proof, P::HASH_TO_PRIME_ENTROPY)
+    }
+
+    pub fn verify_challenge_with_entropy(
+        u: &Hog<RsaP>,
+        v: &Hog<RsaP>,
+        t: u64,
+        proof: &Proof<RsaP, H>,
+        entropy: usize,
     ) -> Result<bool, Error> {
         let mut hash_input = vec![];
         hash_input.append(&mut pad_to_32_byte_offset(u.n.to_bytes_be().1));
         hash_input.append(&mut pad_to_32_byte_offset(v.n.to_bytes_be().1));
         hash_input.extend_from_slice(&t.to_be_bytes());
-        let b =
-            H::verify_hash_to_prime(P::HASH_TO_PRIME_ENTROPY, &hash_input, &proof.l, &proof.cert)?;
+        H::verify_hash_to_prime(entropy, &hash_input, &proof.l, &proof.cert)
+    }
+
+    // Checks v = q^l * u^r for r = 2^t mod l, assuming `l` has been verified
+    pub fn verify_quotient(u: &Hog<RsaP>, v: &Hog<RsaP>, t: u64, proof: &Proof<RsaP, H>) -> bool {
         let r = BigInt::from(2).modpow(&BigInt::from(t), &proof.l);
+        v == &proof.q.power(&proof.l).op(&u.power(&r))
+    }
+}
 
-        // Verify proof
-        Ok(b && v == &proof.q.power(&proof.l).op(&u.power(&r)))
+// Defects injected into a valid proof, to exercise verifier rejection paths
+#[cfg(feature = "test-utils")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CorruptionKind {
+    WrongQ,             // quotient shifted by the generator
+    BadPocklingtonStep, // witness of the first Pocklington step shifted
+    OffByOneNonce,      // certificate nonce incremented
+}
+
+#[cfg(feature = "test-utils")]
+impl<P: RsaGroupParams, C: PocklingtonCertParams, D: Digest> Proof<P, PocklingtonHash<C, D>> {
+    // Panics for `BadPocklingtonStep` if the certificate has no steps
+    pub fn corrupt(&self, kind: CorruptionKind) -> Self {
+        let mut proof = self.clone();
+        match kind {
+            CorruptionKind::WrongQ => proof.q = proof.q.op(&Hog::<P>::generator()),
+            CorruptionKind::BadPocklingtonStep => proof.cert.step_certificates[0].bu += 1,
+            CorruptionKind::OffByOneNonce => proof.cert.nonce = proof.cert.nonce.wrapping_add(1),
+        }
+        proof
     }
 }
 
@@ -165,4 +237,21 @@ mod tests {
         let is_valid = TestWesolowski::verify(&u, &v, 30, &proof).unwrap();
         assert!(!is_valid);
     }
+
+    #[test]
+    fn custom_entropy_test() {
+        let u = Hog::from_nat(BigInt::from(20));
+        let t = 40;
+        let v = u.power(&BigInt::from(2).pow(t));
+
+        let proof = TestWesolowski::prove_with_entropy(&u, &v, t.into(), 256).unwrap();
+        assert!(TestWesolowski::verify_with_entropy(&u, &v, t.into(), &proof, 256).unwrap());
+        assert!(!TestWesolowski::verify(&u, &v, t.into(), &proof).unwrap());
+        assert!(!TestWesolowski::verify_with_entropy(&u, &v, t.into(), &proof, 192).unwrap());
+
+        // The default entropy is the const
+        let proof = TestWesolowski::prove(&u, &v, t.into()).unwrap();
+        assert!(TestWesolowski::verify_with_entropy(&u, &v, t.into(), &proof, 128).unwrap());
+        assert!(!TestWesolowski::verify_with_entropy(&u, &v, t.into(), &proof, 256).unwrap());
+    }
 }
@@ -1,27 +1,28 @@
 use ark_bn254::{Bn254, G1Projective as G};
 
 use ethabi::Token;
-use num_bigint::Sign;
 use primitive_types::U256;
 use rand::{rngs::StdRng, SeedableRng};
 use std::{ops::Deref, str::FromStr, thread, time::Duration};
 
 use auction_house::{
-    auction::AuctionParams,
+    auction::{AuctionParams, AuctionPhase, PhaseTiming},
     house::{AccountPrivateState, AuctionHouse, HouseAuctionParams, HouseParams},
 };
 use range_proofs::bulletproofs::Bulletproofs;
-use rsa::bigint::{nat_to_f, BigInt};
-use solidity::{encode_bulletproof, encode_new_auction, encode_tc_comm, encode_tc_opening};
+use rsa::bigint::BigInt;
+use solidity::{
+    ah_coin_address_from_factory, encode_bulletproof, encode_new_auction, encode_tc_comm,
+    encode_tc_opening,
+};
 use solidity_test_utils::{
     address::Address, contract::Contract, encode_field_element, evm::Evm, to_be_bytes,
 };
 use timed_commitments::{lazy_tc::LazyTC, PedersenComm};
 
-use csv::WriterBuilder;
 use std::{io::stdout, time::Instant};
 
-use solidity::{mean, std_deviation};
+use solidity::report::{BenchReporter, CsvReporter, Stats};
 
 mod utilities;
 use utilities::{collect_bids, setup_bidders};
@@ -54,19 +55,7 @@ fn main() {
     let mut start = Instant::now();
     let mut end = start.elapsed().as_millis();
 
-    // csv writer
-    let mut csv_writer = WriterBuilder::new().delimiter(b' ').from_writer(stdout());
-    csv_writer
-        .write_record(&[
-            "function",
-            "client_time",
-            "c_std",
-            "server_time",
-            "s_std",
-            "gas_cost",
-        ])
-        .unwrap();
-    csv_writer.flush().unwrap();
+    let mut reporter = CsvReporter::new(stdout()).unwrap();
 
     // Begin benchmark
     let mut rng = StdRng::seed_from_u64(1u64);
@@ -77,13 +66,19 @@ fn main() {
     let bulletproofs_pp = Bulletproofs::<G, sha3::Keccak256>::gen_params(&mut rng, NUM_BID_BITS);
     let auction_pp = HouseAuctionParams {
         auction_pp: AuctionParams {
-            t_bid_collection: Duration::from_secs(5),
-            t_bid_self_open: Duration::from_secs(10),
+            timing: PhaseTiming::WallClock {
+                t_bid_collection: Duration::from_secs(5),
+                t_bid_self_open: Duration::from_secs(10),
+            },
             time_pp: time_pp.clone(),
             ped_pp: ped_pp.clone(),
         },
         reward_self_open: REWARD_SELF_OPEN,
         reward_force_open: REWARD_FORCE_OPEN,
+        seller_bond: 0,
+        bundle: Vec::new(),
+        reclaim_deadline: None,
+        max_bid_bits: NUM_BID_BITS as u32,
     };
     let house_pp = HouseParams {
         range_proof_pp: bulletproofs_pp.clone(),
@@ -131,7 +126,11 @@ fn main() {
     );
 
     // Deploy auction house contract
-    let contract_constructor_input = vec![ahc_factory_contract_addr.as_token()];
+    // Zero coin address deploys the house with a new AHCoin
+    let contract_constructor_input = vec![
+        ahc_factory_contract_addr.as_token(),
+        Token::Address(ethabi::Address::zero()),
+    ];
     let deploy_ah_result = evm
         .deploy(
             ah_contract
@@ -144,34 +143,21 @@ fn main() {
     // println!("Contract deploy gas cost: {}", create_result.gas);
 
     // Benchmark: Create House
-    csv_writer
-        .write_record(&[
+    reporter
+        .record(
             "create_house",
-            "0",
-            "0",
-            "0",
-            "0",
-            &deploy_ah_result.gas.to_string(),
-        ])
+            Stats::default(),
+            Stats::default(),
+            deploy_ah_result.gas,
+        )
         .unwrap();
-    csv_writer.flush().unwrap();
 
     // Mint token to auction (auctioned by "owner")
     let owner = Address::random(&mut rng);
 
-    let result_coin_address = evm
-        .call(
-            ah_contract
-                .encode_call_contract_bytes("get_AHCoin_address", &[])
-                .unwrap(),
-            &ah_contract_addr,
-            &owner,
-        )
-        .unwrap();
-
-    let ah_coin_contract_addr = solidity_test_utils::address::Address(
-        primitive_types::H160::from_slice(&result_coin_address.out[12..]),
-    );
+    let ah_coin_contract_addr =
+        ah_coin_address_from_factory(&mut evm, &ahc_factory_contract_addr, &ah_contract_addr)
+            .unwrap();
     // println!("Coin contract is at address: {:?}", ah_coin_contract_addr);
 
     evm.create_account(&owner, 0);
@@ -219,10 +205,12 @@ fn main() {
         &ah_coin_contract_addr,
     );
 
+    // Seller gets its own account so that it is not also one of the bidders
+    let (seller_id, _) = auction_house.new_account(&house_pp);
+
     // Create a new auction
     let new_auction_inputs = encode_new_auction::<Bn254, _>(
-        &erc721_contract_addr,
-        1,
+        &[(erc721_contract_addr.clone(), 1)],
         20,
         10,
         REWARD_SELF_OPEN,
@@ -254,20 +242,19 @@ fn main() {
     // println!("Owner created auction: gas: {}", result.gas);
     assert_eq!(&result.out, &to_be_bytes(&U256::from(0)));
 
-    let auction_id = auction_house.new_auction(&house_pp, &auction_pp);
+    let auction_id = auction_house
+        .new_auction(&house_pp, &auction_pp, seller_id, None, None)
+        .unwrap();
 
     // Benchmark: Create Auction
-    csv_writer
-        .write_record(&[
+    reporter
+        .record(
             "create_auction",
-            "0",
-            "0",
-            "0",
-            "0",
-            &result.gas.to_string(),
-        ])
+            Stats::default(),
+            Stats::default(),
+            result.gas,
+        )
         .unwrap();
-    csv_writer.flush().unwrap();
     assert_eq!(&result.out, &to_be_bytes(&U256::from(0)));
 
     evm.set_block_number(1 + 10);
@@ -284,17 +271,14 @@ fn main() {
         );
 
         // Benchmark: Submit Bid
-        csv_writer
-            .write_record(&[
+        reporter
+            .record(
                 "submit_bid",
-                &mean(&place_bid_client_vec).unwrap().to_string(),
-                &std_deviation(&place_bid_client_vec).unwrap().to_string(),
-                &mean(&place_bid_server_vec).unwrap().to_string(),
-                &std_deviation(&place_bid_server_vec).unwrap().to_string(),
-                &(place_bid_gas).to_string(),
-            ])
+                Stats::from_samples(&place_bid_client_vec).unwrap(),
+                Stats::from_samples(&place_bid_server_vec).unwrap(),
+                place_bid_gas,
+            )
             .unwrap();
-        csv_writer.flush().unwrap();
 
         let result = evm
             .call(
@@ -305,7 +289,7 @@ fn main() {
                 &deployer,
             )
             .unwrap();
-        assert_eq!(&result.out, &to_be_bytes(&U256::from(0))); // Bid collection enum = 0
+        assert_eq!(&result.out, &to_be_bytes(&AuctionPhase::BidCollection.into()));
     }
 
     // Self opening
@@ -325,16 +309,8 @@ fn main() {
             end = start.elapsed().as_nanos();
             self_open_client_vec.push(end as u64);
 
-            let tc_m_copy = &(opening.tc_m).clone().unwrap();
-
-            let mut m_computed = tc_m_copy.to_vec();
-            // let f_bytes = <G::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8;
-            let f_bytes = 32;
-            let ped_opening = nat_to_f(&BigInt::from_bytes_be(
-                Sign::Plus,
-                &m_computed.split_off(m_computed.len() - f_bytes),
-            ))
-            .unwrap();
+            assert!(opening.is_self_opening());
+            let ped_opening = opening.get_ped_opening();
 
             let result = evm
                 .call(
@@ -386,21 +362,18 @@ fn main() {
                 &deployer,
             )
             .unwrap();
-        assert_eq!(&result.out, &to_be_bytes(&U256::from(1))); // Bid self open enum = 1
+        assert_eq!(&result.out, &to_be_bytes(&AuctionPhase::BidSelfOpening.into()));
     }
 
     // Benchmark: Self Opening Bid
-    csv_writer
-        .write_record(&[
+    reporter
+        .record(
             "self_open",
-            &mean(&self_open_client_vec).unwrap().to_string(),
-            &std_deviation(&self_open_client_vec).unwrap().to_string(),
-            &mean(&self_open_server_vec).unwrap().to_string(),
-            &std_deviation(&self_open_server_vec).unwrap().to_string(),
-            &self_open_gas.to_string(),
-        ])
+            Stats::from_samples(&self_open_client_vec).unwrap(),
+            Stats::from_samples(&self_open_server_vec).unwrap(),
+            self_open_gas,
+        )
         .unwrap();
-    csv_writer.flush().unwrap();
 
     let mut update_winnner_gas = 0;
     // Benchmark: Update winner, prices
@@ -456,7 +429,7 @@ fn main() {
         //     &deployer,
         //   )
         //   .unwrap();
-        // assert_eq!(&result.out, &to_be_bytes(&U256::from(2))); // Bid force open enum = 2
+        // assert_eq!(&result.out, &to_be_bytes(&AuctionPhase::BidForceOpening.into()));
 
         let (bidder, bidder_addr) = bidders.get(n_bidders - 1).unwrap();
         let (bid, _, comm) = bidder.active_bids.get(&((0) as u32)).unwrap();
@@ -530,17 +503,14 @@ fn main() {
     }
 
     // Benchmark: Force Opening Bid
-    csv_writer
-        .write_record(&[
+    reporter
+        .record(
             "FORCE_OPEN",
-            &mean(&force_open_client_vec).unwrap().to_string(),
-            &std_deviation(&force_open_client_vec).unwrap().to_string(),
-            &mean(&force_open_server_vec).unwrap().to_string(),
-            &std_deviation(&force_open_server_vec).unwrap().to_string(),
-            &force_open_gas.to_string(),
-        ])
+            Stats::from_samples(&force_open_client_vec).unwrap(),
+            Stats::from_samples(&force_open_server_vec).unwrap(),
+            force_open_gas,
+        )
         .unwrap();
-    csv_writer.flush().unwrap();
 
     // Withdrawal
 
@@ -554,7 +524,7 @@ fn main() {
                         "withdraw",
                         &[
                             Token::Uint(U256::from(65)),
-                            encode_bulletproof::<Bn254>(&withdrawal_proof),
+                            encode_bulletproof::<Bn254>(&withdrawal_proof).unwrap(),
                         ],
                     )
                     .unwrap(),
@@ -589,7 +559,7 @@ fn main() {
                 &deployer,
             )
             .unwrap();
-        assert_eq!(&result.out, &to_be_bytes(&U256::from(3))); // Auction complete enum = 3
+        assert_eq!(&result.out, &to_be_bytes(&AuctionPhase::Complete.into()));
 
         let result = evm
             .call(
@@ -660,7 +630,7 @@ fn main() {
                         "withdraw",
                         &[
                             Token::Uint(U256::from(60)),
-                            encode_bulletproof::<Bn254>(&withdrawal_proof),
+                            encode_bulletproof::<Bn254>(&withdrawal_proof).unwrap(),
                         ],
                     )
                     .unwrap(),
@@ -693,17 +663,14 @@ fn main() {
             .unwrap();
     }
 
-    csv_writer
-        .write_record(&[
+    reporter
+        .record(
             "complete_auction_fixed",
-            "0",
-            "0",
-            &(complete_server_fixed / 1).to_string(),
-            "0",
-            &(complete_gas / 1).to_string(),
-        ])
+            Stats::default(),
+            Stats::from_samples(&[complete_server_fixed / 1]).unwrap(),
+            complete_gas / 1,
+        )
         .unwrap();
-    csv_writer.flush().unwrap();
 
     let mut reclaim_server = 0;
     let mut reclaim_gas = 0;
@@ -733,17 +700,15 @@ fn main() {
             reclaim_gas = reclaim_result.gas;
         }
     }
-    csv_writer
-        .write_record(&[
+    reporter
+        .record(
             "complete_auction_per_bidder",
-            "0",
-            "0",
-            &((complete_server - complete_server_fixed) / (n_bidders as u64)).to_string(),
-            "0",
-            &(reclaim_gas + update_winnner_gas).to_string(),
-        ])
+            Stats::default(),
+            Stats::from_samples(&[(complete_server - complete_server_fixed) / n_bidders as u64])
+                .unwrap(),
+            reclaim_gas + update_winnner_gas,
+        )
         .unwrap();
-    csv_writer.flush().unwrap();
 
     // Withdrawal after active bids updated
 
@@ -765,7 +730,7 @@ fn main() {
                             "withdraw",
                             &[
                                 Token::Uint(U256::from(80)),
-                                encode_bulletproof::<Bn254>(&withdrawal_proof),
+                                encode_bulletproof::<Bn254>(&withdrawal_proof).unwrap(),
                             ],
                         )
                         .unwrap(),
@@ -784,16 +749,13 @@ fn main() {
         }
 
         // Benchmark: Withdraw
-        csv_writer
-            .write_record(&[
+        reporter
+            .record(
                 "withdraw",
-                &mean(&withdraw_client_vec).unwrap().to_string(),
-                &std_deviation(&withdraw_client_vec).unwrap().to_string(),
-                &mean(&withdraw_server_vec).unwrap().to_string(),
-                &std_deviation(&withdraw_server_vec).unwrap().to_string(),
-                &(withdraw_gas).to_string(),
-            ])
+                Stats::from_samples(&withdraw_client_vec).unwrap(),
+                Stats::from_samples(&withdraw_server_vec).unwrap(),
+                withdraw_gas,
+            )
             .unwrap();
-        csv_writer.flush().unwrap();
     }
 }
@@ -11,11 +11,11 @@ use range_proofs::bulletproofs::{Bulletproofs, PedersenComm};
 use rsa::bigint::BigInt;
 use solidity::{
     encode_bulletproof, get_bn254_library_src, get_bulletproofs_verifier_contract_src,
-    get_pedersen_library_src,
+    get_pedersen_library_src, log2_checked,
 };
 
 const NUM_BITS: u64 = 64;
-const LOG_NUM_BITS: u64 = 6;
+const LOG_NUM_BITS: u64 = log2_checked(NUM_BITS);
 
 fn main() {
     let mut rng = StdRng::seed_from_u64(0u64);
@@ -34,10 +34,10 @@ fn main() {
     .unwrap());
 
     // Compile contract from template
-    let bn254_src = get_bn254_library_src();
-    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
+    let bn254_src = get_bn254_library_src().unwrap();
+    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
     let bulletproofs_src =
-        get_bulletproofs_verifier_contract_src(&pp, &ped_pp, NUM_BITS, LOG_NUM_BITS, true);
+        get_bulletproofs_verifier_contract_src(&pp, &ped_pp, NUM_BITS, LOG_NUM_BITS, true).unwrap();
 
     let solc_config = r#"
             {
@@ -82,7 +82,7 @@ fn main() {
     // Call verify function on contract
     let input = vec![
         encode_group_element::<Bn254>(&comm),
-        encode_bulletproof::<Bn254>(&proof),
+        encode_bulletproof::<Bn254>(&proof).unwrap(),
     ];
     let result = evm
         .call(
@@ -80,10 +80,10 @@ fn main() {
     println!("Compiling contract...");
 
     // Compile contract from template
-    let bigint_src = get_bigint_library_src();
-    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false);
-    let poe_src = get_filename_src("PoEVerifier.sol", false);
-    let fkps_src = get_fkps_src(&fkps_pp.x.n, &fkps_pp.y.n, MOD_BITS, TIME_PARAM, true);
+    let bigint_src = get_bigint_library_src().unwrap();
+    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false).unwrap();
+    let poe_src = get_filename_src("PoEVerifier.sol", false).unwrap();
+    let fkps_src = get_fkps_src(&fkps_pp.x.n, &fkps_pp.y.n, MOD_BITS, TIME_PARAM, true).unwrap();
 
     let solc_config = r#"
             {
@@ -0,0 +1,64 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sha3::Keccak256;
+use std::time::Instant;
+
+use rsa::hash_to_prime::{
+    pocklington::{PocklingtonCertParams, PocklingtonHash},
+    HashToPrime, MillerRabinRejectionSample,
+};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestPocklingtonParams;
+impl PocklingtonCertParams for TestPocklingtonParams {
+    const NONCE_SIZE: usize = 16;
+    const MAX_STEPS: usize = 5;
+    const INCLUDE_SOLIDITY_WITNESSES: bool = false;
+}
+
+const ENTROPY: usize = 128;
+const NUM_INPUTS: usize = 20;
+
+fn bench_hash_to_prime<H2P: HashToPrime>(name: &str, inputs: &[[u8; 32]]) {
+    let start = Instant::now();
+    let (outputs, counts): (Vec<_>, Vec<_>) = inputs
+        .iter()
+        .map(|input| {
+            let (p, cert, count) = H2P::hash_to_prime_counted(ENTROPY, input).unwrap();
+            ((p, cert), count)
+        })
+        .unzip();
+    let hash_time = start.elapsed();
+
+    let start = Instant::now();
+    for (input, (p, cert)) in inputs.iter().zip(outputs.iter()) {
+        assert!(H2P::verify_hash_to_prime(ENTROPY, input, p, cert).unwrap());
+    }
+    let verify_time = start.elapsed();
+
+    println!(
+        "{}: hash {:?} / input, verify {:?} / input",
+        name,
+        hash_time / inputs.len() as u32,
+        verify_time / inputs.len() as u32,
+    );
+    println!(
+        "{}: {:.1} iterations / input, {:.1} expected",
+        name,
+        counts.iter().sum::<usize>() as f64 / inputs.len() as f64,
+        H2P::expected_iterations(ENTROPY),
+    );
+}
+
+fn main() {
+    // cargo bench --bench hash_to_prime --profile test
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let inputs = (0..NUM_INPUTS)
+        .map(|_| rng.gen())
+        .collect::<Vec<[u8; 32]>>();
+
+    bench_hash_to_prime::<PocklingtonHash<TestPocklingtonParams, Keccak256>>(
+        "Pocklington",
+        &inputs,
+    );
+    bench_hash_to_prime::<MillerRabinRejectionSample<Keccak256>>("Miller-Rabin", &inputs);
+}
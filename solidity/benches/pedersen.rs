@@ -23,8 +23,8 @@ fn main() {
     assert!(PedersenComm::<G>::ver_open(&ped_pp, &comm, &v.to_bytes_le().1, &opening).unwrap());
 
     // Compile contract from template
-    let bn254_src = get_bn254_library_src();
-    let pedersen_src = get_pedersen_library_src(&ped_pp, true);
+    let bn254_src = get_bn254_library_src().unwrap();
+    let pedersen_src = get_pedersen_library_src(&ped_pp, true).unwrap();
 
     let solc_config = r#"
             {
@@ -34,9 +34,9 @@ fn main() {
     println!("{}", &ped_pp.h.y);
 
     // Compile contract from template
-    // let bn254_src = get_bn254_library_src();
-    let babyjubjub_src = get_filename_src("BabyJubjub.sol", false);
-    let pedersen_src = get_pedersen_library_src2::<G>(&ped_pp, true);
+    // let bn254_src = get_bn254_library_src().unwrap();
+    let babyjubjub_src = get_filename_src("BabyJubjub.sol", false).unwrap();
+    let pedersen_src = get_pedersen_library_src2::<G>(&ped_pp, true).unwrap();
 
     let solc_config = r#"
             {
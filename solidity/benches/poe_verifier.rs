@@ -78,9 +78,9 @@ fn main() {
     println!("Compiling contract...");
 
     // Compile contract from template
-    let bigint_src = get_bigint_library_src();
-    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false);
-    let poe_src = get_filename_src("PoEVerifier.sol", true);
+    let bigint_src = get_bigint_library_src().unwrap();
+    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false).unwrap();
+    let poe_src = get_filename_src("PoEVerifier.sol", true).unwrap();
 
     let solc_config = r#"
             {
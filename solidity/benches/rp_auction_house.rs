@@ -23,7 +23,7 @@ use solidity::{
     encode_bulletproof, encode_new_auction, encode_ped_comm_struct, get_bigint_library_src,
     get_bn254_deploy_src, get_bn254_library_src, get_bulletproofs_verifier_contract_src,
     get_filename_src, get_fkps_src, get_pedersen_deploy_src, get_pedersen_library_src,
-    get_rsa_library_src,
+    get_rsa_library_src, log2_checked,
 };
 use solidity_test_utils::{
     address::Address, contract::Contract, encode_field_element, encode_group_element, evm::Evm,
@@ -53,7 +53,7 @@ pub type Hog = RsaHiddenOrderGroup<TestRsaParams>;
 const MOD_BITS: usize = 2048;
 const TIME_PARAM: u64 = 40;
 const NUM_BID_BITS: u64 = 32;
-const LOG_NUM_BID_BITS: u64 = 5;
+const LOG_NUM_BID_BITS: u64 = log2_checked(NUM_BID_BITS);
 
 const REWARD_SELF_OPEN: u32 = 5;
 const REWARD_FORCE_OPEN: u32 = 5;
@@ -115,15 +115,16 @@ fn main() {
     // TODO: Create better tooling for compiling and deploying with libraries
     println!("Compiling bulletproofs contract...");
     let (_bulletproofs_contract, bulletproofs_contract_addr) = {
-        let bn254_src = get_bn254_library_src();
-        let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
+        let bn254_src = get_bn254_library_src().unwrap();
+        let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
         let bulletproofs_src = get_bulletproofs_verifier_contract_src(
             &bulletproofs_pp,
             &ped_pp,
             NUM_BID_BITS,
             LOG_NUM_BID_BITS,
             false,
-        );
+        )
+        .unwrap();
 
         let solc_config = r#"
             {
@@ -168,13 +169,13 @@ fn main() {
 
     // println!("Compiling timed commitments contract...");
     // let (_tc_contract, tc_contract_addr) = {
-    //   let bn254_src = get_bn254_library_src();
-    //   let bigint_src = get_bigint_library_src();
-    //   let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
-    //   let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false);
-    //   let poe_src = get_filename_src("PoEVerifier.sol", false);
-    //   let fkps_src = get_fkps_src(&time_pp.x.n, &time_pp.y.n, MOD_BITS, TIME_PARAM, false);
-    //   let tc_src = get_filename_src("TC.sol", false);
+    //   let bn254_src = get_bn254_library_src().unwrap();
+    //   let bigint_src = get_bigint_library_src().unwrap();
+    //   let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
+    //   let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false).unwrap();
+    //   let poe_src = get_filename_src("PoEVerifier.sol", false).unwrap();
+    //   let fkps_src = get_fkps_src(&time_pp.x.n, &time_pp.y.n, MOD_BITS, TIME_PARAM, false).unwrap();
+    //   let tc_src = get_filename_src("TC.sol", false).unwrap();
     //
     //   let solc_config = r#"
     //           {
@@ -243,25 +244,32 @@ fn main() {
                 }
             }"#
     .replace("<%opt%>", &true.to_string())
-    .replace("<%erc721_src%>", &get_filename_src("IERC721.sol", false))
-    .replace("<%src%>", &get_filename_src("TestERC721.sol", true));
+    .replace(
+        "<%erc721_src%>",
+        &get_filename_src("IERC721.sol", false).unwrap(),
+    )
+    .replace(
+        "<%src%>",
+        &get_filename_src("TestERC721.sol", true).unwrap(),
+    );
 
     let erc721_contract = Contract::compile_from_config(&solc_config, "TestERC721").unwrap();
 
     println!("Compiling (but not deploying) Auction House Coin contract...");
     let ah_coin_contract = {
-        let auction_house_coin_src = get_filename_src("AuctionHouseCoin.sol", true);
-        let bn254_src = get_bn254_library_src();
-        let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
+        let auction_house_coin_src = get_filename_src("AuctionHouseCoin.sol", true).unwrap();
+        let bn254_src = get_bn254_library_src().unwrap();
+        let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
         let bulletproofs_src = get_bulletproofs_verifier_contract_src(
             &bulletproofs_pp,
             &ped_pp,
             NUM_BID_BITS,
             LOG_NUM_BID_BITS,
             false,
-        );
-        let erc20_src = get_filename_src("IERC20.sol", false);
-        let erc721_src = get_filename_src("IERC721.sol", false);
+        )
+        .unwrap();
+        let erc20_src = get_filename_src("IERC20.sol", false).unwrap();
+        let erc721_src = get_filename_src("IERC721.sol", false).unwrap();
 
         let solc_config = r#"
             {
@@ -315,19 +323,20 @@ fn main() {
 
     println!("Compiling Auction House Coin Factory contract...");
     let (ahc_factory_contract, ahc_factory_contract_addr) = {
-        let ahc_factory_src = get_filename_src("AuctionHouseCoinFactory.sol", true);
-        let bn254_src = get_bn254_library_src();
-        let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
+        let ahc_factory_src = get_filename_src("AuctionHouseCoinFactory.sol", true).unwrap();
+        let bn254_src = get_bn254_library_src().unwrap();
+        let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
         let bulletproofs_src = get_bulletproofs_verifier_contract_src(
             &bulletproofs_pp,
             &ped_pp,
             NUM_BID_BITS,
             LOG_NUM_BID_BITS,
             false,
-        );
-        let erc20_src = get_filename_src("IERC20.sol", false);
-        let erc721_src = get_filename_src("IERC721.sol", false);
-        let ah_coin_src = get_filename_src("AuctionHouseCoin.sol", false);
+        )
+        .unwrap();
+        let erc20_src = get_filename_src("IERC20.sol", false).unwrap();
+        let erc721_src = get_filename_src("IERC721.sol", false).unwrap();
+        let ah_coin_src = get_filename_src("AuctionHouseCoin.sol", false).unwrap();
 
         let solc_config = r#"
             {
@@ -388,20 +397,21 @@ fn main() {
 
     // Compile auction house contract from template
     println!("Compiling RP auction house contract...");
-    let rp_auction_house_src = get_filename_src("AuctionHouseRP.sol", true);
-    let bn254_src = get_bn254_library_src();
-    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
+    let rp_auction_house_src = get_filename_src("AuctionHouseRP.sol", true).unwrap();
+    let bn254_src = get_bn254_library_src().unwrap();
+    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
     let bulletproofs_src = get_bulletproofs_verifier_contract_src(
         &bulletproofs_pp,
         &ped_pp,
         NUM_BID_BITS,
         LOG_NUM_BID_BITS,
         false,
-    );
-    let erc20_src = get_filename_src("IERC20.sol", false);
-    let erc721_src = get_filename_src("IERC721.sol", false);
-    let ahc_factory_src = get_filename_src("AuctionHouseCoinFactory.sol", false);
-    let ah_coin_src = get_filename_src("AuctionHouseCoin.sol", false);
+    )
+    .unwrap();
+    let erc20_src = get_filename_src("IERC20.sol", false).unwrap();
+    let erc721_src = get_filename_src("IERC721.sol", false).unwrap();
+    let ahc_factory_src = get_filename_src("AuctionHouseCoinFactory.sol", false).unwrap();
+    let ah_coin_src = get_filename_src("AuctionHouseCoin.sol", false).unwrap();
 
     let solc_config = r#"
             {
@@ -654,8 +664,9 @@ fn main() {
                             &[
                                 Token::Uint(U256::from(0)),
                                 encode_ped_comm_struct::<Bn254>(&bid_proposal.comm_bid.g),
-                                encode_bulletproof::<Bn254>(&bid_proposal.range_proof_bid),
-                                encode_bulletproof::<Bn254>(&bid_proposal.range_proof_balance),
+                                encode_bulletproof::<Bn254>(&bid_proposal.range_proof_bid).unwrap(),
+                                encode_bulletproof::<Bn254>(&bid_proposal.range_proof_balance)
+                                    .unwrap(),
                             ],
                         )
                         .unwrap(),
@@ -854,7 +865,7 @@ fn main() {
                         "withdraw",
                         &[
                             Token::Uint(U256::from(60)),
-                            encode_bulletproof::<Bn254>(&withdrawal_proof),
+                            encode_bulletproof::<Bn254>(&withdrawal_proof).unwrap(),
                         ],
                     )
                     .unwrap(),
@@ -898,7 +909,7 @@ fn main() {
                         "withdraw",
                         &[
                             Token::Uint(U256::from(80)),
-                            encode_bulletproof::<Bn254>(&withdrawal_proof),
+                            encode_bulletproof::<Bn254>(&withdrawal_proof).unwrap(),
                         ],
                     )
                     .unwrap(),
@@ -52,8 +52,8 @@ fn main() {
     println!("Compiling contract...");
 
     // Compile contract from template
-    let bigint_src = get_bigint_library_src();
-    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, true);
+    let bigint_src = get_bigint_library_src().unwrap();
+    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, true).unwrap();
 
     let solc_config = r#"
             {
@@ -135,13 +135,13 @@ fn main() {
     println!("Compiling contract...");
 
     // Compile contract from template
-    let bn254_src = get_bn254_library_src();
-    let bigint_src = get_bigint_library_src();
-    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
-    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false);
-    let poe_src = get_filename_src("PoEVerifier.sol", false);
-    let fkps_src = get_fkps_src(&time_pp.x.n, &time_pp.y.n, MOD_BITS, TIME_PARAM, false);
-    let tc_src = get_filename_src("TC.sol", true);
+    let bn254_src = get_bn254_library_src().unwrap();
+    let bigint_src = get_bigint_library_src().unwrap();
+    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
+    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false).unwrap();
+    let poe_src = get_filename_src("PoEVerifier.sol", false).unwrap();
+    let fkps_src = get_fkps_src(&time_pp.x.n, &time_pp.y.n, MOD_BITS, TIME_PARAM, false).unwrap();
+    let tc_src = get_filename_src("TC.sol", true).unwrap();
 
     let solc_config = r#"
             {
@@ -194,7 +194,7 @@ fn main() {
     // Benchmark self open
     println!("Benchmark self-open...");
     let input = vec![
-        encode_tc_comm::<Bn254, _>(&tc_comm),
+        encode_tc_comm::<Bn254, _>(&tc_comm).unwrap(),
         encode_tc_opening(&tc_opening),
         Token::Uint(U256::from_little_endian(&m)),
         encode_tc_pp::<Bn254, _>(TestRsaParams::M.deref(), &time_pp, &ped_pp),
@@ -273,7 +273,7 @@ fn main() {
             }
         };
         let force_input = vec![
-            encode_tc_comm::<Bn254, _>(&tc_comm),
+            encode_tc_comm::<Bn254, _>(&tc_comm).unwrap(),
             encode_tc_opening(&tc_force_opening),
             Token::Uint(
                 m.map(|m| U256::from_little_endian(&m))
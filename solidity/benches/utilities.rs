@@ -20,7 +20,7 @@ use std::{
 
 use auction_house::{
     auction::AuctionParams,
-    house::{AccountPrivateState, AuctionHouse, HouseAuctionParams, HouseParams},
+    house::{AccountPrivateState, AuctionHouse, BidValue, HouseAuctionParams, HouseParams},
 };
 use range_proofs::bulletproofs::{Bulletproofs, Params as BulletproofsParams};
 use rsa::{
@@ -33,7 +33,8 @@ use solidity::{
     encode_bulletproof, encode_new_auction, encode_tc_comm, encode_tc_opening, encode_tc_partial,
     get_bigint_library_src, get_bn254_deploy_src, get_bn254_library_src,
     get_bulletproofs_verifier_contract_src, get_filename_src, get_fkps_src,
-    get_pedersen_deploy_src, get_pedersen_library_src, get_rsa_library_src,
+    get_pedersen_deploy_src, get_pedersen_library_src, get_rsa_library_src, log2_checked,
+    simulation,
 };
 use solidity_test_utils::{
     address::Address, contract::Contract, encode_field_element, evm::Evm, to_be_bytes,
@@ -62,7 +63,7 @@ pub type Hog = RsaHiddenOrderGroup<TestRsaParams>;
 pub const MOD_BITS: usize = 2048;
 pub const TIME_PARAM: u64 = 100;
 pub const NUM_BID_BITS: u64 = 32;
-pub const LOG_NUM_BID_BITS: u64 = 5;
+pub const LOG_NUM_BID_BITS: u64 = log2_checked(NUM_BID_BITS);
 
 pub const REWARD_SELF_OPEN: u32 = 5;
 pub const REWARD_FORCE_OPEN: u32 = 5;
@@ -112,15 +113,16 @@ pub fn deploy_bulletproofs(
     ped_pp: &PedersenParams<G>,
     bulletproofs_pp: &BulletproofsParams<G>,
 ) -> (Contract, Address) {
-    let bn254_src = get_bn254_library_src();
-    let pedersen_lib_src = get_pedersen_library_src(ped_pp, false);
+    let bn254_src = get_bn254_library_src().unwrap();
+    let pedersen_lib_src = get_pedersen_library_src(ped_pp, false).unwrap();
     let bulletproofs_src = get_bulletproofs_verifier_contract_src(
         bulletproofs_pp,
         ped_pp,
         NUM_BID_BITS,
         LOG_NUM_BID_BITS,
         false,
-    );
+    )
+    .unwrap();
 
     let solc_config = r#"
             {
@@ -169,13 +171,13 @@ pub fn deploy_tc(
     ped_pp: &PedersenParams<G>,
     time_pp: &TimeParams<TestRsaParams>,
 ) -> (Contract, Address) {
-    let bn254_src = get_bn254_library_src();
-    let bigint_src = get_bigint_library_src();
-    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
-    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false);
-    let poe_src = get_filename_src("PoEVerifier.sol", false);
-    let fkps_src = get_fkps_src(&time_pp.x.n, &time_pp.y.n, MOD_BITS, TIME_PARAM, false);
-    let tc_src = get_filename_src("TC.sol", false);
+    let bn254_src = get_bn254_library_src().unwrap();
+    let bigint_src = get_bigint_library_src().unwrap();
+    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
+    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false).unwrap();
+    let poe_src = get_filename_src("PoEVerifier.sol", false).unwrap();
+    let fkps_src = get_fkps_src(&time_pp.x.n, &time_pp.y.n, MOD_BITS, TIME_PARAM, false).unwrap();
+    let tc_src = get_filename_src("TC.sol", false).unwrap();
 
     let solc_config = r#"
             {
@@ -221,84 +223,11 @@ pub fn deploy_tc(
 }
 
 pub fn deploy_erc721(evm: &mut Evm, deployer: &Address) -> (Contract, Address) {
-    let solc_config = r#"
-            {
-                "language": "Solidity",
-                "sources": {
-                    "input.sol": { "content": "<%src%>" },
-                    "IERC721.sol": { "content": "<%erc721_src%>" }
-                },
-                "settings": {
-                    "optimizer": { "enabled": <%opt%> },
-                    "outputSelection": {
-                        "*": {
-                            "*": [
-                                "evm.bytecode.object", "abi"
-                            ],
-                        "": [ "*" ] } }
-                }
-            }"#
-    .replace("<%opt%>", &true.to_string())
-    .replace("<%erc721_src%>", &get_filename_src("IERC721.sol", false))
-    .replace("<%src%>", &get_filename_src("TestERC721.sol", true));
-
-    let erc721_contract = Contract::compile_from_config(&solc_config, "TestERC721").unwrap();
-
-    // Deploy ERC-721 contract
-    let create_result = evm
-        .deploy(
-            erc721_contract
-                .encode_create_contract_bytes(&[
-                    Token::String("TestERC721".to_string()),
-                    Token::String("NFT".to_string()),
-                ])
-                .unwrap(),
-            &deployer,
-        )
-        .unwrap();
-    let erc721_contract_addr = create_result.addr.clone();
-
-    (erc721_contract, erc721_contract_addr)
+    simulation::deploy_erc721(evm, deployer).unwrap()
 }
 
 pub fn deploy_ah_coin(evm: &mut Evm, deployer: &Address) -> Contract {
-    let auction_house_coin_src = get_filename_src("AuctionHouseCoin.sol", true);
-    let erc20_src = get_filename_src("IERC20.sol", false);
-    let erc721_src = get_filename_src("IERC721.sol", false);
-
-    let solc_config = r#"
-          {
-              "language": "Solidity",
-              "sources": {
-                  "input.sol": { "content": "<%src%>" },
-                  "IERC20.sol": { "content": "<%erc20_src%>" },
-                  "IERC721.sol": { "content": "<%erc721_src%>" }
-              },
-              "settings": {
-                  "optimizer": { "enabled": <%opt%> },
-                  "outputSelection": {
-                      "*": {
-                          "*": [
-                              "evm.bytecode.object", "abi"
-                          ],
-                      "": [ "*" ] } }
-              }
-          }"#
-    .replace("<%opt%>", &false.to_string()) // Needed to disable opt for a BigNumber assembly instruction
-    .replace("<%erc20_src%>", &erc20_src)
-    .replace("<%erc721_src%>", &erc721_src)
-    .replace("<%src%>", &auction_house_coin_src);
-
-    let contract = Contract::compile_from_config(&solc_config, "AuctionHouseCoin").unwrap();
-    // let create_result = evm
-    //   .deploy(
-    //     contract.encode_create_contract_bytes(&[]).unwrap(),
-    //     &deployer,
-    //   )
-    //   .unwrap();
-    // let contract_addr = create_result.addr.clone();
-    // println!("AH Coin contract deployed at address: {:?}", contract_addr);
-    contract
+    simulation::deploy_ah_coin(evm, deployer).unwrap()
 }
 
 // pub fn deploy_ah_coin(
@@ -308,18 +237,18 @@ pub fn deploy_ah_coin(evm: &mut Evm, deployer: &Address) -> Contract {
 //     bulletproofs_pp: &BulletproofsParams<G>,
 //     bulletproofs_contract_addr: &Address,
 // ) -> Contract {
-//     let auction_house_coin_src = get_filename_src("AuctionHouseCoin.sol", true);
-//     let bn254_src = get_bn254_library_src();
-//     let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
+//     let auction_house_coin_src = get_filename_src("AuctionHouseCoin.sol", true).unwrap();
+//     let bn254_src = get_bn254_library_src().unwrap();
+//     let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
 //     let bulletproofs_src = get_bulletproofs_verifier_contract_src(
 //         &bulletproofs_pp,
 //         &ped_pp,
 //         NUM_BID_BITS,
 //         LOG_NUM_BID_BITS,
 //         false,
-//     );
-//     let erc20_src = get_filename_src("IERC20.sol", false);
-//     let erc721_src = get_filename_src("IERC721.sol", false);
+//     ).unwrap();
+//     let erc20_src = get_filename_src("IERC20.sol", false).unwrap();
+//     let erc721_src = get_filename_src("IERC721.sol", false).unwrap();
 
 //     let solc_config = r#"
 //           {
@@ -371,101 +300,8 @@ pub fn deploy_ah_coin(evm: &mut Evm, deployer: &Address) -> Contract {
 //     contract
 // }
 
-pub fn deploy_ahc_factory(
-    evm: &mut Evm,
-    deployer: &Address,
-    // ped_pp: &PedersenParams<G>,
-    // bulletproofs_pp: &BulletproofsParams<G>,
-    // bulletproofs_contract_addr: &Address,
-) -> (Contract, Address) {
-    let ahc_factory_src = get_filename_src("AuctionHouseCoinFactory.sol", true);
-    // let bn254_src = get_bn254_library_src();
-    // let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
-    // let bulletproofs_src = get_bulletproofs_verifier_contract_src(
-    //     &bulletproofs_pp,
-    //     &ped_pp,
-    //     NUM_BID_BITS,
-    //     LOG_NUM_BID_BITS,
-    //     false,
-    // );
-    let erc20_src = get_filename_src("IERC20.sol", false);
-    let erc721_src = get_filename_src("IERC721.sol", false);
-    let ah_coin_src = get_filename_src("AuctionHouseCoin.sol", false);
-
-    // let solc_config = r#"
-    //         {
-    //             "language": "Solidity",
-    //             "sources": {
-    //                 "input.sol": { "content": "<%src%>" },
-    //                 "BN254.sol": { "content": "<%bn254_src%>" },
-    //                 "Pedersen.sol": { "content": "<%pedersen_lib_src%>" },
-    //                 "BulletproofsVerifier.sol": { "content": "<%bulletproofs_lib_src%>" },
-    //                 "IERC20.sol": { "content": "<%erc20_src%>" },
-    //                 "IERC721.sol": { "content": "<%erc721_src%>" },
-    //                 "AuctionHouseCoin.sol": { "content": "<%ah_coin_src%>" }
-
-    //             },
-    //             "settings": {
-    //                 "optimizer": { "enabled": <%opt%> },
-    //                 "outputSelection": {
-    //                     "*": {
-    //                         "*": [
-    //                             "evm.bytecode.object", "abi"
-    //                         ],
-    //                     "": [ "*" ] } },
-    //                 "libraries": {
-    //                     "BulletproofsVerifier.sol": {
-    //                         "BulletproofsVerifier": "<%bulletproofs_lib_addr%>"
-    //                     }
-    //                 }
-    //             }
-    //         }"#
-    let solc_config = r#"
-    {
-        "language": "Solidity",
-        "sources": {
-            "input.sol": { "content": "<%src%>" },
-            "IERC20.sol": { "content": "<%erc20_src%>" },
-            "IERC721.sol": { "content": "<%erc721_src%>" },
-            "AuctionHouseCoin.sol": { "content": "<%ah_coin_src%>" }
-
-        },
-        "settings": {
-            "optimizer": { "enabled": <%opt%> },
-            "outputSelection": {
-                "*": {
-                    "*": [
-                        "evm.bytecode.object", "abi"
-                    ],
-                "": [ "*" ] } }
-        }
-    }"#
-    .replace("<%opt%>", &false.to_string()) // Needed to disable opt for a BigNumber assembly instruction
-    // .replace("<%bn254_src%>", &bn254_src)
-    // .replace("<%pedersen_lib_src%>", &pedersen_lib_src)
-    // .replace("<%bulletproofs_lib_src%>", &bulletproofs_src)
-    // .replace(
-    //     "<%bulletproofs_lib_addr%>",
-    //     &bulletproofs_contract_addr.to_string(),
-    // )
-    .replace("<%erc20_src%>", &erc20_src)
-    .replace("<%erc721_src%>", &erc721_src)
-    .replace("<%ah_coin_src%>", &ah_coin_src)
-    .replace("<%src%>", &ahc_factory_src);
-
-    let contract = Contract::compile_from_config(&solc_config, "AuctionHouseCoinFactory").unwrap();
-    let create_result = evm
-        .deploy(
-            contract.encode_create_contract_bytes(&[]).unwrap(),
-            &deployer,
-        )
-        .unwrap();
-    let contract_addr = create_result.addr.clone();
-    // println!(
-    //   "AHC Factory contract deployed at address: {:?}",
-    //   contract_addr
-    // );
-    (contract, contract_addr)
+pub fn deploy_ahc_factory(evm: &mut Evm, deployer: &Address) -> (Contract, Address) {
+    simulation::deploy_ahc_factory(evm, deployer).unwrap()
 }
 
 pub fn deploy_ah(
@@ -480,25 +316,26 @@ pub fn deploy_ah(
 ) -> Contract {
     // Compile auction house contract from template
     // println!("Compiling auction house contract...");
-    let auction_house_src = get_filename_src("AuctionHouse.sol", true);
-    let bn254_src = get_bn254_library_src();
-    let bigint_src = get_bigint_library_src();
-    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false);
-    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false);
-    let poe_src = get_filename_src("PoEVerifier.sol", false);
-    let fkps_src = get_fkps_src(&time_pp.x.n, &time_pp.y.n, MOD_BITS, TIME_PARAM, false);
-    let tc_src = get_filename_src("TC.sol", false);
+    let auction_house_src = get_filename_src("AuctionHouse.sol", true).unwrap();
+    let bn254_src = get_bn254_library_src().unwrap();
+    let bigint_src = get_bigint_library_src().unwrap();
+    let pedersen_lib_src = get_pedersen_library_src(&ped_pp, false).unwrap();
+    let rsa_src = get_rsa_library_src(TestRsaParams::M.deref(), MOD_BITS, false).unwrap();
+    let poe_src = get_filename_src("PoEVerifier.sol", false).unwrap();
+    let fkps_src = get_fkps_src(&time_pp.x.n, &time_pp.y.n, MOD_BITS, TIME_PARAM, false).unwrap();
+    let tc_src = get_filename_src("TC.sol", false).unwrap();
     let bulletproofs_src = get_bulletproofs_verifier_contract_src(
         &bulletproofs_pp,
         &ped_pp,
         NUM_BID_BITS,
         LOG_NUM_BID_BITS,
         false,
-    );
-    let erc20_src = get_filename_src("IERC20.sol", false);
-    let erc721_src = get_filename_src("IERC721.sol", false);
-    let ahc_factory_src = get_filename_src("AuctionHouseCoinFactory.sol", false);
-    let ah_coin_src = get_filename_src("AuctionHouseCoin.sol", false);
+    )
+    .unwrap();
+    let erc20_src = get_filename_src("IERC20.sol", false).unwrap();
+    let erc721_src = get_filename_src("IERC721.sol", false).unwrap();
+    let ahc_factory_src = get_filename_src("AuctionHouseCoinFactory.sol", false).unwrap();
+    let ah_coin_src = get_filename_src("AuctionHouseCoin.sol", false).unwrap();
 
     let solc_config = r#"
             {
@@ -702,7 +539,12 @@ pub fn collect_bids(
         let (bidder, bidder_addr) = bidders.get_mut(i).unwrap();
         let mut start = Instant::now();
         let (bid_proposal, opening) = bidder
-            .propose_bid(&mut rng, &house_pp, &auction_pp, (i as u32 + 1) * 20)
+            .propose_bid(
+                &mut rng,
+                &house_pp,
+                &auction_pp,
+                BidValue::new((i as u32 + 1) * 20),
+            )
             .unwrap();
         let mut end = start.elapsed().as_nanos();
         place_bid_client_vec.push(end as u64);
@@ -714,9 +556,9 @@ pub fn collect_bids(
                         "bidAuction",
                         &[
                             Token::Uint(U256::from(0)),
-                            encode_tc_comm::<Bn254, _>(&bid_proposal.comm_bid),
-                            encode_bulletproof::<Bn254>(&bid_proposal.range_proof_bid),
-                            encode_bulletproof::<Bn254>(&bid_proposal.range_proof_balance),
+                            encode_tc_comm::<Bn254, _>(&bid_proposal.comm_bid).unwrap(),
+                            encode_bulletproof::<Bn254>(&bid_proposal.range_proof_bid).unwrap(),
+                            encode_bulletproof::<Bn254>(&bid_proposal.range_proof_balance).unwrap(),
                         ],
                     )
                     .unwrap(),
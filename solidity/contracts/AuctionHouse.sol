@@ -13,12 +13,20 @@ import "./AuctionHouseCoinFactory.sol";
 contract AuctionHouse is IERC721Receiver {
     AuctionHouseCoinFactory AHCF_contract;
     AuctionHouseCoin AHC_contract;
+    IERC20 coin; // token deposited into the house, AHCoin unless an existing ERC-20 is given
+    bool external_coin;
+    mapping(address => uint256) deposits; // balances held by the house for an external coin
 
     mapping(uint256 => Auction) active_auctions;
     uint256 ctr_auction;
 
     mapping(address => BN254.G1Point) active_bid_comms;
 
+    struct NFT {
+        IERC721 token;
+        uint256 token_id;
+    }
+
     struct Auction {
         uint256 start_block;
         uint256 bid_collection_end_block;
@@ -34,8 +42,7 @@ contract AuctionHouse is IERC721Receiver {
         mapping(bytes32 => bool) comms;
         uint256 bids_to_open;
         uint256 total_valid_bids;
-        IERC721 token;
-        uint256 token_id;
+        NFT[] bundle; // tokens auctioned together, all transferred to the winner
         address owner;
         address winner;
         uint256 first_price;
@@ -45,19 +52,35 @@ contract AuctionHouse is IERC721Receiver {
     enum AuctionPhase { BidCollection, BidSelfOpening, BidForceOpening, Complete }
 
     // TODO: Allow auctions to have different time parameters
+    // A zero coin address creates a new AHCoin through the factory
     constructor(
             // address AHC_contract_addr
-            address AHCF_addr
+            address AHCF_addr,
+            address coin_addr
             ) {
         // AHC_contract = AuctionHouseCoin(AHC_contract_addr);
         AHCF_contract = AuctionHouseCoinFactory(AHCF_addr);
-        AHC_contract = AuctionHouseCoin(AHCF_contract.newAHCoin());
+        if (coin_addr == address(0)) {
+            AHC_contract = AuctionHouseCoin(AHCF_contract.newAHCoin());
+            coin = IERC20(address(AHC_contract));
+        } else {
+            coin = IERC20(coin_addr);
+            external_coin = true;
+        }
     }
 
     function get_AHCoin_address() public returns (address) {
         return address(AHC_contract);
     }
 
+    function paramsHash() public pure returns (bytes32) {
+        return BulletproofsVerifier.paramsHash();
+    }
+
+    function get_coin_address() public view returns (address) {
+        return address(coin);
+    }
+
 
     // IERC-721 Receiver Implementation
 
@@ -74,23 +97,45 @@ contract AuctionHouse is IERC721Receiver {
     // Auction House Implementation
 
     function queryDeposit(address user) public view returns (uint256) {
+      if (external_coin) {
+        return deposits[user];
+      }
       return AHC_contract.queryDeposit(user);
     }
 
     function setDeposit(address user, uint256 amount) public {
+      if (external_coin) {
+        deposits[user] = amount;
+        return;
+      }
       return AHC_contract.setDeposit(user, amount);
     }
 
     function incrementDeposit(address user, uint256 amount) public {
+      if (external_coin) {
+        deposits[user] += amount;
+        return;
+      }
       return AHC_contract.incrementDeposit(user, amount);
     }
 
     function decrementDeposit(address user, uint256 amount) public {
+      if (external_coin) {
+        deposits[user] -= amount;
+        return;
+      }
       return AHC_contract.decrementDeposit(user, amount);
     }
 
 
 
+    // AHCoin is deposited through the coin contract, an external coin is transferred to the house
+    function deposit(uint256 amt) public {
+        require(external_coin, "Deposit AHCoin through the coin contract");
+        require(coin.transferFrom(msg.sender, address(this), amt));
+        incrementDeposit(msg.sender, amt);
+    }
+
     // TODO: Optimization: Shouldn't need to provide range proof if no active bids
     function withdraw(uint256 amt, BulletproofsVerifier.Proof memory proof) public {
         uint256 balance_less_amt = queryDeposit(msg.sender) - amt;
@@ -98,13 +143,12 @@ contract AuctionHouse is IERC721Receiver {
         BN254.G1Point memory ped_g = Pedersen.publicParams().G;
         BN254.G1Point memory balance_comm = BN254.g1add(BN254.g1mul(ped_g, balance_less_amt), BN254.g1negate(active_bids_comm));
         require(BulletproofsVerifier.verify(balance_comm, proof));
-        AHC_contract.transfer(msg.sender, amt); 
+        require(coin.transfer(msg.sender, amt));
         setDeposit(msg.sender, balance_less_amt);
     }
 
     function newAuction(
-            address token, 
-            uint256 token_id, 
+            NFT[] memory bundle,
             uint256 bid_collection_num_blocks, 
             uint256 bid_self_open_num_blocks, 
             uint256 reward_self_open, 
@@ -113,10 +157,12 @@ contract AuctionHouse is IERC721Receiver {
             ) public returns (uint256 id) {
         id = ctr_auction;
         Auction storage auction = active_auctions[id];
-        auction.token = IERC721(token);
-        auction.token_id = token_id;
-        require(msg.sender == auction.token.ownerOf(token_id));  
-        auction.token.safeTransferFrom(msg.sender, address(this), token_id); // Transfer token to house
+        require(bundle.length > 0);
+        for (uint i; i < bundle.length; i++) {
+            require(msg.sender == bundle[i].token.ownerOf(bundle[i].token_id));
+            bundle[i].token.safeTransferFrom(msg.sender, address(this), bundle[i].token_id); // Transfer token to house
+            auction.bundle.push(bundle[i]);
+        }
         auction.owner = msg.sender;
         auction.start_block = block.number;
         auction.bid_collection_end_block = block.number + bid_collection_num_blocks;
@@ -278,8 +324,8 @@ contract AuctionHouse is IERC721Receiver {
         uint256 price;
 
         if (auction.total_valid_bids == 0) {
-            // Return token to owner
-            auction.token.transferFrom(address(this), auction.owner, auction.token_id);
+            // Return bundle to owner
+            transferBundle(auction, auction.owner);
             return;
         } else if (auction.total_valid_bids == 1) {
             for (uint i; i < auction.bidders_list.length; i++) {
@@ -321,10 +367,17 @@ contract AuctionHouse is IERC721Receiver {
 
         decrementDeposit(winner, price);
         incrementDeposit(auction.owner, price);
-        auction.token.transferFrom(address(this), winner, auction.token_id);
+        transferBundle(auction, winner);
         auction.start_block = 0;
     }
 
+    // Any failed transfer reverts the transfers of the rest of the bundle
+    function transferBundle(Auction storage auction, address to) internal {
+        for (uint i; i < auction.bundle.length; i++) {
+            auction.bundle[i].token.transferFrom(address(this), to, auction.bundle[i].token_id);
+        }
+    }
+
     function reclaim(uint256 id) public {
         Auction storage auction = active_auctions[id];
 
@@ -55,6 +55,10 @@ contract AuctionHouseRP is IERC721Receiver {
         return address(AHC_contract);
     }
 
+    function paramsHash() public pure returns (bytes32) {
+        return BulletproofsVerifier.paramsHash();
+    }
+
 
     // IERC-721 Receiver Implementation
 
@@ -36,6 +36,11 @@ import "./Pedersen.sol";
         <%ipa_pp_vecs%>
     }
 
+    // Fingerprint of the params baked in above, checked against local params by clients
+    function paramsHash() <%visibility%> pure returns (bytes32) {
+        return <%pp_hash%>;
+    }
+
     function variableBaseMSM(BN254.G1Point[<%ipa_final_check_len%>] memory bases, uint256[<%ipa_final_check_len%>] memory exps) internal view returns (BN254.G1Point memory out) {
         out = BN254.g1mul(bases[0], exps[0]);
         for (uint i = 1; i < <%ipa_final_check_len%>; i++) {
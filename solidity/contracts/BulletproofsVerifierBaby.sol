@@ -36,6 +36,11 @@ import "./PedersenBaby.sol";
         <%ipa_pp_vecs%>
     }
 
+    // Fingerprint of the params baked in above, checked against local params by clients
+    function paramsHash() <%visibility%> pure returns (bytes32) {
+        return <%pp_hash%>;
+    }
+
     // function variableBaseMSM(BabyJubjub.G1Point[<%ipa_final_check_len%>] memory bases, uint256[<%ipa_final_check_len%>] memory exps) internal view returns (BabyJubjub.G1Point memory out) {
     //     out = BabyJubjub.g1mul(bases[0], exps[0]);
     //     for (uint i = 1; i < <%ipa_final_check_len%>; i++) {
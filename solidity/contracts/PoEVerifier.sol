@@ -130,6 +130,8 @@ import "./RSA2048.sol";
         h1 = h1 & uint256(0x1FFFFF);
         // Set high bit
         h1 = h1 | uint256(0x100000);
+        // Set low bit, even candidates are never prime
+        h2 = h2 | uint256(1);
         h.val = abi.encodePacked(h1, h2);
     }
 
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.10;
+
+import "./BigInt.sol";
+import "./PoEVerifier.sol";
+
+// Decodes certificates packed by `encode_pocklington_certificate_packed`:
+//   nonce (uint32) | step count (uint8)
+//   | per step: n (uint32), n2 (uint32), field sign bits (uint16)
+//   | per step field: end offset into the magnitudes (uint16)
+//   | big endian field magnitudes
+// Fields per step are f, a, bu, bv, v, s, sqrt, p_less_one_div_f, p_less_one_div_two,
+// b_p_div_f1, b_p_div_f2, b_p_div_two1, b_p_div_two2.
+<%con_or_lib%> PocklingtonPacked {
+
+    uint256 constant STEP_FIELDS = 13;
+    uint256 constant STEP_HEADER_LEN = 10;
+
+    function decode(bytes memory packed) <%visibility%> pure returns (PoEVerifier.PocklingtonCertificate memory cert) {
+        cert.nonce = uint32(readUint(packed, 0, 4));
+        uint256 nSteps = readUint(packed, 4, 1);
+        cert.steps = new PoEVerifier.PocklingtonStep[](nSteps);
+
+        uint256 offsetsStart = 5 + STEP_HEADER_LEN * nSteps;
+        uint256 dataStart = offsetsStart + 2 * STEP_FIELDS * nSteps;
+        uint256 start = dataStart;
+        for (uint256 i = 0; i < nSteps; i++) {
+            uint256 header = 5 + STEP_HEADER_LEN * i;
+            uint256 signs = readUint(packed, header + 8, 2);
+            BigInt.BigInt[13] memory fields;
+            for (uint256 j = 0; j < STEP_FIELDS; j++) {
+                uint256 end = dataStart + readUint(packed, offsetsStart + 2 * (i * STEP_FIELDS + j), 2);
+                fields[j] = readBigInt(packed, start, end - start, (signs >> j) & 1 == 1);
+                start = end;
+            }
+
+            PoEVerifier.PocklingtonStep memory step = cert.steps[i];
+            step.n = uint32(readUint(packed, header, 4));
+            step.n2 = uint32(readUint(packed, header + 4, 4));
+            step.f = fields[0];
+            step.a = fields[1];
+            step.bu = fields[2];
+            step.bv = fields[3];
+            step.v = fields[4];
+            step.s = fields[5];
+            step.sqrt = fields[6];
+            step.p_less_one_div_f = fields[7];
+            step.p_less_one_div_two = fields[8];
+            step.b_p_div_f1 = fields[9];
+            step.b_p_div_f2 = fields[10];
+            step.b_p_div_two1 = fields[11];
+            step.b_p_div_two2 = fields[12];
+        }
+        require(start == packed.length, "trailing bytes in packed certificate");
+    }
+
+    function readUint(bytes memory b, uint256 start, uint256 len) internal pure returns (uint256 r) {
+        for (uint256 k = 0; k < len; k++) {
+            r = (r << 8) | uint8(b[start + k]);
+        }
+    }
+
+    // Left pads the magnitude to a multiple of 32 bytes as expected by BigInt
+    function readBigInt(bytes memory b, uint256 start, uint256 len, bool neg) internal pure returns (BigInt.BigInt memory r) {
+        uint256 paddedLen = len == 0 ? 32 : ((len + 31) / 32) * 32;
+        r.val = new bytes(paddedLen);
+        for (uint256 k = 0; k < len; k++) {
+            r.val[paddedLen - len + k] = b[start + k];
+        }
+        r.neg = neg;
+    }
+}
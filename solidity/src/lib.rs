@@ -2,36 +2,148 @@
 use ark_bn254::{Bn254, Fr as F, G1Projective as G};
 use ark_ec::{bn, AffineCurve, PairingEngine, ProjectiveCurve};
 use ark_ed_on_bn254::{constraints::EdwardsVar as GV, EdwardsAffine, EdwardsProjective as E};
+use ark_ff::{FpParameters, FromBytes, PrimeField};
 // use ark_ed_on_bls12_381::{constraints::EdwardsVar as GV, EdwardsProjective as G_Groth};
 //use ark_ed_on_bn254::{constraints::EdwardsVar as GV, EdwardsProjective as EG};
 
 use digest::Digest;
 use ethabi::Token;
+use num_bigint::Sign;
 use num_traits::Signed;
-use primitive_types::U256;
+use primitive_types::{H160, U256};
 use sha3::digest;
-use std::{fs::File, io::Read, str::FromStr};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    error::Error as ErrorTrait,
+    fmt,
+    fs::File,
+    io::{self, Read},
+    path::PathBuf,
+    str::FromStr,
+};
 
-use range_proofs::bulletproofs::{serialize_group_elem, Params, PedersenParams, Proof};
+use range_proofs::bulletproofs::{Bulletproofs, Params, PedersenParams, Proof};
 use rsa::{
     bigint::BigInt,
-    hash_to_prime::{
-        hash_to_variable_output_length,
-        pocklington::{PocklingtonCert, PocklingtonCertParams, PocklingtonHash, StepCert},
+    hash_to_prime::pocklington::{
+        PocklingtonCert, PocklingtonCertParams, PocklingtonHash, StepCert,
     },
     hog::{RsaGroupParams, RsaHiddenOrderGroup},
     poe::Proof as PoEProof,
 };
 use solidity_test_utils::{
-    encode_field_element, encode_field_element_pc, encode_g2_element, encode_group_element,
-    encode_group_element_pc, encode_int_from_bytes, parse_g1_pc, parse_g1_to_solidity_string,
-    parse_g1_to_solidity_string_pc, parse_g2_to_solidity_string,
+    address::Address, contract::Contract, encode_field_element, encode_field_element_pc,
+    encode_g2_element, encode_group_element, encode_group_element_pc, encode_int_from_bytes,
+    evm::Evm, parse_g1_pc, parse_g1_to_solidity_string, parse_g1_to_solidity_string_pc,
+    parse_g2_to_solidity_string, to_be_bytes,
 };
 use timed_commitments::{basic_tc, lazy_tc};
 
 use ark_groth16::{Proof as G16Proof, VerifyingKey};
 
 use once_cell::sync::Lazy;
+
+pub mod report;
+pub mod simulation;
+
+use report::Stats;
+
+pub type Error = Box<dyn ErrorTrait>;
+
+#[derive(Debug)]
+pub enum SolidityError {
+    MissingWitness,
+    ContractFileNotFound(PathBuf),
+    Io(io::Error),
+    TemplateVar(String), // template placeholder left unsubstituted
+    FieldSize { expected: usize, found: usize }, // field element byte length
+    ProofLengthMismatch { expected: usize, found: usize }, // inner product rounds across a batch
+    MalformedToken(String),
+    PackedCertificateTooLarge, // step count or field data exceeds the packed offset width
+    BigIntTooWide { words: usize }, // negative or wider than the fixed number of words
+    CoinNotFromFactory(H160),
+}
+
+impl ErrorTrait for SolidityError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        match self {
+            SolidityError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SolidityError {
+    fn fmt(self: &Self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            SolidityError::MissingWitness => format!("certificate missing solidity witness"),
+            SolidityError::ContractFileNotFound(path) => {
+                format!("contract file not found: {}", path.display())
+            }
+            SolidityError::Io(e) => format!("error reading contract file: {}", e),
+            SolidityError::TemplateVar(var) => format!("unsubstituted template variable: {}", var),
+            SolidityError::FieldSize { expected, found } => format!(
+                "field elements are {} bytes, verifier expects {} bytes",
+                found, expected
+            ),
+            SolidityError::ProofLengthMismatch { expected, found } => format!(
+                "bulletproof has {} inner product rounds, batch expects {}",
+                found, expected
+            ),
+            SolidityError::MalformedToken(what) => format!("malformed token for {}", what),
+            SolidityError::PackedCertificateTooLarge => {
+                format!("certificate too large for packed encoding")
+            }
+            SolidityError::CoinNotFromFactory(addr) => {
+                format!("coin {:?} was not created by the AHCoin factory", addr)
+            }
+            SolidityError::BigIntTooWide { words } => {
+                format!("integer does not fit in {} unsigned 256-bit words", words)
+            }
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl From<io::Error> for SolidityError {
+    fn from(e: io::Error) -> Self {
+        SolidityError::Io(e)
+    }
+}
+
+fn read_contract_src(path: PathBuf) -> Result<String, SolidityError> {
+    let mut src_file = File::open(&path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => SolidityError::ContractFileNotFound(path.clone()),
+        _ => SolidityError::Io(e),
+    })?;
+    let mut src = String::new();
+    src_file.read_to_string(&mut src)?;
+    Ok(src)
+}
+
+/// Substitutes each `<%name%>` placeholder in `src` with `vars[name]`. Errors on placeholders
+/// missing from `vars` rather than leaving them in the rendered contract.
+pub fn render_template(src: &str, vars: &HashMap<&str, String>) -> Result<String, SolidityError> {
+    let mut out = String::with_capacity(src.len());
+    let mut rest = src;
+    while let Some(start) = rest.find("<%") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("%>")
+            .map(|i| start + i + 2)
+            .ok_or(SolidityError::TemplateVar(rest[start..].to_string()))?;
+        let name = &rest[start + 2..end - 2];
+        out.push_str(
+            vars.get(name)
+                .ok_or(SolidityError::TemplateVar(rest[start..end].to_string()))?,
+        );
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct TestRsaParams;
 
@@ -43,59 +155,43 @@ impl RsaGroupParams for TestRsaParams {
 }
 
 pub fn mean(data: &[u64]) -> Option<f64> {
-    let sum = data.iter().sum::<u64>() as f64;
-    let count = data.len();
-
-    match count {
-        positive if positive > 0 => Some((sum / count as f64)),
-        // positive if positive > 0 => Some("format!("{:01}", (sum / count as f64)").parse::<f64>().unwrap()),
-        _ => None,
-    }
+    Stats::from_samples(data).map(|stats| stats.mean)
 }
 
 pub fn std_deviation(data: &[u64]) -> Option<f64> {
-    match (mean(data), data.len()) {
-        (Some(data_mean), count) if count > 0 => {
-            let variance = data
-                .iter()
-                .map(|value| {
-                    let diff = data_mean - (*value as f64);
-                    diff * diff
-                })
-                .sum::<f64>()
-                / count as f64;
-
-            Some(variance.sqrt())
-            // Some(format!("{:04}", variance.sqrt()).parse::<f64>().unwrap())
-        }
-        _ => None,
-    }
+    Stats::from_samples(data).map(|stats| stats.std_dev)
 }
 
-pub fn get_bn254_library_src() -> String {
+pub fn get_bn254_library_src() -> Result<String, SolidityError> {
     let contract_path = format!("{}/contracts/BN254.sol", env!("CARGO_MANIFEST_DIR"));
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace("<%con_or_lib%>", "library")
-        .replace("<%visibility%>", "internal");
-    src
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        ("con_or_lib", "library".to_string()),
+        ("visibility", "internal".to_string()),
+    ]);
+    render_template(&src, &vars)
 }
 
-pub fn get_bn254_deploy_src() -> String {
+pub fn get_bn254_deploy_src() -> Result<String, SolidityError> {
     let contract_path = format!("{}/contracts/BN254.sol", env!("CARGO_MANIFEST_DIR"));
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace("<%con_or_lib%>", "library")
-        .replace("<%visibility%>", "public");
-    src
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        ("con_or_lib", "library".to_string()),
+        ("visibility", "public".to_string()),
+    ]);
+    render_template(&src, &vars)
+}
+
+// Inner product rounds for a bulletproofs vector of length `n`, panicking unless `n` is a power of
+// two. Deriving a const from it makes a mismatched bit count fail to compile.
+pub const fn log2_checked(n: u64) -> u64 {
+    assert!(
+        n.is_power_of_two(),
+        "bulletproofs length must be a power of two"
+    );
+    n.trailing_zeros() as u64
 }
 
 pub fn get_bulletproofs_verifier_contract_src_2<E: ProjectiveCurve>(
@@ -104,48 +200,31 @@ pub fn get_bulletproofs_verifier_contract_src_2<E: ProjectiveCurve>(
     n: u64,
     lg_n: u64,
     as_contract: bool,
-) -> String {
-    let pp_hash = {
-        let mut hash_input = Vec::<u8>::new();
-        hash_input.append(&mut serialize_group_elem(&ped_pp.g));
-        hash_input.append(&mut serialize_group_elem(&ped_pp.h));
-        for g in pp.g.iter() {
-            hash_input.append(&mut serialize_group_elem(g));
-        }
-        for h in pp.h.iter() {
-            hash_input.append(&mut serialize_group_elem(h));
-        }
-        hash_input.append(&mut serialize_group_elem(&pp.u));
-        hash_to_variable_output_length::<sha3::Keccak256>(&hash_input, 32)
-    };
+) -> Result<String, SolidityError> {
+    debug_assert_eq!(lg_n, log2_checked(n));
+    let pp_hash = Bulletproofs::<E, sha3::Keccak256>::params_fingerprint(pp, ped_pp);
 
     let contract_path = format!(
         "{}/contracts/BulletproofsVerifierBaby.sol",
         env!("CARGO_MANIFEST_DIR")
     );
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace(
-            "<%con_or_lib%>",
-            if as_contract { "contract" } else { "library" },
-        )
-        .replace(
-            "<%visibility%>",
-            if as_contract { "public" } else { "internal" },
-        )
-        .replace("<%pp_hash%>", &format!("0x{}", hex::encode(&pp_hash)))
-        .replace("<%ipa_pp_u%>", &parse_g1_to_solidity_string_pc::<E>(&pp.u))
-        .replace("<%ipa_pp_len%>", &n.to_string())
-        .replace("<%ipa_log_len%>", &lg_n.to_string())
-        .replace(
-            "<%ipa_final_check_len%>",
-            &(2 * n + 2 * lg_n + 8).to_string(),
-        )
-        .replace("<%ipa_pp_vecs%>", &{
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        (
+            "con_or_lib",
+            String::from(if as_contract { "contract" } else { "library" }),
+        ),
+        (
+            "visibility",
+            String::from(if as_contract { "public" } else { "internal" }),
+        ),
+        ("pp_hash", format!("0x{}", hex::encode(&pp_hash))),
+        ("ipa_pp_u", parse_g1_to_solidity_string_pc::<E>(&pp.u)),
+        ("ipa_pp_len", n.to_string()),
+        ("ipa_log_len", lg_n.to_string()),
+        ("ipa_final_check_len", (2 * n + 2 * lg_n + 8).to_string()),
+        ("ipa_pp_vecs", {
             let mut populate_ipa_pp_vec = String::new();
             for (i, (g, h)) in pp.g.iter().zip(pp.h.iter()).enumerate() {
                 populate_ipa_pp_vec.push_str(&format!(
@@ -164,8 +243,9 @@ pub fn get_bulletproofs_verifier_contract_src_2<E: ProjectiveCurve>(
                 }
             }
             populate_ipa_pp_vec
-        });
-    src
+        }),
+    ]);
+    render_template(&src, &vars)
 }
 
 pub fn get_bulletproofs_verifier_contract_src(
@@ -174,103 +254,89 @@ pub fn get_bulletproofs_verifier_contract_src(
     n: u64,
     lg_n: u64,
     as_contract: bool,
-) -> String {
-    let pp_hash = {
-        let mut hash_input = Vec::<u8>::new();
-        hash_input.append(&mut serialize_group_elem(&ped_pp.g));
-        hash_input.append(&mut serialize_group_elem(&ped_pp.h));
-        for g in pp.g.iter() {
-            hash_input.append(&mut serialize_group_elem(g));
-        }
-        for h in pp.h.iter() {
-            hash_input.append(&mut serialize_group_elem(h));
-        }
-        hash_input.append(&mut serialize_group_elem(&pp.u));
-        hash_to_variable_output_length::<sha3::Keccak256>(&hash_input, 32)
-    };
+) -> Result<String, SolidityError> {
+    debug_assert_eq!(lg_n, log2_checked(n));
+    let pp_hash = Bulletproofs::<G, sha3::Keccak256>::params_fingerprint(pp, ped_pp);
 
     let contract_path = format!(
         "{}/contracts/BulletproofsVerifier.sol",
         env!("CARGO_MANIFEST_DIR")
     );
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace(
-            "<%con_or_lib%>",
-            if as_contract { "contract" } else { "library" },
-        )
-        .replace(
-            "<%visibility%>",
-            if as_contract { "public" } else { "internal" },
-        )
-        .replace("<%pp_hash%>", &format!("0x{}", hex::encode(&pp_hash)))
-        .replace(
-            "<%ipa_pp_u%>",
-            &parse_g1_to_solidity_string::<Bn254>(&pp.u.into_affine()),
-        )
-        .replace("<%ipa_pp_len%>", &n.to_string())
-        .replace("<%ipa_log_len%>", &lg_n.to_string())
-        .replace(
-            "<%ipa_final_check_len%>",
-            &(2 * n + 2 * lg_n + 8).to_string(),
-        )
-        .replace("<%ipa_pp_vecs%>", &{
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        (
+            "con_or_lib",
+            String::from(if as_contract { "contract" } else { "library" }),
+        ),
+        (
+            "visibility",
+            String::from(if as_contract { "public" } else { "internal" }),
+        ),
+        ("pp_hash", format!("0x{}", hex::encode(&pp_hash))),
+        (
+            "ipa_pp_u",
+            parse_g1_to_solidity_string::<Bn254>(&pp.u.into_affine()),
+        ),
+        ("ipa_pp_len", n.to_string()),
+        ("ipa_log_len", lg_n.to_string()),
+        ("ipa_final_check_len", (2 * n + 2 * lg_n + 8).to_string()),
+        ("ipa_pp_vecs", {
             let mut populate_ipa_pp_vec = String::new();
-            for (i, (g, h)) in pp.g.iter().zip(pp.h.iter()).enumerate() {
+            let g_affine = G::batch_normalization_into_affine(&pp.g);
+            let h_affine = G::batch_normalization_into_affine(&pp.h);
+            for (i, (g, h)) in g_affine.iter().zip(h_affine.iter()).enumerate() {
                 populate_ipa_pp_vec.push_str(&format!(
                     "pp.ipaG[{}] = BN254.G1Point({});",
                     i,
-                    &parse_g1_to_solidity_string::<Bn254>(&g.into_affine())
+                    &parse_g1_to_solidity_string::<Bn254>(g)
                 ));
                 populate_ipa_pp_vec.push_str("\n        ");
                 populate_ipa_pp_vec.push_str(&format!(
                     "pp.ipaH[{}] = BN254.G1Point({});",
                     i,
-                    &parse_g1_to_solidity_string::<Bn254>(&h.into_affine())
+                    &parse_g1_to_solidity_string::<Bn254>(h)
                 ));
                 if i < pp.g.len() - 1 {
                     populate_ipa_pp_vec.push_str("\n        ");
                 }
             }
             populate_ipa_pp_vec
-        });
-    src
+        }),
+    ]);
+    render_template(&src, &vars)
 }
 
-pub fn get_bigint_library_src() -> String {
+pub fn get_bigint_library_src() -> Result<String, SolidityError> {
     let contract_path = format!("{}/contracts/BigInt.sol", env!("CARGO_MANIFEST_DIR"));
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src.replace("\"", "\\\"");
-    src
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::new();
+    render_template(&src, &vars)
 }
 
-pub fn get_fkps_src(h: &BigInt, z: &BigInt, m_len: usize, t: u64, as_contract: bool) -> String {
+pub fn get_fkps_src(
+    h: &BigInt,
+    z: &BigInt,
+    m_len: usize,
+    t: u64,
+    as_contract: bool,
+) -> Result<String, SolidityError> {
     let contract_path = format!("{}/contracts/FKPS.sol", env!("CARGO_MANIFEST_DIR"));
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace(
-            "<%con_or_lib%>",
-            if as_contract { "contract" } else { "library" },
-        )
-        .replace(
-            "<%visibility%>",
-            if as_contract { "public" } else { "internal" },
-        )
-        //.replace("<%pp_time%>", &format!("{}", t))
-        .replace("<%pp_time%>", &format!("{}", t))
-        .replace("<%pp_m_len%>", &format!("{}", m_len / 256))
-        .replace("<%pp_h_populate%>", &{
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        (
+            "con_or_lib",
+            String::from(if as_contract { "contract" } else { "library" }),
+        ),
+        (
+            "visibility",
+            String::from(if as_contract { "public" } else { "internal" }),
+        ),
+        ("pp_time", format!("{}", t)),
+        ("pp_m_len", format!("{}", m_len / 256)),
+        ("pp_h_populate", {
             let mut populate_h = String::new();
             for (i, u256digit) in h.to_u64_digits().1.chunks(4).rev().enumerate() {
                 populate_h.push_str(&format!(
@@ -286,8 +352,8 @@ pub fn get_fkps_src(h: &BigInt, z: &BigInt, m_len: usize, t: u64, as_contract: b
                 }
             }
             populate_h
-        })
-        .replace("<%pp_z_populate%>", &{
+        }),
+        ("pp_z_populate", {
             let mut populate_z = String::new();
             for (i, u256digit) in z.to_u64_digits().1.chunks(4).rev().enumerate() {
                 populate_z.push_str(&format!(
@@ -303,28 +369,30 @@ pub fn get_fkps_src(h: &BigInt, z: &BigInt, m_len: usize, t: u64, as_contract: b
                 }
             }
             populate_z
-        });
-    src
+        }),
+    ]);
+    render_template(&src, &vars)
 }
 
-pub fn get_rsa_library_src(m: &BigInt, m_len: usize, as_contract: bool) -> String {
+pub fn get_rsa_library_src(
+    m: &BigInt,
+    m_len: usize,
+    as_contract: bool,
+) -> Result<String, SolidityError> {
     let contract_path = format!("{}/contracts/RSA2048.sol", env!("CARGO_MANIFEST_DIR"));
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace(
-            "<%con_or_lib%>",
-            if as_contract { "contract" } else { "library" },
-        )
-        .replace(
-            "<%visibility%>",
-            if as_contract { "public" } else { "internal" },
-        )
-        .replace("<%pp_m_len%>", &format!("{}", m_len / 256))
-        .replace("<%pp_m_populate%>", &{
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        (
+            "con_or_lib",
+            String::from(if as_contract { "contract" } else { "library" }),
+        ),
+        (
+            "visibility",
+            String::from(if as_contract { "public" } else { "internal" }),
+        ),
+        ("pp_m_len", format!("{}", m_len / 256)),
+        ("pp_m_populate", {
             let mut populate_m = String::new();
             for (i, u256digit) in m.to_u64_digits().1.chunks(4).rev().enumerate() {
                 populate_m.push_str(&format!(
@@ -340,115 +408,252 @@ pub fn get_rsa_library_src(m: &BigInt, m_len: usize, as_contract: bool) -> Strin
                 }
             }
             populate_m
-        });
-    src
+        }),
+    ]);
+    render_template(&src, &vars)
 }
 
 pub fn get_pedersen_library_src2<E: ProjectiveCurve>(
     ped_pp: &PedersenParams<E>,
     as_contract: bool,
-) -> String {
+) -> Result<String, SolidityError> {
     let contract_path = format!("{}/contracts/PedersenBaby.sol", env!("CARGO_MANIFEST_DIR"));
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace(
-            "<%con_or_lib%>",
-            if as_contract { "contract" } else { "library" },
-        )
-        .replace(
-            "<%visibility%>",
-            if as_contract { "public" } else { "internal" },
-        )
-        .replace(
-            "<%ped_pp_g%>",
-            &parse_g1_to_solidity_string_pc::<E>(&ped_pp.g),
-        )
-        .replace(
-            "<%ped_pp_h%>",
-            &parse_g1_to_solidity_string_pc::<E>(&ped_pp.h),
-        );
-    src
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        (
+            "con_or_lib",
+            String::from(if as_contract { "contract" } else { "library" }),
+        ),
+        (
+            "visibility",
+            String::from(if as_contract { "public" } else { "internal" }),
+        ),
+        ("ped_pp_g", parse_g1_to_solidity_string_pc::<E>(&ped_pp.g)),
+        ("ped_pp_h", parse_g1_to_solidity_string_pc::<E>(&ped_pp.h)),
+    ]);
+    render_template(&src, &vars)
 }
 
-pub fn get_pedersen_library_src(ped_pp: &PedersenParams<G>, as_contract: bool) -> String {
+pub fn get_pedersen_library_src(
+    ped_pp: &PedersenParams<G>,
+    as_contract: bool,
+) -> Result<String, SolidityError> {
     let contract_path = format!("{}/contracts/Pedersen.sol", env!("CARGO_MANIFEST_DIR"));
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace(
-            "<%con_or_lib%>",
-            if as_contract { "contract" } else { "library" },
-        )
-        .replace(
-            "<%visibility%>",
-            if as_contract { "public" } else { "internal" },
-        )
-        .replace(
-            "<%ped_pp_g%>",
-            &parse_g1_to_solidity_string::<Bn254>(&ped_pp.g.into_affine()),
-        )
-        .replace(
-            "<%ped_pp_h%>",
-            &parse_g1_to_solidity_string::<Bn254>(&ped_pp.h.into_affine()),
-        );
-    src
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        (
+            "con_or_lib",
+            String::from(if as_contract { "contract" } else { "library" }),
+        ),
+        (
+            "visibility",
+            String::from(if as_contract { "public" } else { "internal" }),
+        ),
+        (
+            "ped_pp_g",
+            parse_g1_to_solidity_string::<Bn254>(&ped_pp.g.into_affine()),
+        ),
+        (
+            "ped_pp_h",
+            parse_g1_to_solidity_string::<Bn254>(&ped_pp.h.into_affine()),
+        ),
+    ]);
+    render_template(&src, &vars)
 }
 
-pub fn get_pedersen_deploy_src(ped_pp: &PedersenParams<G>, as_contract: bool) -> String {
+pub fn get_pedersen_deploy_src(
+    ped_pp: &PedersenParams<G>,
+    as_contract: bool,
+) -> Result<String, SolidityError> {
     let contract_path = format!("{}/contracts/Pedersen.sol", env!("CARGO_MANIFEST_DIR"));
 
-    let mut src_file = File::open(contract_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace(
-            "<%con_or_lib%>",
-            if as_contract { "library" } else { "library" },
-        )
-        .replace(
-            "<%visibility%>",
-            if as_contract { "public" } else { "public" },
-        )
-        .replace(
-            "<%ped_pp_g%>",
-            &parse_g1_to_solidity_string::<Bn254>(&ped_pp.g.into_affine()),
-        )
-        .replace(
-            "<%ped_pp_h%>",
-            &parse_g1_to_solidity_string::<Bn254>(&ped_pp.h.into_affine()),
-        );
-    src
+    let src = read_contract_src(PathBuf::from(contract_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        (
+            "con_or_lib",
+            String::from(if as_contract { "library" } else { "library" }),
+        ),
+        (
+            "visibility",
+            String::from(if as_contract { "public" } else { "public" }),
+        ),
+        (
+            "ped_pp_g",
+            parse_g1_to_solidity_string::<Bn254>(&ped_pp.g.into_affine()),
+        ),
+        (
+            "ped_pp_h",
+            parse_g1_to_solidity_string::<Bn254>(&ped_pp.h.into_affine()),
+        ),
+    ]);
+    render_template(&src, &vars)
 }
 
-pub fn get_filename_src(filename: &str, as_contract: bool) -> String {
+pub fn get_filename_src(filename: &str, as_contract: bool) -> Result<String, SolidityError> {
     let contract_path = format!("{}/contracts/", env!("CARGO_MANIFEST_DIR"));
     let full_path: String = contract_path + filename;
 
-    let mut src_file = File::open(full_path).unwrap();
-    let mut src = String::new();
-    src_file.read_to_string(&mut src).unwrap();
-    src = src
-        .replace("\"", "\\\"")
-        .replace(
-            "<%con_or_lib%>",
-            if as_contract { "contract" } else { "library" },
-        )
-        .replace(
-            "<%visibility%>",
-            if as_contract { "public" } else { "internal" },
-        );
-    src
+    let src = read_contract_src(PathBuf::from(full_path))?.replace("\"", "\\\"");
+    let vars = HashMap::from([
+        (
+            "con_or_lib",
+            String::from(if as_contract { "contract" } else { "library" }),
+        ),
+        (
+            "visibility",
+            String::from(if as_contract { "public" } else { "internal" }),
+        ),
+    ]);
+    render_template(&src, &vars)
 }
 
-pub fn encode_bulletproof<E: PairingEngine>(proof: &Proof<E::G1Projective>) -> Token {
+/// Compiles the TC contract together with the BN254, BigInt, Pedersen, RSA, PoE and FKPS
+/// libraries it links against, and deploys it from `deployer`.
+pub fn deploy_tc_contract<P: RsaGroupParams>(
+    evm: &mut Evm,
+    deployer: &Address,
+    ped_pp: &PedersenParams<G>,
+    time_pp: &basic_tc::TimeParams<P>,
+    mod_bits: usize,
+    time_param: u64,
+) -> Result<(Contract, Address), Error> {
+    let solc_config = r#"
+            {
+                "language": "Solidity",
+                "sources": {
+                    "input.sol": { "content": "<%src%>" },
+                    "BN254.sol": { "content": "<%bn254_src%>" },
+                    "Pedersen.sol": { "content": "<%pedersen_lib_src%>" },
+                    "BigInt.sol": { "content": "<%bigint_src%>" },
+                    "RSA2048.sol": { "content": "<%rsa_lib_src%>" },
+                    "PoEVerifier.sol": { "content": "<%poe_lib_src%>" },
+                    "FKPS.sol": { "content": "<%fkps_lib_src%>" }
+                },
+                "settings": {
+                    "optimizer": { "enabled": <%opt%> },
+                    "outputSelection": {
+                        "*": {
+                            "*": [
+                                "evm.bytecode.object", "abi"
+                            ],
+                        "": [ "*" ] } }
+                }
+            }"#
+    .replace("<%opt%>", &false.to_string()) // Needed to disable opt for a BigNumber assembly instruction
+    .replace("<%bn254_src%>", &get_bn254_library_src()?)
+    .replace(
+        "<%pedersen_lib_src%>",
+        &get_pedersen_library_src(ped_pp, false)?,
+    )
+    .replace("<%bigint_src%>", &get_bigint_library_src()?)
+    .replace(
+        "<%rsa_lib_src%>",
+        &get_rsa_library_src(&*P::M, mod_bits, false)?,
+    )
+    .replace(
+        "<%poe_lib_src%>",
+        &get_filename_src("PoEVerifier.sol", false)?,
+    )
+    .replace(
+        "<%fkps_lib_src%>",
+        &get_fkps_src(&time_pp.x.n, &time_pp.y.n, mod_bits, time_param, false)?,
+    )
+    .replace("<%src%>", &get_filename_src("TC.sol", true)?);
+
+    let tc_contract = Contract::compile_from_config(&solc_config, "TC")?;
+
+    let create_result = evm.deploy(tc_contract.encode_create_contract_bytes(&[])?, &deployer)?;
+    Ok((tc_contract, create_result.addr))
+}
+
+// Address of a contract created by `creator` with the CREATE opcode at `nonce`, i.e. the last
+// 20 bytes of keccak256(rlp([creator, nonce]))
+fn create_address(creator: &Address, nonce: u64) -> Address {
+    let nonce_bytes = nonce.to_be_bytes();
+    let nonce_bytes = &nonce_bytes[nonce.leading_zeros() as usize / 8..];
+    let mut rlp = vec![0, 0x80 + 20];
+    rlp.extend_from_slice(creator.0.as_bytes());
+    match nonce_bytes {
+        [b] if *b < 0x80 => rlp.push(*b),
+        _ => {
+            rlp.push(0x80 + nonce_bytes.len() as u8);
+            rlp.extend_from_slice(nonce_bytes);
+        }
+    }
+    rlp[0] = 0xc0 + (rlp.len() - 1) as u8;
+    Address(H160::from_slice(&sha3::Keccak256::digest(&rlp)[12..]))
+}
+
+/// Reads the AHCoin address from `get_AHCoin_address` on the house at `house_addr`, checking the
+/// returned word is a zero padded address of a coin created by the factory at `factory_addr`.
+pub fn ah_coin_address_from_factory(
+    evm: &mut Evm,
+    factory_addr: &Address,
+    house_addr: &Address,
+) -> Result<Address, Error> {
+    let selector = sha3::Keccak256::digest(b"get_AHCoin_address()");
+    let result = evm.call(selector[..4].to_vec(), house_addr, &Address(H160::zero()))?;
+    if result.out.len() != 32 || result.out[..12].iter().any(|b| *b != 0) {
+        return Err(Box::new(SolidityError::MalformedToken(
+            "address".to_string(),
+        )));
+    }
+    let coin_addr = Address(H160::from_slice(&result.out[12..]));
+    let factory_nonce = evm.get_account(factory_addr).map_or(0, |a| a.nonce);
+    if !(0..factory_nonce).any(|nonce| create_address(factory_addr, nonce) == coin_addr) {
+        return Err(Box::new(SolidityError::CoinNotFromFactory(coin_addr.0)));
+    }
+    Ok(coin_addr)
+}
+
+/// Checks that the bulletproofs and Pedersen params baked into the contract at `contract_addr`
+/// match the local ones, by comparing its `paramsHash()` against `params_fingerprint`
+pub fn verify_deployed_params(
+    evm: &mut Evm,
+    contract_addr: &Address,
+    ped_pp: &PedersenParams<G>,
+    bulletproofs_pp: &Params<G>,
+) -> Result<bool, Error> {
+    let selector = sha3::Keccak256::digest(b"paramsHash()");
+    let result = evm.call_static(
+        selector[..4].to_vec(),
+        contract_addr,
+        &Address(H160::zero()),
+    )?;
+    if result.out.len() != 32 {
+        return Err(Box::new(SolidityError::MalformedToken(
+            "bytes32".to_string(),
+        )));
+    }
+    let fingerprint =
+        Bulletproofs::<G, sha3::Keccak256>::params_fingerprint(bulletproofs_pp, ped_pp);
+    Ok(result.out == fingerprint)
+}
+
+// Generated verifiers hold field elements in single EVM words, as for BN254
+const EVM_FIELD_BYTES: usize = 32;
+
+fn check_evm_field_size<E: PairingEngine>() -> Result<(), SolidityError> {
+    let fq_bytes = (<<E::Fq as PrimeField>::Params as FpParameters>::MODULUS_BITS as usize + 7) / 8;
+    let fr_bytes = (<<E::Fr as PrimeField>::Params as FpParameters>::MODULUS_BITS as usize + 7) / 8;
+    match [fq_bytes, fr_bytes]
+        .into_iter()
+        .find(|b| *b != EVM_FIELD_BYTES)
+    {
+        Some(found) => Err(SolidityError::FieldSize {
+            expected: EVM_FIELD_BYTES,
+            found,
+        }),
+        None => Ok(()),
+    }
+}
+
+pub fn encode_bulletproof<E: PairingEngine>(
+    proof: &Proof<E::G1Projective>,
+) -> Result<Token, SolidityError> {
+    check_evm_field_size::<E>()?;
     let mut tokens = Vec::new();
     tokens.push(encode_group_element::<E>(&proof.comm_bits));
     tokens.push(encode_group_element::<E>(&proof.comm_blind));
@@ -473,7 +678,99 @@ pub fn encode_bulletproof<E: PairingEngine>(proof: &Proof<E::G1Projective>) -> T
     ));
     tokens.push(encode_field_element::<E>(&proof.base_a));
     tokens.push(encode_field_element::<E>(&proof.base_b));
-    Token::Tuple(tokens)
+    Ok(Token::Tuple(tokens))
+}
+
+/// Encodes proofs as a `Bulletproof[]` argument. All proofs must be for the same bit length, i.e.
+/// have the same number of inner product rounds.
+pub fn encode_bulletproofs<E: PairingEngine>(
+    proofs: &[Proof<E::G1Projective>],
+) -> Result<Token, SolidityError> {
+    let lg_n = proofs.first().map_or(0, |proof| proof.comm_ipa.len());
+    let tokens = proofs
+        .iter()
+        .map(|proof| {
+            if proof.comm_ipa.len() != lg_n {
+                return Err(SolidityError::ProofLengthMismatch {
+                    expected: lg_n,
+                    found: proof.comm_ipa.len(),
+                });
+            }
+            encode_bulletproof::<E>(proof)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Token::Array(tokens))
+}
+
+fn decode_uint(token: &Token) -> Result<U256, SolidityError> {
+    match token {
+        Token::Uint(n) => Ok(*n),
+        _ => Err(SolidityError::MalformedToken("uint".to_string())),
+    }
+}
+
+fn decode_le_bytes(n: &U256) -> Vec<u8> {
+    let mut bytes = to_be_bytes(n).to_vec();
+    bytes.reverse();
+    bytes
+}
+
+fn decode_field_element<E: PairingEngine>(token: &Token) -> Result<E::Fr, SolidityError> {
+    E::Fr::read(&decode_le_bytes(&decode_uint(token)?)[..])
+        .map_err(|_| SolidityError::MalformedToken("field element".to_string()))
+}
+
+// Inverse of `encode_group_element`, where (0, 0) encodes the point at infinity
+fn decode_group_element<E: PairingEngine>(token: &Token) -> Result<E::G1Projective, SolidityError> {
+    let (x, y) = match token {
+        Token::Tuple(xy) if xy.len() == 2 => (decode_uint(&xy[0])?, decode_uint(&xy[1])?),
+        _ => return Err(SolidityError::MalformedToken("group element".to_string())),
+    };
+    let mut bytes = decode_le_bytes(&x);
+    bytes.extend(decode_le_bytes(&y));
+    bytes.push((x.is_zero() && y.is_zero()) as u8);
+    let g = E::G1Affine::read(&bytes[..])
+        .map_err(|_| SolidityError::MalformedToken("group element".to_string()))?;
+    Ok(g.into_projective())
+}
+
+fn decode_group_elements<E: PairingEngine>(
+    token: &Token,
+) -> Result<Vec<E::G1Projective>, SolidityError> {
+    match token {
+        Token::Array(tokens) => tokens.iter().map(decode_group_element::<E>).collect(),
+        _ => Err(SolidityError::MalformedToken(
+            "group element array".to_string(),
+        )),
+    }
+}
+
+/// Inverse of `encode_bulletproof`.
+pub fn decode_bulletproof<E: PairingEngine>(
+    token: &Token,
+) -> Result<Proof<E::G1Projective>, SolidityError> {
+    check_evm_field_size::<E>()?;
+    let tokens = match token {
+        Token::Tuple(tokens) if tokens.len() == 11 => tokens,
+        _ => return Err(SolidityError::MalformedToken("bulletproof".to_string())),
+    };
+    let comm_ipa_l = decode_group_elements::<E>(&tokens[7])?;
+    let comm_ipa_r = decode_group_elements::<E>(&tokens[8])?;
+    if comm_ipa_l.len() != comm_ipa_r.len() {
+        return Err(SolidityError::MalformedToken("bulletproof".to_string()));
+    }
+    Ok(Proof {
+        comm_bits: decode_group_element::<E>(&tokens[0])?,
+        comm_blind: decode_group_element::<E>(&tokens[1])?,
+        comm_lc1: decode_group_element::<E>(&tokens[2])?,
+        comm_lc2: decode_group_element::<E>(&tokens[3])?,
+        t_x: decode_field_element::<E>(&tokens[4])?,
+        r_t_x: decode_field_element::<E>(&tokens[5])?,
+        r_ab: decode_field_element::<E>(&tokens[6])?,
+        comm_ipa: comm_ipa_l.into_iter().zip(comm_ipa_r).collect(),
+        base_a: decode_field_element::<E>(&tokens[9])?,
+        base_b: decode_field_element::<E>(&tokens[10])?,
+    })
 }
 
 pub fn encode_bulletproof_2<E: ProjectiveCurve>(proof: &Proof<E>) -> Token {
@@ -511,11 +808,75 @@ pub fn encode_bigint(n: &BigInt) -> Token {
     ])
 }
 
+/// Encodes a non-negative integer as exactly `words` big endian 256-bit words, most significant
+/// first, matching a solidity `uint256[words]` regardless of the value's leading zeros
+pub fn encode_bigint_fixed(n: &BigInt, words: usize) -> Result<Token, Error> {
+    if n.is_negative() || n.bits() > 256 * words as u64 {
+        return Err(Box::new(SolidityError::BigIntTooWide { words }));
+    }
+    let mut padded = vec![0u8; 32 * words];
+    if n.bits() > 0 {
+        let bytes = n.to_bytes_be().1;
+        let offset = padded.len() - bytes.len();
+        padded[offset..].copy_from_slice(&bytes);
+    }
+    Ok(Token::FixedArray(
+        padded
+            .chunks(32)
+            .map(|word| Token::Uint(U256::from_big_endian(word)))
+            .collect(),
+    ))
+}
+
 pub fn encode_rsa_element<P: RsaGroupParams>(elmt: &RsaHiddenOrderGroup<P>) -> Token {
     Token::Tuple(vec![encode_bigint(&elmt.n)])
 }
 
-pub fn encode_pocklington_step_certificate(cert: &StepCert) -> Token {
+/// Inverse of `encode_bigint`. The magnitude must be padded to whole 32 byte words, and zero is
+/// only accepted with a cleared sign flag.
+pub fn decode_bigint(token: &Token) -> Result<BigInt, SolidityError> {
+    let malformed = || SolidityError::MalformedToken("bigint".to_string());
+    let (bytes, negative) = match token {
+        Token::Tuple(tokens) if tokens.len() == 2 => match (&tokens[0], &tokens[1]) {
+            (Token::Bytes(bytes), Token::Bool(negative)) => (bytes, *negative),
+            _ => return Err(malformed()),
+        },
+        _ => return Err(malformed()),
+    };
+    if bytes.is_empty() || bytes.len() % 32 != 0 {
+        return Err(malformed());
+    }
+    let n = BigInt::from_bytes_be(Sign::Plus, bytes);
+    if negative && n.sign() == Sign::NoSign {
+        return Err(malformed());
+    }
+    Ok(if negative { -n } else { n })
+}
+
+/// Inverse of `encode_rsa_element`, accepting only the reduced representative `from_nat` produces.
+pub fn decode_rsa_element<P: RsaGroupParams>(
+    token: &Token,
+) -> Result<RsaHiddenOrderGroup<P>, SolidityError> {
+    let malformed = || SolidityError::MalformedToken("rsa element".to_string());
+    let n = match token {
+        Token::Tuple(tokens) if tokens.len() == 1 => decode_bigint(&tokens[0])?,
+        _ => return Err(malformed()),
+    };
+    if !n.is_positive() || &n + &n >= *P::M {
+        return Err(malformed());
+    }
+    Ok(RsaHiddenOrderGroup::from_nat(n))
+}
+
+/// Encodes a step certificate for on-chain verification.
+/// Errors if the certificate was generated without solidity witnesses.
+pub fn encode_pocklington_step_certificate(cert: &StepCert) -> Result<Token, Error> {
+    fn witness(w: &Option<BigInt>) -> Result<Token, Error> {
+        w.as_ref()
+            .map(encode_bigint)
+            .ok_or(Box::new(SolidityError::MissingWitness) as Error)
+    }
+
     let mut tokens = Vec::new();
     tokens.push(encode_bigint(&cert.f));
     tokens.push(Token::Uint(U256::from(cert.n)));
@@ -523,26 +884,166 @@ pub fn encode_pocklington_step_certificate(cert: &StepCert) -> Token {
     tokens.push(encode_bigint(&cert.a));
     tokens.push(encode_bigint(&cert.bu));
     tokens.push(encode_bigint(&cert.bv));
-    tokens.push(encode_bigint(&cert.v.as_ref().unwrap()));
-    tokens.push(encode_bigint(&cert.s.as_ref().unwrap()));
-    tokens.push(encode_bigint(&cert.expr_sqrt.as_ref().unwrap()));
-    tokens.push(encode_bigint(&cert.p_less_one_div_f.as_ref().unwrap()));
-    tokens.push(encode_bigint(&cert.p_less_one_div_two.as_ref().unwrap()));
-    tokens.push(encode_bigint(&cert.b_p_div_f1.as_ref().unwrap()));
-    tokens.push(encode_bigint(&cert.b_p_div_f2.as_ref().unwrap()));
-    tokens.push(encode_bigint(&cert.b_p_div_two1.as_ref().unwrap()));
-    tokens.push(encode_bigint(&cert.b_p_div_two2.as_ref().unwrap()));
-    Token::Tuple(tokens)
+    tokens.push(witness(&cert.v)?);
+    tokens.push(witness(&cert.s)?);
+    tokens.push(witness(&cert.expr_sqrt)?);
+    tokens.push(witness(&cert.p_less_one_div_f)?);
+    tokens.push(witness(&cert.p_less_one_div_two)?);
+    tokens.push(witness(&cert.b_p_div_f1)?);
+    tokens.push(witness(&cert.b_p_div_f2)?);
+    tokens.push(witness(&cert.b_p_div_two1)?);
+    tokens.push(witness(&cert.b_p_div_two2)?);
+    Ok(Token::Tuple(tokens))
 }
 
-pub fn encode_pocklington_certificate(cert: &PocklingtonCert) -> Token {
+pub fn encode_pocklington_certificate_checked(cert: &PocklingtonCert) -> Result<Token, Error> {
     let step_certs = Token::Array(
         cert.step_certificates
             .iter()
             .map(|c| encode_pocklington_step_certificate(c))
-            .collect::<Vec<_>>(),
+            .collect::<Result<Vec<_>, Error>>()?,
     );
-    Token::Tuple(vec![step_certs, Token::Uint(U256::from(cert.nonce))])
+    Ok(Token::Tuple(vec![
+        step_certs,
+        Token::Uint(U256::from(cert.nonce)),
+    ]))
+}
+
+// Panics if certificate was generated without solidity witnesses
+pub fn encode_pocklington_certificate(cert: &PocklingtonCert) -> Token {
+    encode_pocklington_certificate_checked(cert).unwrap()
+}
+
+const PACKED_STEP_FIELDS: usize = 13;
+const PACKED_STEP_HEADER_LEN: usize = 10;
+
+// BigInt fields of a step certificate in packed order
+fn packed_step_fields(cert: &StepCert) -> Result<Vec<&BigInt>, Error> {
+    fn witness(w: &Option<BigInt>) -> Result<&BigInt, Error> {
+        w.as_ref()
+            .ok_or(Box::new(SolidityError::MissingWitness) as Error)
+    }
+
+    Ok(vec![
+        &cert.f,
+        &cert.a,
+        &cert.bu,
+        &cert.bv,
+        witness(&cert.v)?,
+        witness(&cert.s)?,
+        witness(&cert.expr_sqrt)?,
+        witness(&cert.p_less_one_div_f)?,
+        witness(&cert.p_less_one_div_two)?,
+        witness(&cert.b_p_div_f1)?,
+        witness(&cert.b_p_div_f2)?,
+        witness(&cert.b_p_div_two1)?,
+        witness(&cert.b_p_div_two2)?,
+    ])
+}
+
+/// Encodes a certificate as a single bytes blob for the `PocklingtonPacked` decoder, with the
+/// field magnitudes unpadded and located through a table of 2-byte end offsets. See
+/// `contracts/PocklingtonPacked.sol` for the layout.
+/// Errors if the certificate was generated without solidity witnesses.
+pub fn encode_pocklington_certificate_packed(cert: &PocklingtonCert) -> Result<Token, Error> {
+    let n_steps = u8::try_from(cert.step_certificates.len())
+        .map_err(|_| Box::new(SolidityError::PackedCertificateTooLarge) as Error)?;
+    let mut headers = Vec::new();
+    headers.extend_from_slice(&cert.nonce.to_be_bytes());
+    headers.push(n_steps);
+    let mut offsets = Vec::new();
+    let mut data = Vec::new();
+    for step in cert.step_certificates.iter() {
+        let fields = packed_step_fields(step)?;
+        let signs = fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.is_negative())
+            .fold(0u16, |signs, (i, _)| signs | (1 << i));
+        headers.extend_from_slice(&step.n.to_be_bytes());
+        headers.extend_from_slice(&step.n2.to_be_bytes());
+        headers.extend_from_slice(&signs.to_be_bytes());
+        for field in fields {
+            data.extend_from_slice(&field.to_bytes_be().1);
+            let end = u16::try_from(data.len())
+                .map_err(|_| Box::new(SolidityError::PackedCertificateTooLarge) as Error)?;
+            offsets.extend_from_slice(&end.to_be_bytes());
+        }
+    }
+    Ok(Token::Bytes([headers, offsets, data].concat()))
+}
+
+/// Inverse of `encode_pocklington_certificate_packed`, mirroring the solidity decoder.
+pub fn decode_pocklington_certificate_packed(token: &Token) -> Result<PocklingtonCert, Error> {
+    let malformed = || {
+        Box::new(SolidityError::MalformedToken(
+            "packed pocklington certificate".to_string(),
+        )) as Error
+    };
+    let read_uint = |bytes: &[u8]| bytes.iter().fold(0u64, |r, b| (r << 8) | *b as u64);
+
+    let packed = match token {
+        Token::Bytes(packed) => packed,
+        _ => return Err(malformed()),
+    };
+    if packed.len() < 5 {
+        return Err(malformed());
+    }
+    let nonce = read_uint(&packed[0..4]) as u32;
+    let n_steps = packed[4] as usize;
+    let offsets_start = 5 + PACKED_STEP_HEADER_LEN * n_steps;
+    let data_start = offsets_start + 2 * PACKED_STEP_FIELDS * n_steps;
+    if packed.len() < data_start {
+        return Err(malformed());
+    }
+
+    let mut step_certificates = Vec::with_capacity(n_steps);
+    let mut start = data_start;
+    for i in 0..n_steps {
+        let header = &packed[5 + PACKED_STEP_HEADER_LEN * i..][..PACKED_STEP_HEADER_LEN];
+        let signs = read_uint(&header[8..10]);
+        let mut fields = Vec::with_capacity(PACKED_STEP_FIELDS);
+        for j in 0..PACKED_STEP_FIELDS {
+            let offset = offsets_start + 2 * (i * PACKED_STEP_FIELDS + j);
+            let end = data_start + read_uint(&packed[offset..offset + 2]) as usize;
+            if end < start || end > packed.len() {
+                return Err(malformed());
+            }
+            let sign = if (signs >> j) & 1 == 1 {
+                Sign::Minus
+            } else {
+                Sign::Plus
+            };
+            fields.push(BigInt::from_bytes_be(sign, &packed[start..end]));
+            start = end;
+        }
+        let mut fields = fields.into_iter();
+        let mut next = || fields.next().unwrap();
+        step_certificates.push(StepCert {
+            f: next(),
+            n: read_uint(&header[0..4]) as u32,
+            n2: read_uint(&header[4..8]) as u32,
+            a: next(),
+            bu: next(),
+            bv: next(),
+            v: Some(next()),
+            s: Some(next()),
+            expr_sqrt: Some(next()),
+            p_less_one_div_f: Some(next()),
+            p_less_one_div_two: Some(next()),
+            b_p_div_f1: Some(next()),
+            b_p_div_f2: Some(next()),
+            b_p_div_two1: Some(next()),
+            b_p_div_two2: Some(next()),
+        });
+    }
+    if start != packed.len() {
+        return Err(malformed());
+    }
+    Ok(PocklingtonCert {
+        step_certificates,
+        nonce,
+    })
 }
 
 pub fn encode_poe_proof<P: RsaGroupParams, HP: PocklingtonCertParams, D: Digest>(
@@ -571,11 +1072,12 @@ pub fn encode_fkps_comm<P: RsaGroupParams>(comm: &basic_tc::Comm<P>) -> Token {
 
 pub fn encode_tc_comm<E: PairingEngine, P: RsaGroupParams>(
     comm: &lazy_tc::Comm<E::G1Projective, P>,
-) -> Token {
+) -> Result<Token, SolidityError> {
+    check_evm_field_size::<E>()?;
     let mut tokens = Vec::new();
     tokens.push(encode_group_element::<E>(&comm.ped_comm));
     tokens.push(encode_fkps_comm(&comm.tc_comm));
-    Token::Tuple(tokens)
+    Ok(Token::Tuple(tokens))
 }
 
 // Openings
@@ -615,6 +1117,32 @@ pub fn encode_tc_opening<P: RsaGroupParams, HP: PocklingtonCertParams, D: Digest
     Token::Tuple(tokens)
 }
 
+// EIP-2028 calldata pricing
+const CALLDATA_ZERO_BYTE_GAS: u64 = 4;
+const CALLDATA_NONZERO_BYTE_GAS: u64 = 16;
+
+/// Length of the ABI encoded opening, which for a force opening is dominated by the Pocklington
+/// certificate of the PoE proof.
+pub fn force_open_calldata_size<P: RsaGroupParams, HP: PocklingtonCertParams, D: Digest>(
+    opening: &lazy_tc::Opening<G, P, PocklingtonHash<HP, D>>,
+) -> usize {
+    ethabi::encode(&[encode_tc_opening(opening)]).len()
+}
+
+/// Rough calldata gas of the ABI encoded opening. Excludes the selector, the other call
+/// arguments, the base transaction cost and execution.
+pub fn force_open_calldata_gas<P: RsaGroupParams, HP: PocklingtonCertParams, D: Digest>(
+    opening: &lazy_tc::Opening<G, P, PocklingtonHash<HP, D>>,
+) -> u64 {
+    ethabi::encode(&[encode_tc_opening(opening)])
+        .iter()
+        .map(|b| match b {
+            0 => CALLDATA_ZERO_BYTE_GAS,
+            _ => CALLDATA_NONZERO_BYTE_GAS,
+        })
+        .sum()
+}
+
 // Public Params
 
 pub fn encode_ped_pp_pc<G: ProjectiveCurve>(ped_pp: &PedersenParams<G>) -> Token {
@@ -670,9 +1198,9 @@ pub fn encode_tc_partial<E: PairingEngine, P: RsaGroupParams>(
     Token::Tuple(tokens)
 }
 
+// `bundle` lists the (ERC-721 contract, token id) pairs auctioned together
 pub fn encode_new_auction<E: PairingEngine, P: RsaGroupParams>(
-    erc721_contract_addr: &solidity_test_utils::address::Address,
-    token_id: u32,
+    bundle: &[(Address, u32)],
     bid_collection_num_blocks: u32,
     bid_self_open_num_blocks: u32,
     reward_self_open: u32,
@@ -680,8 +1208,17 @@ pub fn encode_new_auction<E: PairingEngine, P: RsaGroupParams>(
     fkps_pp: &basic_tc::TimeParams<P>,
 ) -> Vec<Token> {
     let mut tokens = Vec::new();
-    tokens.push(erc721_contract_addr.as_token());
-    tokens.push(Token::Uint(U256::from(token_id)));
+    tokens.push(Token::Array(
+        bundle
+            .iter()
+            .map(|(contract_addr, token_id)| {
+                Token::Tuple(vec![
+                    contract_addr.as_token(),
+                    Token::Uint(U256::from(*token_id)),
+                ])
+            })
+            .collect(),
+    ));
     tokens.push(Token::Uint(U256::from(bid_collection_num_blocks)));
     tokens.push(Token::Uint(U256::from(bid_self_open_num_blocks)));
     tokens.push(Token::Uint(U256::from(reward_self_open)));
@@ -691,8 +1228,9 @@ pub fn encode_new_auction<E: PairingEngine, P: RsaGroupParams>(
     tokens
 }
 
+// Left pads to a multiple of 32 bytes, empty input becomes a single zero word
 fn pad_to_32_byte_offset(mut bytes: Vec<u8>) -> Vec<u8> {
-    let pad_len = 32 * ((bytes.len() - 1) / 32 + 1);
+    let pad_len = 32 * ((bytes.len().max(1) + 31) / 32);
     bytes.reverse();
     bytes.resize(pad_len, 0);
     debug_assert_eq!(bytes.len() % 32, 0);
@@ -886,3 +1424,615 @@ pub fn encode_groth16_proof<E: PairingEngine>(proof: &G16Proof<E>) -> Token {
 //     }
 //     populate_m
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use num_traits::Zero;
+    use rand::{rngs::StdRng, SeedableRng};
+    use range_proofs::bulletproofs::PedersenComm;
+    use rsa::{hash_to_prime::HashToPrime, poe::PoEParams};
+    use solidity_test_utils::{
+        address::Address, contract::Contract, encode_group_elements, evm::Evm, to_be_bytes,
+    };
+    use timed_commitments::lazy_tc::LazyTC;
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct TestPocklingtonParams;
+    impl PocklingtonCertParams for TestPocklingtonParams {
+        const NONCE_SIZE: usize = 16;
+        const MAX_STEPS: usize = 5;
+        const INCLUDE_SOLIDITY_WITNESSES: bool = false;
+    }
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct WitnessPocklingtonParams;
+    impl PocklingtonCertParams for WitnessPocklingtonParams {
+        const NONCE_SIZE: usize = 16;
+        const MAX_STEPS: usize = 5;
+        const INCLUDE_SOLIDITY_WITNESSES: bool = true;
+    }
+
+    #[test]
+    fn encode_witness_free_certificate_test() {
+        let (_, cert) =
+            PocklingtonHash::<TestPocklingtonParams, sha3::Keccak256>::hash_to_prime(128, &vec![0])
+                .unwrap();
+        assert!(!cert.step_certificates.is_empty());
+        assert!(encode_pocklington_step_certificate(&cert.step_certificates[0]).is_err());
+        assert!(encode_pocklington_certificate_checked(&cert).is_err());
+    }
+
+    #[test]
+    fn packed_certificate_test() {
+        for input in [vec![0], vec![1, 2, 3]] {
+            let (_, cert) =
+                PocklingtonHash::<WitnessPocklingtonParams, sha3::Keccak256>::hash_to_prime(
+                    128, &input,
+                )
+                .unwrap();
+            let packed = encode_pocklington_certificate_packed(&cert).unwrap();
+            assert_eq!(
+                decode_pocklington_certificate_packed(&packed).unwrap(),
+                cert
+            );
+            let mut truncated = match packed {
+                Token::Bytes(bytes) => bytes,
+                _ => panic!("expected Bytes"),
+            };
+            let abi_len = ethabi::encode(&[encode_pocklington_certificate(&cert)]).len();
+            assert!(truncated.len() < abi_len);
+            truncated.pop();
+            assert!(decode_pocklington_certificate_packed(&Token::Bytes(truncated)).is_err());
+        }
+
+        let (_, cert) =
+            PocklingtonHash::<TestPocklingtonParams, sha3::Keccak256>::hash_to_prime(128, &vec![0])
+                .unwrap();
+        assert!(encode_pocklington_certificate_packed(&cert).is_err());
+    }
+
+    #[test]
+    fn packed_certificate_evm_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let solc_config = r#"
+            {
+                "language": "Solidity",
+                "sources": {
+                    "input.sol": { "content": "<%src%>" },
+                    "BigInt.sol": { "content": "<%bigint_src%>" },
+                    "RSA2048.sol": { "content": "<%rsa_lib_src%>" },
+                    "PoEVerifier.sol": { "content": "<%poe_lib_src%>" }
+                },
+                "settings": {
+                    "optimizer": { "enabled": false },
+                    "outputSelection": {
+                        "*": {
+                            "*": [
+                                "evm.bytecode.object", "abi"
+                            ],
+                        "": [ "*" ] } }
+                }
+            }"#
+        .replace("<%bigint_src%>", &get_bigint_library_src().unwrap())
+        .replace(
+            "<%rsa_lib_src%>",
+            &get_rsa_library_src(&*TestRsaParams::M, 2048, false).unwrap(),
+        )
+        .replace(
+            "<%poe_lib_src%>",
+            &get_filename_src("PoEVerifier.sol", false).unwrap(),
+        )
+        .replace(
+            "<%src%>",
+            &get_filename_src("PocklingtonPacked.sol", true).unwrap(),
+        );
+        let contract = Contract::compile_from_config(&solc_config, "PocklingtonPacked").unwrap();
+
+        let mut evm = Evm::new();
+        let deployer = Address::random(&mut rng);
+        evm.create_account(&deployer, 0);
+        let contract_addr = evm
+            .deploy(
+                contract.encode_create_contract_bytes(&[]).unwrap(),
+                &deployer,
+            )
+            .unwrap()
+            .addr;
+
+        // Decoded struct is returned ABI encoded, so it must match the unpacked encoding
+        for input in [vec![0], vec![1, 2, 3]] {
+            let (_, cert) =
+                PocklingtonHash::<WitnessPocklingtonParams, sha3::Keccak256>::hash_to_prime(
+                    128, &input,
+                )
+                .unwrap();
+            let packed = encode_pocklington_certificate_packed(&cert).unwrap();
+            let result = evm
+                .call(
+                    contract
+                        .encode_call_contract_bytes("decode", &[packed])
+                        .unwrap(),
+                    &contract_addr,
+                    &deployer,
+                )
+                .unwrap();
+            assert_eq!(
+                result.out,
+                ethabi::encode(&[encode_pocklington_certificate(&cert)])
+            );
+        }
+    }
+
+    #[test]
+    fn evm_field_size_test() {
+        fn dummy_proof<G: ProjectiveCurve>() -> Proof<G> {
+            Proof {
+                comm_bits: G::zero(),
+                comm_blind: G::zero(),
+                comm_lc1: G::zero(),
+                comm_lc2: G::zero(),
+                t_x: G::ScalarField::zero(),
+                r_t_x: G::ScalarField::zero(),
+                r_ab: G::ScalarField::zero(),
+                comm_ipa: vec![],
+                base_a: G::ScalarField::zero(),
+                base_b: G::ScalarField::zero(),
+            }
+        }
+        assert!(encode_bulletproof::<Bn254>(&dummy_proof()).is_ok());
+        // BLS12-381 base field elements are 48 bytes
+        match encode_bulletproof::<Bls12_381>(&dummy_proof()) {
+            Err(SolidityError::FieldSize { expected, found }) => {
+                assert_eq!((expected, found), (32, 48))
+            }
+            _ => panic!("expected FieldSize"),
+        }
+    }
+
+    #[test]
+    fn missing_contract_file_test() {
+        match get_filename_src("DoesNotExist.sol", false) {
+            Err(SolidityError::ContractFileNotFound(path)) => {
+                assert!(path.ends_with("contracts/DoesNotExist.sol"))
+            }
+            _ => panic!("expected ContractFileNotFound"),
+        }
+        assert!(get_filename_src("TC.sol", false).is_ok());
+    }
+
+    #[test]
+    fn render_template_test() {
+        let src = "<%con_or_lib%> Foo { uint256 t = <%pp_time%>; }";
+        let mut vars = HashMap::from([("con_or_lib", "library".to_string())]);
+        assert!(matches!(
+            render_template(src, &vars),
+            Err(SolidityError::TemplateVar(var)) if var == "<%pp_time%>"
+        ));
+        assert!(matches!(
+            render_template("uint256 t = <%pp_time;", &vars),
+            Err(SolidityError::TemplateVar(_))
+        ));
+
+        vars.insert("pp_time", "40".to_string());
+        assert_eq!(
+            render_template(src, &vars).unwrap(),
+            "library Foo { uint256 t = 40; }"
+        );
+    }
+
+    #[test]
+    fn batched_group_element_encoding_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let pp = Bulletproofs::<G, sha3::Keccak256>::gen_params(&mut rng, 8);
+        let mut points = pp.g.clone();
+        points.extend_from_slice(&pp.h);
+        let batched = encode_group_elements::<Bn254>(&points);
+        let element_wise = points
+            .iter()
+            .map(|g| encode_group_element::<Bn254>(g))
+            .collect::<Vec<_>>();
+        assert_eq!(batched, element_wise);
+    }
+
+    #[test]
+    fn encode_bulletproofs_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let pp = Bulletproofs::<G, sha3::Keccak256>::gen_params(&mut rng, 32);
+        let mut prove = |v: u64, n: u64| {
+            let v = BigInt::from(v);
+            let (comm, opening) =
+                PedersenComm::<G>::commit(&mut rng, &ped_pp, &v.to_bytes_le().1).unwrap();
+            Bulletproofs::<G, sha3::Keccak256>::prove_range(
+                &mut rng, &pp, &ped_pp, &comm, &v, &opening, n,
+            )
+            .unwrap()
+        };
+        let proofs = vec![prove(1000, 32), prove(20, 32), prove(0, 32)];
+
+        let token = encode_bulletproofs::<Bn254>(&proofs).unwrap();
+        let decoded = match token {
+            Token::Array(tokens) => tokens
+                .iter()
+                .map(|t| decode_bulletproof::<Bn254>(t).unwrap())
+                .collect::<Vec<_>>(),
+            _ => panic!("expected Array"),
+        };
+        assert_eq!(decoded, proofs);
+        assert_eq!(
+            encode_bulletproofs::<Bn254>(&[]).unwrap(),
+            Token::Array(vec![])
+        );
+
+        let mixed = vec![proofs[0].clone(), prove(7, 16)];
+        assert!(matches!(
+            encode_bulletproofs::<Bn254>(&mixed),
+            Err(SolidityError::ProofLengthMismatch {
+                expected: 5,
+                found: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn pad_to_32_byte_offset_test() {
+        assert_eq!(pad_to_32_byte_offset(vec![]), vec![0u8; 32]);
+
+        let mut expected = vec![0u8; 32];
+        expected[31] = 7;
+        assert_eq!(pad_to_32_byte_offset(vec![7]), expected);
+
+        assert_eq!(pad_to_32_byte_offset(vec![9u8; 32]), vec![9u8; 32]);
+
+        let mut expected = vec![0u8; 64];
+        expected[31..].copy_from_slice(&[5u8; 33]);
+        assert_eq!(pad_to_32_byte_offset(vec![5u8; 33]), expected);
+    }
+
+    #[test]
+    fn encode_bigint_zero_test() {
+        assert_eq!(
+            encode_bigint(&BigInt::from(0)),
+            Token::Tuple(vec![Token::Bytes(vec![0u8; 32]), Token::Bool(false)])
+        );
+    }
+
+    #[test]
+    fn decode_bigint_test() {
+        let m = (*TestRsaParams::M).clone();
+        for n in [
+            BigInt::from(0),
+            BigInt::from(7),
+            BigInt::from(-7),
+            BigInt::from(1) << 256,
+            -&m,
+            m,
+        ] {
+            assert_eq!(decode_bigint(&encode_bigint(&n)).unwrap(), n);
+        }
+
+        let bytes = Token::Bytes(vec![0u8; 32]);
+        assert!(decode_bigint(&Token::Tuple(vec![bytes.clone(), Token::Bool(true)])).is_err());
+        assert!(decode_bigint(&Token::Tuple(vec![bytes.clone()])).is_err());
+        assert!(decode_bigint(&Token::Tuple(vec![
+            Token::Bytes(vec![1u8; 31]),
+            Token::Bool(false)
+        ]))
+        .is_err());
+        assert!(decode_bigint(&Token::Tuple(vec![bytes, Token::Uint(U256::zero())])).is_err());
+    }
+
+    #[test]
+    fn decode_rsa_element_test() {
+        type Hog = RsaHiddenOrderGroup<TestRsaParams>;
+        let g = Hog::generator().power(&BigInt::from(12345));
+        let decoded = decode_rsa_element::<TestRsaParams>(&encode_rsa_element(&g)).unwrap();
+        assert_eq!(decoded, g);
+        let id = Hog::identity();
+        assert_eq!(
+            decode_rsa_element::<TestRsaParams>(&encode_rsa_element(&id)).unwrap(),
+            id
+        );
+
+        // Zero and unreduced representatives are rejected
+        for n in [BigInt::from(0), &*TestRsaParams::M - &g.n] {
+            let token = Token::Tuple(vec![encode_bigint(&n)]);
+            assert!(decode_rsa_element::<TestRsaParams>(&token).is_err());
+        }
+        assert!(decode_rsa_element::<TestRsaParams>(&encode_bigint(&g.n)).is_err());
+    }
+
+    #[test]
+    fn encode_bigint_fixed_test() {
+        let small = encode_bigint_fixed(&BigInt::from(7), 3).unwrap();
+        assert_eq!(
+            small,
+            Token::FixedArray(vec![
+                Token::Uint(U256::zero()),
+                Token::Uint(U256::zero()),
+                Token::Uint(U256::from(7)),
+            ])
+        );
+
+        // 257 bit value spills into a second word
+        let large = (BigInt::from(1) << 256) + BigInt::from(5);
+        assert_eq!(
+            encode_bigint_fixed(&large, 3).unwrap(),
+            Token::FixedArray(vec![
+                Token::Uint(U256::zero()),
+                Token::Uint(U256::one()),
+                Token::Uint(U256::from(5)),
+            ])
+        );
+        assert_eq!(
+            encode_bigint_fixed(&BigInt::from(0), 2).unwrap(),
+            Token::FixedArray(vec![Token::Uint(U256::zero()); 2])
+        );
+
+        let oversize = BigInt::from(1) << 768;
+        assert!(encode_bigint_fixed(&oversize, 3).is_err());
+        assert!(encode_bigint_fixed(&BigInt::from(-1), 3).is_err());
+    }
+
+    #[test]
+    fn log2_checked_test() {
+        const LOG_BITS: u64 = log2_checked(32);
+        assert_eq!(LOG_BITS, 5);
+        assert_eq!(log2_checked(1), 0);
+        assert_eq!(log2_checked(64), 6);
+        assert!(std::panic::catch_unwind(|| log2_checked(24)).is_err());
+        assert!(std::panic::catch_unwind(|| log2_checked(0)).is_err());
+    }
+
+    #[test]
+    fn params_fingerprint_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let pp = Bulletproofs::<G, sha3::Keccak256>::gen_params(&mut rng, 32);
+        let fingerprint = Bulletproofs::<G, sha3::Keccak256>::params_fingerprint(&pp, &ped_pp);
+        let src = get_bulletproofs_verifier_contract_src(&pp, &ped_pp, 32, 5, true).unwrap();
+        assert!(src.contains(&format!("pp.hash = 0x{};", hex::encode(&fingerprint))));
+
+        let other_pp = Bulletproofs::<G, sha3::Keccak256>::gen_params(&mut rng, 32);
+        assert_ne!(
+            Bulletproofs::<G, sha3::Keccak256>::params_fingerprint(&other_pp, &ped_pp),
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn bulletproof_evm_equivalent_verify_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let pp = Bulletproofs::<G, sha3::Keccak256>::gen_params(&mut rng, 32);
+        let v = BigInt::from(1000);
+        let (comm, opening) =
+            PedersenComm::<G>::commit(&mut rng, &ped_pp, &v.to_bytes_le().1).unwrap();
+        let proof = Bulletproofs::<G, sha3::Keccak256>::prove_range(
+            &mut rng, &pp, &ped_pp, &comm, &v, &opening, 32,
+        )
+        .unwrap();
+        assert!(
+            Bulletproofs::<G, sha3::Keccak256>::verify_range_evm_equivalent(
+                &pp, &ped_pp, &comm, 32, &proof
+            )
+            .unwrap()
+        );
+
+        let solc_config = r#"
+            {
+                "language": "Solidity",
+                "sources": {
+                    "input.sol": { "content": "<%src%>" },
+                    "Pedersen.sol": { "content": "<%pedersen_lib_src%>" },
+                    "BN254.sol": { "content": "<%bn254_src%>" }
+                },
+                "settings": {
+                    "optimizer": { "enabled": true },
+                    "outputSelection": {
+                        "*": {
+                            "*": [
+                                "evm.bytecode.object", "abi"
+                            ],
+                        "": [ "*" ] } }
+                }
+            }"#
+        .replace(
+            "<%pedersen_lib_src%>",
+            &get_pedersen_library_src(&ped_pp, false).unwrap(),
+        )
+        .replace("<%bn254_src%>", &get_bn254_library_src().unwrap())
+        .replace(
+            "<%src%>",
+            &get_bulletproofs_verifier_contract_src(&pp, &ped_pp, 32, 5, true).unwrap(),
+        );
+        let contract = Contract::compile_from_config(&solc_config, "BulletproofsVerifier").unwrap();
+
+        let mut evm = Evm::new();
+        let deployer = Address::random(&mut rng);
+        evm.create_account(&deployer, 0);
+        let contract_addr = evm
+            .deploy(
+                contract.encode_create_contract_bytes(&[]).unwrap(),
+                &deployer,
+            )
+            .unwrap()
+            .addr;
+        let input = vec![
+            encode_group_element::<Bn254>(&comm),
+            encode_bulletproof::<Bn254>(&proof).unwrap(),
+        ];
+        let result = evm
+            .call(
+                contract
+                    .encode_call_contract_bytes("verify", &input)
+                    .unwrap(),
+                &contract_addr,
+                &deployer,
+            )
+            .unwrap();
+        assert_eq!(&result.out, &to_be_bytes(&U256::from(1)));
+    }
+
+    #[test]
+    fn verify_deployed_params_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let pp = Bulletproofs::<G, sha3::Keccak256>::gen_params(&mut rng, 32);
+
+        let solc_config = r#"
+            {
+                "language": "Solidity",
+                "sources": {
+                    "input.sol": { "content": "<%src%>" },
+                    "Pedersen.sol": { "content": "<%pedersen_lib_src%>" },
+                    "BN254.sol": { "content": "<%bn254_src%>" }
+                },
+                "settings": {
+                    "optimizer": { "enabled": true },
+                    "outputSelection": {
+                        "*": {
+                            "*": [
+                                "evm.bytecode.object", "abi"
+                            ],
+                        "": [ "*" ] } }
+                }
+            }"#
+        .replace(
+            "<%pedersen_lib_src%>",
+            &get_pedersen_library_src(&ped_pp, false).unwrap(),
+        )
+        .replace("<%bn254_src%>", &get_bn254_library_src().unwrap())
+        .replace(
+            "<%src%>",
+            &get_bulletproofs_verifier_contract_src(&pp, &ped_pp, 32, 5, true).unwrap(),
+        );
+        let contract = Contract::compile_from_config(&solc_config, "BulletproofsVerifier").unwrap();
+
+        let mut evm = Evm::new();
+        let deployer = Address::random(&mut rng);
+        evm.create_account(&deployer, 0);
+        let contract_addr = evm
+            .deploy(
+                contract.encode_create_contract_bytes(&[]).unwrap(),
+                &deployer,
+            )
+            .unwrap()
+            .addr;
+        assert!(verify_deployed_params(&mut evm, &contract_addr, &ped_pp, &pp).unwrap());
+
+        let other_pp = Bulletproofs::<G, sha3::Keccak256>::gen_params(&mut rng, 32);
+        assert!(!verify_deployed_params(&mut evm, &contract_addr, &ped_pp, &other_pp).unwrap());
+        let mut other_ped_pp = ped_pp.clone();
+        other_ped_pp.h = other_ped_pp.g;
+        assert!(!verify_deployed_params(&mut evm, &contract_addr, &other_ped_pp, &pp).unwrap());
+
+        // No contract deployed there
+        assert!(
+            verify_deployed_params(&mut evm, &Address::random(&mut rng), &ped_pp, &pp).is_err()
+        );
+    }
+
+    #[test]
+    fn encode_new_auction_bundle_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (erc721_a, erc721_b) = (Address::random(&mut rng), Address::random(&mut rng));
+        let time_pp = basic_tc::TimeParams::<TestRsaParams> {
+            x: RsaHiddenOrderGroup::from_nat(BigInt::from(2)),
+            y: RsaHiddenOrderGroup::from_nat(BigInt::from(4)),
+            t: 1,
+        };
+        let tokens = encode_new_auction::<Bn254, _>(
+            &[(erc721_a.clone(), 1), (erc721_b.clone(), 2)],
+            20,
+            10,
+            200,
+            300,
+            &time_pp,
+        );
+        assert_eq!(tokens.len(), 6);
+        assert_eq!(
+            tokens[0],
+            Token::Array(vec![
+                Token::Tuple(vec![erc721_a.as_token(), Token::Uint(U256::from(1))]),
+                Token::Tuple(vec![erc721_b.as_token(), Token::Uint(U256::from(2))]),
+            ])
+        );
+    }
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct TestPoEParams;
+    impl PoEParams for TestPoEParams {
+        const HASH_TO_PRIME_ENTROPY: usize = 256;
+    }
+
+    #[test]
+    fn deploy_tc_contract_test() {
+        type TC = LazyTC<
+            G,
+            TestPoEParams,
+            TestRsaParams,
+            sha3::Keccak256,
+            PocklingtonHash<TestPocklingtonParams, sha3::Keccak256>,
+        >;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let m = [7u8; 8];
+        let (tc_comm, tc_opening) = TC::commit(&mut rng, &time_pp, &ped_pp, &m).unwrap();
+
+        let mut evm = Evm::new();
+        let deployer = Address::random(&mut rng);
+        evm.create_account(&deployer, 0);
+        let (contract, contract_addr) =
+            deploy_tc_contract(&mut evm, &deployer, &ped_pp, &time_pp, 2048, 40).unwrap();
+
+        let input = vec![
+            encode_tc_comm::<Bn254, _>(&tc_comm).unwrap(),
+            encode_tc_opening(&tc_opening),
+            Token::Uint(U256::from_little_endian(&m)),
+            encode_tc_pp::<Bn254, _>(&TestRsaParams::M, &time_pp, &ped_pp),
+        ];
+        let result = evm
+            .call(
+                contract
+                    .encode_call_contract_bytes("verOpen", &input)
+                    .unwrap(),
+                &contract_addr,
+                &deployer,
+            )
+            .unwrap();
+        assert_eq!(&result.out, &to_be_bytes(&U256::from(1)));
+        assert!(contract.abi.functions.contains_key("verForceOpen"));
+    }
+
+    #[test]
+    fn force_open_calldata_size_test() {
+        type TC = LazyTC<
+            G,
+            TestPoEParams,
+            TestRsaParams,
+            sha3::Keccak256,
+            PocklingtonHash<TestPocklingtonParams, sha3::Keccak256>,
+        >;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        let (tc_comm, self_opening) = TC::commit(&mut rng, &time_pp, &ped_pp, &[7u8; 8]).unwrap();
+        let (_, force_opening) = TC::force_open(&time_pp, &ped_pp, &tc_comm).unwrap();
+
+        let encoded = ethabi::encode(&[encode_tc_opening(&force_opening)]);
+        let size = force_open_calldata_size(&force_opening);
+        assert_eq!(size, encoded.len());
+        assert_eq!(size % 32, 0);
+        assert!(size > force_open_calldata_size(&self_opening));
+
+        let zero_bytes = encoded.iter().filter(|b| **b == 0).count() as u64;
+        assert_eq!(
+            force_open_calldata_gas(&force_opening),
+            4 * zero_bytes + 16 * (size as u64 - zero_bytes)
+        );
+    }
+}
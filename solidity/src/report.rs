@@ -0,0 +1,180 @@
+use csv::{Writer, WriterBuilder};
+use std::io::Write;
+
+use crate::Error;
+
+const FIELDS: [&str; 6] = [
+    "function",
+    "client_time",
+    "c_std",
+    "server_time",
+    "s_std",
+    "gas_cost",
+];
+
+// Mean and population standard deviation of timing samples
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Stats {
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Stats {
+    pub fn from_samples(data: &[u64]) -> Option<Self> {
+        if data.is_empty() {
+            return None;
+        }
+        let count = data.len() as f64;
+        let mean = data.iter().sum::<u64>() as f64 / count;
+        let variance = data
+            .iter()
+            .map(|value| {
+                let diff = mean - (*value as f64);
+                diff * diff
+            })
+            .sum::<f64>()
+            / count;
+        Some(Self {
+            mean,
+            std_dev: variance.sqrt(),
+        })
+    }
+}
+
+// Sink for benchmark results, one record per measured function
+pub trait BenchReporter {
+    fn record(&mut self, metric: &str, client: Stats, server: Stats, gas: u64)
+        -> Result<(), Error>;
+}
+
+// Space delimited rows under a header, flushed after each record
+pub struct CsvReporter<W: Write> {
+    writer: Writer<W>,
+}
+
+impl<W: Write> CsvReporter<W> {
+    pub fn new(writer: W) -> Result<Self, Error> {
+        let mut writer = WriterBuilder::new().delimiter(b' ').from_writer(writer);
+        writer.write_record(&FIELDS)?;
+        writer.flush()?;
+        Ok(Self { writer })
+    }
+}
+
+impl<W: Write> BenchReporter for CsvReporter<W> {
+    fn record(
+        &mut self,
+        metric: &str,
+        client: Stats,
+        server: Stats,
+        gas: u64,
+    ) -> Result<(), Error> {
+        self.writer.write_record(&[
+            metric,
+            &client.mean.to_string(),
+            &client.std_dev.to_string(),
+            &server.mean.to_string(),
+            &server.std_dev.to_string(),
+            &gas.to_string(),
+        ])?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+// One JSON object per line, keyed by the CSV header fields
+pub struct JsonReporter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonReporter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl<W: Write> BenchReporter for JsonReporter<W> {
+    fn record(
+        &mut self,
+        metric: &str,
+        client: Stats,
+        server: Stats,
+        gas: u64,
+    ) -> Result<(), Error> {
+        let values = [
+            json_string(metric),
+            client.mean.to_string(),
+            client.std_dev.to_string(),
+            server.mean.to_string(),
+            server.std_dev.to_string(),
+            gas.to_string(),
+        ];
+        let fields = FIELDS
+            .iter()
+            .zip(values.iter())
+            .map(|(k, v)| format!("{}:{}", json_string(k), v))
+            .collect::<Vec<_>>();
+        writeln!(self.writer, "{{{}}}", fields.join(","))?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_from_samples_test() {
+        let stats = Stats::from_samples(&[2, 4, 4, 4, 5, 5, 7, 9]).unwrap();
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!(stats.std_dev, 2.0);
+
+        let stats = Stats::from_samples(&[3]).unwrap();
+        assert_eq!(stats.mean, 3.0);
+        assert_eq!(stats.std_dev, 0.0);
+        assert!(Stats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn json_reporter_test() {
+        let mut out = Vec::new();
+        {
+            let mut reporter = JsonReporter::new(&mut out);
+            let client = Stats::from_samples(&[1, 2]).unwrap();
+            reporter
+                .record("submit_bid", client, Stats::default(), 21000)
+                .unwrap();
+            reporter
+                .record("odd \"name\"\n", client, client, 0)
+                .unwrap();
+        }
+        let lines = String::from_utf8(out).unwrap();
+        let records = lines
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["function"], "submit_bid");
+        assert_eq!(records[0]["client_time"], 1.5);
+        assert_eq!(records[0]["c_std"], 0.5);
+        assert_eq!(records[0]["server_time"], 0);
+        assert_eq!(records[0]["gas_cost"], 21000);
+        assert_eq!(records[1]["function"], "odd \"name\"\n");
+    }
+}
@@ -0,0 +1,626 @@
+use ethabi::Token;
+use primitive_types::U256;
+use rand::{rngs::StdRng, SeedableRng};
+use sha3::Keccak256;
+use std::{error::Error as ErrorTrait, fmt, time::Instant};
+
+use auction_house::{
+    baseline_auction::AuctionParams,
+    baseline_house::{AccountPrivateState, AuctionHouse, HouseAuctionParams, HouseParams},
+};
+use solidity_test_utils::{address::Address, contract::Contract, evm::Evm, to_be_bytes};
+
+use crate::{ah_coin_address_from_factory, get_filename_src, Error};
+
+pub type Account = AccountPrivateState<Keccak256>;
+
+pub type SimAuctionHouse = AuctionHouse<Keccak256>;
+
+#[derive(Debug)]
+pub enum SimulationError {
+    TooManyBids { bids: usize, bidders: usize },
+    UnexpectedPhase { expected: u32 },
+    UnexpectedAuctionId { expected: u32 },
+    TokenNotTransferred,
+}
+
+impl ErrorTrait for SimulationError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(self: &Self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            SimulationError::TooManyBids { bids, bidders } => {
+                format!("{} bids submitted for {} bidders", bids, bidders)
+            }
+            SimulationError::UnexpectedPhase { expected } => {
+                format!("contract not in expected auction phase {}", expected)
+            }
+            SimulationError::UnexpectedAuctionId { expected } => {
+                format!("contract did not assign auction id {}", expected)
+            }
+            SimulationError::TokenNotTransferred => format!("token not transferred to winner"),
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+/// Parameters of a simulated baseline auction house. Bidders are funded with `balance` each and
+/// the last `n_unopened` bids of an auction are never self-opened.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SimConfig {
+    pub seed: u64,
+    pub n_bidders: usize,
+    pub balance: u32,
+    pub bid_collection_blocks: u32,
+    pub bid_self_open_blocks: u32,
+    pub n_unopened: usize,
+}
+
+/// Deployed contracts and funded bidders, shared across auctions run through `run_auction`.
+pub struct Harness {
+    pub config: SimConfig,
+    pub rng: StdRng,
+    pub evm: Evm,
+    pub deployer: Address,
+    pub owner: Address,
+    pub erc721_contract: Contract,
+    pub erc721_contract_addr: Address,
+    pub ah_contract: Contract,
+    pub ah_contract_addr: Address,
+    pub ah_coin_contract: Contract,
+    pub ah_coin_contract_addr: Address,
+    pub house_pp: HouseParams,
+    pub auction_pp: HouseAuctionParams,
+    pub auction_house: SimAuctionHouse,
+    pub bidders: Vec<(Account, Address)>,
+    pub create_house_gas: u64,
+    block: u32,
+    n_auctions: u32,
+}
+
+/// Outcome of a simulated auction, with gas costs and client / server times (ns) per step.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AuctionResult {
+    pub auction_id: u32,
+    pub price: u32,
+    pub winners: Vec<u32>,
+    pub create_auction_gas: u64,
+    pub bid_gas: Vec<u64>,
+    pub bid_client_time: Vec<u64>,
+    pub bid_server_time: Vec<u64>,
+    pub self_open_gas: Vec<u64>,
+    pub self_open_server_time: Vec<u64>,
+    pub complete_gas: u64,
+    pub complete_server_time: u64,
+    pub reclaim_gas: Vec<u64>,
+}
+
+pub fn deploy_erc721(evm: &mut Evm, deployer: &Address) -> Result<(Contract, Address), Error> {
+    let solc_config = r#"
+            {
+                "language": "Solidity",
+                "sources": {
+                    "input.sol": { "content": "<%src%>" },
+                    "IERC721.sol": { "content": "<%erc721_src%>" }
+                },
+                "settings": {
+                    "optimizer": { "enabled": <%opt%> },
+                    "outputSelection": {
+                        "*": {
+                            "*": [
+                                "evm.bytecode.object", "abi"
+                            ],
+                        "": [ "*" ] } }
+                }
+            }"#
+    .replace("<%opt%>", &true.to_string())
+    .replace("<%erc721_src%>", &get_filename_src("IERC721.sol", false)?)
+    .replace("<%src%>", &get_filename_src("TestERC721.sol", true)?);
+
+    let erc721_contract = Contract::compile_from_config(&solc_config, "TestERC721")?;
+
+    // Deploy ERC-721 contract
+    let create_result = evm.deploy(
+        erc721_contract.encode_create_contract_bytes(&[
+            Token::String("TestERC721".to_string()),
+            Token::String("NFT".to_string()),
+        ])?,
+        &deployer,
+    )?;
+    Ok((erc721_contract, create_result.addr))
+}
+
+pub fn deploy_ah_coin(_evm: &mut Evm, _deployer: &Address) -> Result<Contract, Error> {
+    let auction_house_coin_src = get_filename_src("AuctionHouseCoin.sol", true)?;
+    let erc20_src = get_filename_src("IERC20.sol", false)?;
+    let erc721_src = get_filename_src("IERC721.sol", false)?;
+
+    let solc_config = r#"
+          {
+              "language": "Solidity",
+              "sources": {
+                  "input.sol": { "content": "<%src%>" },
+                  "IERC20.sol": { "content": "<%erc20_src%>" },
+                  "IERC721.sol": { "content": "<%erc721_src%>" }
+              },
+              "settings": {
+                  "optimizer": { "enabled": <%opt%> },
+                  "outputSelection": {
+                      "*": {
+                          "*": [
+                              "evm.bytecode.object", "abi"
+                          ],
+                      "": [ "*" ] } }
+              }
+          }"#
+    .replace("<%opt%>", &false.to_string()) // Needed to disable opt for a BigNumber assembly instruction
+    .replace("<%erc20_src%>", &erc20_src)
+    .replace("<%erc721_src%>", &erc721_src)
+    .replace("<%src%>", &auction_house_coin_src);
+
+    // Only compiled: coin contracts are deployed by the factory
+    Contract::compile_from_config(&solc_config, "AuctionHouseCoin")
+}
+
+pub fn deploy_ahc_factory(evm: &mut Evm, deployer: &Address) -> Result<(Contract, Address), Error> {
+    let ahc_factory_src = get_filename_src("AuctionHouseCoinFactory.sol", true)?;
+    let erc20_src = get_filename_src("IERC20.sol", false)?;
+    let erc721_src = get_filename_src("IERC721.sol", false)?;
+    let ah_coin_src = get_filename_src("AuctionHouseCoin.sol", false)?;
+
+    let solc_config = r#"
+    {
+        "language": "Solidity",
+        "sources": {
+            "input.sol": { "content": "<%src%>" },
+            "IERC20.sol": { "content": "<%erc20_src%>" },
+            "IERC721.sol": { "content": "<%erc721_src%>" },
+            "AuctionHouseCoin.sol": { "content": "<%ah_coin_src%>" }
+
+        },
+        "settings": {
+            "optimizer": { "enabled": <%opt%> },
+            "outputSelection": {
+                "*": {
+                    "*": [
+                        "evm.bytecode.object", "abi"
+                    ],
+                "": [ "*" ] } }
+        }
+    }"#
+    .replace("<%opt%>", &false.to_string()) // Needed to disable opt for a BigNumber assembly instruction
+    .replace("<%erc20_src%>", &erc20_src)
+    .replace("<%erc721_src%>", &erc721_src)
+    .replace("<%ah_coin_src%>", &ah_coin_src)
+    .replace("<%src%>", &ahc_factory_src);
+
+    let contract = Contract::compile_from_config(&solc_config, "AuctionHouseCoinFactory")?;
+    let create_result = evm.deploy(contract.encode_create_contract_bytes(&[])?, &deployer)?;
+    Ok((contract, create_result.addr))
+}
+
+fn deploy_baseline_ah(
+    evm: &mut Evm,
+    deployer: &Address,
+    ahc_factory_contract_addr: &Address,
+) -> Result<(Contract, Address, u64), Error> {
+    let auction_house_src = get_filename_src("BaselineAuctionHouse.sol", true)?;
+    let erc20_src = get_filename_src("IERC20.sol", false)?;
+    let erc721_src = get_filename_src("IERC721.sol", false)?;
+    let ahc_factory_src = get_filename_src("AuctionHouseCoinFactory.sol", false)?;
+    let ah_coin_src = get_filename_src("AuctionHouseCoin.sol", false)?;
+
+    let solc_config = r#"
+            {
+                "language": "Solidity",
+                "sources": {
+                    "input.sol": { "content": "<%src%>" },
+                    "IERC20.sol": { "content": "<%erc20_src%>" },
+                    "IERC721.sol": { "content": "<%erc721_src%>" },
+                    "AuctionHouseCoinFactory.sol": { "content": "<%ahc_factory_src%>" },
+                    "AuctionHouseCoin.sol": { "content": "<%ah_coin_src%>" }
+                },
+                "settings": {
+                    "optimizer": { "enabled": <%opt%> },
+                    "outputSelection": {
+                        "*": {
+                            "*": [
+                                "evm.bytecode.object", "abi"
+                            ],
+                        "": [ "*" ] } },
+                    "libraries": {
+                    }
+                }
+            }"#
+    .replace("<%opt%>", &false.to_string())
+    .replace("<%erc20_src%>", &erc20_src)
+    .replace("<%erc721_src%>", &erc721_src)
+    .replace("<%ahc_factory_src%>", &ahc_factory_src)
+    .replace("<%ah_coin_src%>", &ah_coin_src)
+    .replace("<%src%>", &auction_house_src);
+
+    let ah_contract = Contract::compile_from_config(&solc_config, "AuctionHouse")?;
+    let deploy_ah_result = evm.deploy(
+        ah_contract.encode_create_contract_bytes(&[ahc_factory_contract_addr.as_token()])?,
+        &deployer,
+    )?;
+    Ok((ah_contract, deploy_ah_result.addr, deploy_ah_result.gas))
+}
+
+/// Deploys the baseline auction house with its coin and a test ERC-721 contract, and funds
+/// `config.n_bidders` bidders both in the contract and in the rust auction house.
+pub fn setup_evm_and_contracts(config: &SimConfig) -> Result<Harness, Error> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let house_pp = HouseParams {};
+    // Phases are driven by block numbers; the rust house does not enforce real-time delays
+    let auction_pp = HouseAuctionParams {
+        auction_pp: AuctionParams {
+            t_bid_collection: Default::default(),
+            t_bid_self_open: Default::default(),
+        },
+    };
+
+    let mut evm = Evm::new();
+    let deployer = Address::random(&mut rng);
+    evm.create_account(&deployer, 0);
+
+    let (erc721_contract, erc721_contract_addr) = deploy_erc721(&mut evm, &deployer)?;
+    let ah_coin_contract = deploy_ah_coin(&mut evm, &deployer)?;
+    let (_ahc_factory_contract, ahc_factory_contract_addr) =
+        deploy_ahc_factory(&mut evm, &deployer)?;
+    let (ah_contract, ah_contract_addr, create_house_gas) =
+        deploy_baseline_ah(&mut evm, &deployer, &ahc_factory_contract_addr)?;
+
+    let owner = Address::random(&mut rng);
+    evm.create_account(&owner, 0);
+    let ah_coin_contract_addr =
+        ah_coin_address_from_factory(&mut evm, &ahc_factory_contract_addr, &ah_contract_addr)?;
+
+    // Create bidders and their accounts in the AH contract
+    let mut auction_house = SimAuctionHouse::new(&house_pp);
+    let mut bidders = Vec::with_capacity(config.n_bidders);
+    for _ in 0..config.n_bidders {
+        let bidder_addr = Address::random(&mut rng);
+        evm.create_account(&bidder_addr, config.balance);
+
+        let (uid, _) = auction_house.new_account(&house_pp);
+        auction_house.account_deposit(&house_pp, uid, config.balance)?;
+
+        evm.call_payable(
+            ah_coin_contract.encode_call_contract_bytes("exchangeAHCFromEther", &[])?,
+            &ah_coin_contract_addr,
+            bidder_addr,
+            U256::from(config.balance),
+        )?;
+        evm.call(
+            ah_coin_contract.encode_call_contract_bytes(
+                "approve",
+                &[
+                    ah_coin_contract_addr.as_token(),
+                    Token::Uint(U256::from(config.balance)),
+                ],
+            )?,
+            &ah_coin_contract_addr,
+            bidder_addr,
+        )?;
+        evm.call(
+            ah_coin_contract.encode_call_contract_bytes(
+                "deposit",
+                &[Token::Uint(U256::from(config.balance))],
+            )?,
+            &ah_coin_contract_addr,
+            bidder_addr,
+        )?;
+        let mut bidder = Account::new();
+        bidder.confirm_deposit(&house_pp, config.balance)?;
+        bidders.push((bidder, bidder_addr));
+    }
+
+    Ok(Harness {
+        config: config.clone(),
+        rng,
+        evm,
+        deployer,
+        owner,
+        erc721_contract,
+        erc721_contract_addr,
+        ah_contract,
+        ah_contract_addr,
+        ah_coin_contract,
+        ah_coin_contract_addr,
+        house_pp,
+        auction_pp,
+        auction_house,
+        bidders,
+        create_house_gas,
+        block: 1,
+        n_auctions: 0,
+    })
+}
+
+fn check_phase(harness: &mut Harness, auction_id: u32, expected: u32) -> Result<(), Error> {
+    let result = harness.evm.call_static(
+        harness.ah_contract.encode_call_contract_bytes(
+            "getAuctionPhase",
+            &[Token::Uint(U256::from(auction_id))],
+        )?,
+        &harness.ah_contract_addr,
+        &harness.deployer,
+    )?;
+    if result.out != to_be_bytes(&U256::from(expected)) {
+        return Err(Box::new(SimulationError::UnexpectedPhase { expected }));
+    }
+    Ok(())
+}
+
+/// Runs a fresh auction for a newly minted token, where bidder `i` bids `bids[i]` with the bid
+/// as collateral. Bids go through both the contract and the rust auction house.
+pub fn run_auction(harness: &mut Harness, bids: &[u32]) -> Result<AuctionResult, Error> {
+    if bids.len() > harness.bidders.len() {
+        return Err(Box::new(SimulationError::TooManyBids {
+            bids: bids.len(),
+            bidders: harness.bidders.len(),
+        }));
+    }
+    let house_pp = harness.house_pp.clone();
+    let auction_pp = harness.auction_pp.clone();
+    let token_id = U256::from(harness.n_auctions + 1);
+
+    // Mint token to auction (auctioned by "owner") and approve auction house to take it
+    harness.evm.call(
+        harness.erc721_contract.encode_call_contract_bytes(
+            "mint",
+            &[harness.owner.as_token(), Token::Uint(token_id)],
+        )?,
+        &harness.erc721_contract_addr,
+        &harness.deployer,
+    )?;
+    harness.evm.call(
+        harness.erc721_contract.encode_call_contract_bytes(
+            "approve",
+            &[harness.ah_contract_addr.as_token(), Token::Uint(token_id)],
+        )?,
+        &harness.erc721_contract_addr,
+        &harness.owner,
+    )?;
+
+    // Create new auction
+    let start_block = harness.block;
+    harness.evm.set_block_number(start_block);
+    let result = harness.evm.call(
+        harness.ah_contract.encode_call_contract_bytes(
+            "newAuction",
+            &[
+                harness.erc721_contract_addr.as_token(),
+                Token::Uint(token_id),
+                Token::Uint(U256::from(harness.config.bid_collection_blocks)),
+                Token::Uint(U256::from(harness.config.bid_self_open_blocks)),
+            ],
+        )?,
+        &harness.ah_contract_addr,
+        &harness.owner,
+    )?;
+    let create_auction_gas = result.gas;
+    let auction_id = harness.auction_house.new_auction(&house_pp, &auction_pp);
+    if result.out != to_be_bytes(&U256::from(auction_id)) {
+        return Err(Box::new(SimulationError::UnexpectedAuctionId {
+            expected: auction_id,
+        }));
+    }
+    harness.n_auctions += 1;
+
+    // Bid collection
+    let mut openings = Vec::with_capacity(bids.len());
+    let mut bid_gas = Vec::with_capacity(bids.len());
+    let mut bid_client_time = Vec::with_capacity(bids.len());
+    let mut bid_server_time = Vec::with_capacity(bids.len());
+    for (i, bid) in bids.iter().enumerate() {
+        let (bidder, bidder_addr) = harness.bidders.get_mut(i).unwrap();
+
+        let start = Instant::now();
+        let (bid_proposal, opening) =
+            bidder.propose_bid(&mut harness.rng, &house_pp, &auction_pp, *bid, *bid)?;
+        bid_client_time.push(start.elapsed().as_nanos() as u64);
+
+        let result = harness.evm.call(
+            harness.ah_contract.encode_call_contract_bytes(
+                "bidAuction",
+                &[
+                    Token::Uint(U256::from(auction_id)),
+                    Token::FixedBytes(bid_proposal.comm_bid.to_vec()),
+                    Token::Uint(U256::from(*bid)),
+                ],
+            )?,
+            &harness.ah_contract_addr,
+            bidder_addr,
+        )?;
+        bid_gas.push(result.gas);
+
+        bidder.confirm_bid(
+            &house_pp,
+            &auction_pp,
+            auction_id,
+            *bid,
+            &bid_proposal,
+            opening,
+        )?;
+
+        let start = Instant::now();
+        harness.auction_house.account_bid(
+            &house_pp,
+            &auction_pp,
+            auction_id,
+            i as u32,
+            &bid_proposal,
+            *bid,
+        )?;
+        bid_server_time.push(start.elapsed().as_nanos() as u64);
+        openings.push(opening);
+    }
+    check_phase(harness, auction_id, 0)?; // Bid collection enum = 0
+
+    // Self opening
+    harness
+        .evm
+        .set_block_number(start_block + harness.config.bid_collection_blocks);
+    let n_opened = bids.len().saturating_sub(harness.config.n_unopened);
+    let mut self_open_gas = Vec::with_capacity(n_opened);
+    let mut self_open_server_time = Vec::with_capacity(n_opened);
+    for i in 0..n_opened {
+        let (bidder, bidder_addr) = harness.bidders.get_mut(i).unwrap();
+        let result = harness.evm.call(
+            harness.ah_contract.encode_call_contract_bytes(
+                "selfOpenAuction",
+                &[
+                    Token::Uint(U256::from(auction_id)),
+                    Token::Uint(U256::from(bids[i])),
+                    Token::Uint(U256::from(openings[i])),
+                ],
+            )?,
+            &harness.ah_contract_addr,
+            bidder_addr,
+        )?;
+        self_open_gas.push(result.gas);
+
+        bidder.confirm_bid_self_open(&house_pp, &auction_pp, auction_id)?;
+
+        let start = Instant::now();
+        harness.auction_house.account_self_open(
+            &house_pp,
+            &auction_pp,
+            auction_id,
+            i as u32,
+            bids[i],
+            openings[i],
+        )?;
+        self_open_server_time.push(start.elapsed().as_nanos() as u64);
+    }
+    check_phase(harness, auction_id, 1)?; // Bid self open enum = 1
+
+    // Complete auction
+    harness.block =
+        start_block + harness.config.bid_collection_blocks + harness.config.bid_self_open_blocks;
+    harness.evm.set_block_number(harness.block);
+    check_phase(harness, auction_id, 2)?; // Auction complete enum = 2
+    let complete_result = harness.evm.call(
+        harness.ah_contract.encode_call_contract_bytes(
+            "completeAuction",
+            &[Token::Uint(U256::from(auction_id))],
+        )?,
+        &harness.ah_contract_addr,
+        &harness.deployer,
+    )?;
+
+    let start = Instant::now();
+    let (price, winners) = harness.auction_house.complete_kplusone_price_auction(
+        &house_pp,
+        &auction_pp,
+        auction_id,
+        0,
+    )?;
+    let complete_server_time = start.elapsed().as_nanos() as u64;
+
+    // Losing bidders reclaim their opened bids
+    let mut reclaim_gas = Vec::new();
+    for i in 0..bids.len() {
+        if winners.contains(&(i as u32)) {
+            continue;
+        }
+        let result = harness.evm.call(
+            harness
+                .ah_contract
+                .encode_call_contract_bytes("reclaim", &[Token::Uint(U256::from(auction_id))])?,
+            &harness.ah_contract_addr,
+            &harness.bidders[i].1,
+        )?;
+        reclaim_gas.push(result.gas);
+    }
+
+    // Check token was transferred to winning bidder
+    for uid in winners.iter() {
+        let result = harness.evm.call(
+            harness
+                .erc721_contract
+                .encode_call_contract_bytes("ownerOf", &[Token::Uint(token_id)])?,
+            &harness.erc721_contract_addr,
+            &harness.deployer,
+        )?;
+        if &result.out[12..] != harness.bidders[*uid as usize].1.as_ref().as_bytes() {
+            return Err(Box::new(SimulationError::TokenNotTransferred));
+        }
+    }
+
+    Ok(AuctionResult {
+        auction_id,
+        price,
+        winners,
+        create_auction_gas,
+        bid_gas,
+        bid_client_time,
+        bid_server_time,
+        self_open_gas,
+        self_open_server_time,
+        complete_gas: complete_result.gas,
+        complete_server_time,
+        reclaim_gas,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitive_types::H160;
+
+    #[test]
+    fn run_auction_test() {
+        let config = SimConfig {
+            seed: 1,
+            n_bidders: 3,
+            balance: 300,
+            bid_collection_blocks: 20,
+            bid_self_open_blocks: 10,
+            n_unopened: 0,
+        };
+        let mut harness = setup_evm_and_contracts(&config).unwrap();
+        let result = run_auction(&mut harness, &[40, 100, 60]).unwrap();
+        assert_eq!(result.winners, vec![1]);
+        assert_eq!(result.price, 60);
+        assert_eq!(result.bid_gas.len(), 3);
+        assert_eq!(result.self_open_gas.len(), 3);
+        assert_eq!(result.reclaim_gas.len(), 2);
+    }
+
+    #[test]
+    fn ah_coin_address_from_factory_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut evm = Evm::new();
+        let deployer = Address::random(&mut rng);
+        evm.create_account(&deployer, 0);
+        let (_, factory_addr) = deploy_ahc_factory(&mut evm, &deployer).unwrap();
+        let (ah_contract, ah_addr, _) =
+            deploy_baseline_ah(&mut evm, &deployer, &factory_addr).unwrap();
+
+        let result = evm
+            .call(
+                ah_contract
+                    .encode_call_contract_bytes("get_AHCoin_address", &[])
+                    .unwrap(),
+                &ah_addr,
+                &deployer,
+            )
+            .unwrap();
+        let sliced = Address(H160::from_slice(&result.out[12..]));
+        assert_eq!(
+            ah_coin_address_from_factory(&mut evm, &factory_addr, &ah_addr).unwrap(),
+            sliced
+        );
+
+        // Coin was not created by another factory
+        let (_, other_factory_addr) = deploy_ahc_factory(&mut evm, &deployer).unwrap();
+        assert!(ah_coin_address_from_factory(&mut evm, &other_factory_addr, &ah_addr).is_err());
+    }
+}
@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.10;
+
+contract Reverter {
+    function fail() public pure {
+        require(false, "nope");
+    }
+
+    function divide(uint a, uint b) public pure returns (uint) {
+        return a / b;
+    }
+}
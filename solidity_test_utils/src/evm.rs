@@ -1,5 +1,6 @@
+use ethabi::{decode, ParamType, Token};
 use primitive_types::U256;
-use revm::{AccountInfo, InMemoryDB, Log, Return, TransactOut, TransactTo, EVM};
+use revm::{AccountInfo, Database, Filth, InMemoryDB, Log, Return, TransactOut, TransactTo, EVM};
 
 use crate::{address::Address, Error, EvmTestError};
 
@@ -17,15 +18,42 @@ pub struct CreateContractResult {
     pub gas: u64,
 }
 
+/// Handle to a state snapshot taken by `Evm::snapshot`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SnapshotId(usize);
+
 pub struct Evm {
     vm: EVM<InMemoryDB>,
+    snapshots: Vec<InMemoryDB>, // indexed by snapshot id
 }
 
 impl Evm {
     pub fn new() -> Self {
         let mut vm = revm::new();
         vm.database(InMemoryDB::default());
-        Self { vm }
+        Self {
+            vm,
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Records the current state, accounts, storage and code, to be restored by `revert_to`
+    pub fn snapshot(&mut self) -> SnapshotId {
+        self.snapshots.push(self.vm.db().unwrap().clone());
+        SnapshotId(self.snapshots.len() - 1)
+    }
+
+    /// Restores the state recorded by `id`. Snapshots taken after `id` are discarded, while `id`
+    /// itself stays valid so the same state can be restored again.
+    pub fn revert_to(&mut self, id: SnapshotId) -> Result<(), Error> {
+        let db = self
+            .snapshots
+            .get(id.0)
+            .ok_or(Box::new(EvmTestError("snapshot not found".to_string())))?
+            .clone();
+        self.snapshots.truncate(id.0 + 1);
+        self.vm.database(db);
+        Ok(())
     }
 
     pub fn call(&mut self, input: Vec<u8>, addr: &Address, caller: &Address) -> Result<CallResult, Error> {
@@ -37,6 +65,54 @@ impl Evm {
             TransactOut::Call(out) => Ok(out.to_vec()),
             _ => Err(Box::new(EvmTestError("call contract function failed".to_string()))),
         }?;
+        if matches!(op_out, Return::Revert) {
+            return Err(Box::new(EvmTestError(format!(
+                "call reverted: {}",
+                decode_revert_reason(&out)
+            ))));
+        }
+        Ok(CallResult {
+            op_out,
+            out,
+            gas,
+            log_out,
+        })
+    }
+
+    /// Executes a call like `eth_call` with `STATICCALL` semantics. State is never committed, and
+    /// a call that changes storage, emits logs, creates or self-destructs an account is rejected
+    /// as reverted. Writes are found by diffing the resulting state, so an `SSTORE` of the value
+    /// already stored goes unnoticed.
+    pub fn call_static(&mut self, input: Vec<u8>, addr: &Address, caller: &Address) -> Result<CallResult, Error> {
+        self.vm.env.tx.caller = caller.as_ref().clone();
+        self.vm.env.tx.transact_to = TransactTo::Call(addr.as_ref().clone());
+        self.vm.env.tx.data = input.into();
+        let (op_out, tx_out, gas, state, log_out) = self.vm.transact();
+        let out = match tx_out {
+            TransactOut::Call(out) => Ok(out.to_vec()),
+            _ => Err(Box::new(EvmTestError("call contract function failed".to_string()))),
+        }?;
+        if matches!(op_out, Return::Revert) {
+            return Err(Box::new(EvmTestError(format!(
+                "call reverted: {}",
+                decode_revert_reason(&out)
+            ))));
+        }
+        // Loaded storage slots are part of the state too, only changed values count as writes
+        let db = self.vm.db().unwrap();
+        let mutated = !log_out.is_empty()
+            || state.iter().any(|(address, account)| {
+                matches!(account.filth, Filth::Destroyed | Filth::NewlyCreated)
+                    || account
+                        .storage
+                        .iter()
+                        .any(|(index, value)| db.storage(*address, *index) != *value)
+            });
+        if mutated {
+            return Err(Box::new(EvmTestError(
+                "call reverted: state mutation in static call".to_string(),
+            )));
+        }
         Ok(CallResult {
             op_out,
             out,
@@ -130,3 +206,30 @@ impl Evm {
         self.vm.env.block.number = U256::from(block_num);
     }
 }
+
+/// Describes revert output, decoding `Error(string)` reasons and `Panic(uint256)` codes and
+/// reporting the selector of any other error
+pub fn decode_revert_reason(out: &[u8]) -> String {
+    if out.len() < 4 {
+        return format!("no reason (0x{})", hex::encode(out));
+    }
+    let (selector, data) = out.split_at(4);
+    let decoded = match selector {
+        [0x08, 0xc3, 0x79, 0xa0] => match decode(&[ParamType::String], data) {
+            Ok(tokens) => match tokens.as_slice() {
+                [Token::String(reason)] => Some(reason.clone()),
+                _ => None,
+            },
+            Err(_) => None,
+        },
+        [0x4e, 0x48, 0x7b, 0x71] => match decode(&[ParamType::Uint(256)], data) {
+            Ok(tokens) => match tokens.as_slice() {
+                [Token::Uint(code)] => Some(format!("panic 0x{:x}", code)),
+                _ => None,
+            },
+            Err(_) => None,
+        },
+        _ => None,
+    };
+    decoded.unwrap_or(format!("error with selector 0x{}", hex::encode(selector)))
+}
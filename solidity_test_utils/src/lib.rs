@@ -127,6 +127,20 @@ pub fn encode_group_element<E: PairingEngine>(g: &E::G1Projective) -> Token {
     ])
 }
 
+/// Batched variant of `encode_group_element` that normalizes all points to affine at once.
+pub fn encode_group_elements<E: PairingEngine>(points: &[E::G1Projective]) -> Vec<Token> {
+    E::G1Projective::batch_normalization_into_affine(points)
+        .iter()
+        .map(|g| {
+            let (x, y) = parse_g1::<E>(g);
+            Token::Tuple(vec![
+                Token::Uint(U256::from_big_endian(&x)),
+                Token::Uint(U256::from_big_endian(&y)),
+            ])
+        })
+        .collect()
+}
+
 pub fn encode_g2_element<E: PairingEngine>(g: &E::G2Affine) -> Token {
     let (x1, x2, y1, y2) = parse_g2::<E>(&g);
     let mut tokens = Vec::new();
@@ -235,4 +249,165 @@ mod tests {
         assert_eq!(&get_result.out, &to_be_bytes(&U256::from(40)));
         println!("{:?}", get_result);
     }
+
+    #[test]
+    fn snapshot_revert_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let contract_path = format!(
+            "{}/contracts/simple_storage.sol",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let contract =
+            Contract::compile_from_solidity_file(contract_path, "SimpleStorage", false).unwrap();
+
+        let mut evm = Evm::new();
+        let deployer = Address::random(&mut rng);
+        evm.create_account(&deployer, 0);
+        let contract_addr = evm
+            .deploy(
+                contract.encode_create_contract_bytes(&[]).unwrap(),
+                &deployer,
+            )
+            .unwrap()
+            .addr;
+        let set = |evm: &mut Evm, value: u64| {
+            evm.call(
+                contract
+                    .encode_call_contract_bytes(
+                        "set",
+                        &[Token::Tuple(vec![Token::Uint(U256::from(value))])],
+                    )
+                    .unwrap(),
+                &contract_addr,
+                &deployer,
+            )
+            .unwrap();
+        };
+        let get = |evm: &mut Evm| {
+            evm.call(
+                contract.encode_call_contract_bytes("get", &[]).unwrap(),
+                &contract_addr,
+                &deployer,
+            )
+            .unwrap()
+            .out
+        };
+
+        set(&mut evm, 40);
+        let snapshot = evm.snapshot();
+        set(&mut evm, 50);
+        evm.set_account_balance(&deployer, 7).unwrap();
+        assert_eq!(get(&mut evm), to_be_bytes(&U256::from(50)));
+
+        evm.revert_to(snapshot).unwrap();
+        assert_eq!(get(&mut evm), to_be_bytes(&U256::from(40)));
+        assert_eq!(evm.balance_of(&deployer), U256::from(0));
+
+        // Snapshot stays valid, later ones are discarded
+        set(&mut evm, 60);
+        let later = evm.snapshot();
+        evm.revert_to(snapshot).unwrap();
+        assert_eq!(get(&mut evm), to_be_bytes(&U256::from(40)));
+        assert!(evm.revert_to(later).is_err());
+    }
+
+    #[test]
+    fn call_static_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let contract_path = format!(
+            "{}/contracts/simple_storage.sol",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let contract =
+            Contract::compile_from_solidity_file(contract_path, "SimpleStorage", false).unwrap();
+
+        let mut evm = Evm::new();
+        let deployer = Address::random(&mut rng);
+        evm.create_account(&deployer, 0);
+        let contract_addr = evm
+            .deploy(
+                contract.encode_create_contract_bytes(&[]).unwrap(),
+                &deployer,
+            )
+            .unwrap()
+            .addr;
+        let set_input = |value: u64| {
+            contract
+                .encode_call_contract_bytes(
+                    "set",
+                    &[Token::Tuple(vec![Token::Uint(U256::from(value))])],
+                )
+                .unwrap()
+        };
+        let get_input = contract.encode_call_contract_bytes("get", &[]).unwrap();
+        evm.call(set_input(40), &contract_addr, &deployer).unwrap();
+
+        // View function runs as usual
+        let get_result = evm
+            .call_static(get_input.clone(), &contract_addr, &deployer)
+            .unwrap();
+        assert_eq!(&get_result.out, &to_be_bytes(&U256::from(40)));
+
+        // Mutating function is rejected and leaves no trace
+        let err = evm
+            .call_static(set_input(50), &contract_addr, &deployer)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("state mutation"));
+        let get_result = evm.call(get_input, &contract_addr, &deployer).unwrap();
+        assert_eq!(&get_result.out, &to_be_bytes(&U256::from(40)));
+    }
+
+    #[test]
+    fn revert_reason_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+
+        let contract_path = format!("{}/contracts/reverter.sol", env!("CARGO_MANIFEST_DIR"));
+        let contract =
+            Contract::compile_from_solidity_file(contract_path, "Reverter", false).unwrap();
+
+        let mut evm = Evm::new();
+        let deployer = Address::random(&mut rng);
+        evm.create_account(&deployer, 0);
+        let contract_addr = evm
+            .deploy(
+                contract.encode_create_contract_bytes(&[]).unwrap(),
+                &deployer,
+            )
+            .unwrap()
+            .addr;
+
+        let err = evm
+            .call(
+                contract.encode_call_contract_bytes("fail", &[]).unwrap(),
+                &contract_addr,
+                &deployer,
+            )
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("nope"));
+
+        // Division by zero panics with code 0x12
+        let err = evm
+            .call(
+                contract
+                    .encode_call_contract_bytes(
+                        "divide",
+                        &[Token::Uint(U256::from(1)), Token::Uint(U256::from(0))],
+                    )
+                    .unwrap(),
+                &contract_addr,
+                &deployer,
+            )
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("panic 0x12"));
+
+        assert_eq!(
+            evm::decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]),
+            "error with selector 0xdeadbeef"
+        );
+    }
 }
@@ -1,7 +1,8 @@
 use crate::Error;
 use num_integer::Integer;
+use num_traits::One;
 use rsa::{
-    bigint::BigInt,
+    bigint::{bit_length, BigInt},
     hash_to_prime::HashToPrime,
     hog::{RsaGroupParams, RsaHiddenOrderGroup},
     poe::{PoE, PoEParams, Proof as PoEProof},
@@ -11,6 +12,8 @@ use std::{
     fmt::{self, Debug},
     hash::{Hash, Hasher},
     marker::PhantomData,
+    sync::atomic::{AtomicBool, Ordering},
+    task::Poll,
 };
 
 use digest::Digest;
@@ -19,11 +22,40 @@ use rand::{CryptoRng, Rng};
 
 pub type Hog<P> = RsaHiddenOrderGroup<P>; // Hidden order group **element**
 
+const CANCEL_CHECK_SQUARINGS: u64 = 1 << 10; // Squarings between checks of the cancellation flag
+
+/// Time parameters with `y = x^(2^t)`. The PoE proof checked by `ver_time_params` hashes `x`,
+/// `y` and `t` into its challenge, so the embedded `t` cannot be changed without forging the proof.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct TimeParams<RsaP: RsaGroupParams> {
     pub t: u64,
     pub x: Hog<RsaP>,
-    pub y: Hog<RsaP>,
+    pub y: Hog<RsaP>, // x^(2^t)
+}
+
+/// Security-relevant sizes of a `TimeParams`: the bit length of the RSA modulus and the number of
+/// sequential squarings needed to force open.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TimeParamsSecurity {
+    pub modulus_bits: u64,
+    pub squaring_count: u64,
+}
+
+impl<RsaP: RsaGroupParams> TimeParams<RsaP> {
+    pub fn modulus_bits(&self) -> u64 {
+        bit_length(&RsaP::M)
+    }
+
+    pub fn squaring_count(&self) -> u64 {
+        self.t
+    }
+
+    pub fn security_summary(&self) -> TimeParamsSecurity {
+        TimeParamsSecurity {
+            modulus_bits: self.modulus_bits(),
+            squaring_count: self.squaring_count(),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -38,6 +70,52 @@ pub enum Opening<RsaP: RsaGroupParams, H2P: HashToPrime> {
     FORCE(Hog<RsaP>, PoEProof<RsaP, H2P>),
 }
 
+/// Tuning for force opening. The opening is identical for every configuration: squaring `chunk`
+/// times in one modular exponentiation by 2^chunk equals squaring one step at a time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ForceOpenConfig {
+    pub chunk: u64, // squarings per modular exponentiation, 1 squares one step at a time
+}
+
+impl Default for ForceOpenConfig {
+    fn default() -> Self {
+        Self { chunk: 1 }
+    }
+}
+
+/// Progress of an incremental force opening. Holds the repeated square computed so far so that it
+/// can be persisted and resumed after an interruption.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ForceOpenState<RsaP: RsaGroupParams> {
+    pub x: Hog<RsaP>,
+    pub y: Hog<RsaP>,
+    pub squarings: u64,
+    pub max_squarings_per_poll: u64,
+    pub chunk: u64,
+}
+
+impl<RsaP: RsaGroupParams> ForceOpenState<RsaP> {
+    pub fn new(comm: &Comm<RsaP>, max_squarings_per_poll: u64) -> Self {
+        Self::with_config(comm, max_squarings_per_poll, &ForceOpenConfig::default())
+    }
+
+    pub fn with_config(
+        comm: &Comm<RsaP>,
+        max_squarings_per_poll: u64,
+        config: &ForceOpenConfig,
+    ) -> Self {
+        assert!(max_squarings_per_poll > 0);
+        assert!(config.chunk > 0);
+        Self {
+            x: comm.x.clone(),
+            y: comm.x.clone(),
+            squarings: 0,
+            max_squarings_per_poll,
+            chunk: config.chunk,
+        }
+    }
+}
+
 impl<P: RsaGroupParams> Hash for Comm<P> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.x.hash(state);
@@ -81,13 +159,58 @@ impl<PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
         pp: &TimeParams<RsaP>,
         proof: &PoEProof<RsaP, H2P>,
     ) -> Result<bool, Error> {
-        PoE::<PoEP, RsaP, H2P>::verify(&pp.x, &pp.y, pp.t, proof)
+        Ok(Self::ver_time_params_detailed(pp, proof).is_ok())
+    }
+
+    /// Like `ver_time_params`, but reports which check rejected the parameters
+    pub fn ver_time_params_detailed(
+        pp: &TimeParams<RsaP>,
+        proof: &PoEProof<RsaP, H2P>,
+    ) -> Result<(), TimeParamsError> {
+        if ![&pp.x, &pp.y, &proof.q]
+            .iter()
+            .all(|e| e.is_canonical_element())
+        {
+            return Err(TimeParamsError::GroupElementOutOfRange);
+        }
+        match PoE::<PoEP, RsaP, H2P>::verify_challenge(&pp.x, &pp.y, pp.t, proof) {
+            Ok(true) => {}
+            _ => return Err(TimeParamsError::MalformedCertificate),
+        }
+        if !PoE::<PoEP, RsaP, H2P>::verify_quotient(&pp.x, &pp.y, pp.t, proof) {
+            return Err(TimeParamsError::PoEInvalid);
+        }
+        Ok(())
     }
 
     pub fn commit<R: CryptoRng + Rng>(
         rng: &mut R,
         pp: &TimeParams<RsaP>,
         m: &[u8],
+    ) -> Result<(Comm<RsaP>, Opening<RsaP, H2P>), Error> {
+        Self::commit_with_ad(rng, pp, m, &[])
+    }
+
+    /// Binds the commitment to `ad` of any length in addition to the time parameter. The same
+    /// `ad` must be passed to `force_open_with_ad` and `ver_open_with_ad`.
+    pub fn commit_with_ad<R: CryptoRng + Rng>(
+        rng: &mut R,
+        pp: &TimeParams<RsaP>,
+        m: &[u8],
+        ad: &[u8],
+    ) -> Result<(Comm<RsaP>, Opening<RsaP, H2P>), Error> {
+        Self::commit_with_domain(rng, pp, m, ad, &[])
+    }
+
+    /// Like `commit_with_ad`, but also hashes `domain` (e.g. an auction id) into the key derived
+    /// from the repeated square, so reusing a group element across contexts yields unrelated keys.
+    /// The empty domain derives the original key, so existing commitments still open.
+    pub fn commit_with_domain<R: CryptoRng + Rng>(
+        rng: &mut R,
+        pp: &TimeParams<RsaP>,
+        m: &[u8],
+        ad: &[u8],
+        domain: &[u8],
     ) -> Result<(Comm<RsaP>, Opening<RsaP, H2P>), Error> {
         // Sample rando mizing factor
         let r = BigInt::from(rng.gen_biguint(128));
@@ -95,26 +218,136 @@ impl<PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
         let y = pp.y.power(&r);
 
         // Derive key from repeated square
-        debug_assert_eq!(H::output_size(), 32);
-        let key = H::digest(&y.n.to_bytes_be().1).to_vec();
-        let ad = pp.t.to_be_bytes(); // Time parameter as associated data
-        let ct = OneTimeKeyDeterministicAE::encrypt::<H>(&key, &m, &ad)?;
+        let key = Self::derive_key(&y, domain);
+        let ct = OneTimeKeyDeterministicAE::encrypt::<H>(&key, &m, &Self::full_ad(pp, ad))?;
         Ok((Comm { x, ct }, Opening::SELF(r)))
     }
 
+    /// Checks well-formedness without opening: `x` must pass the group membership check and not
+    /// be the identity, and `ct` must be long enough to hold the MAC.
+    pub fn check_comm(_pp: &TimeParams<RsaP>, comm: &Comm<RsaP>) -> Result<bool, Error> {
+        Ok(comm.x.is_canonical_element()
+            && comm.x != Hog::<RsaP>::identity()
+            && comm.ct.len() >= H::output_size())
+    }
+
     pub fn force_open(
         pp: &TimeParams<RsaP>,
         comm: &Comm<RsaP>,
     ) -> Result<(Option<Vec<u8>>, Opening<RsaP, H2P>), Error> {
-        // Compute and prove repeated square
-        let y = comm.x.power(&BigInt::from(2).pow(pp.t as u32));
+        let opened = Self::force_open_cancellable(pp, comm, &AtomicBool::new(false))?;
+        Ok(opened.expect("force open is never cancelled"))
+    }
+
+    pub fn force_open_with_config(
+        pp: &TimeParams<RsaP>,
+        comm: &Comm<RsaP>,
+        config: &ForceOpenConfig,
+    ) -> Result<(Option<Vec<u8>>, Opening<RsaP, H2P>), Error> {
+        let mut state = ForceOpenState::with_config(comm, CANCEL_CHECK_SQUARINGS, config);
+        loop {
+            if let Poll::Ready(result) = Self::force_open_resumable(pp, comm, &mut state) {
+                return result;
+            }
+        }
+    }
+
+    pub fn force_open_with_ad(
+        pp: &TimeParams<RsaP>,
+        comm: &Comm<RsaP>,
+        ad: &[u8],
+    ) -> Result<(Option<Vec<u8>>, Opening<RsaP, H2P>), Error> {
+        Self::force_open_with_domain(pp, comm, ad, &[])
+    }
+
+    pub fn force_open_with_domain(
+        pp: &TimeParams<RsaP>,
+        comm: &Comm<RsaP>,
+        ad: &[u8],
+        domain: &[u8],
+    ) -> Result<(Option<Vec<u8>>, Opening<RsaP, H2P>), Error> {
+        let mut state = ForceOpenState::new(comm, CANCEL_CHECK_SQUARINGS);
+        loop {
+            if let Poll::Ready(result) = Self::force_open_poll(pp, comm, ad, domain, &mut state) {
+                return result;
+            }
+        }
+    }
+
+    /// Checks `cancel` every `CANCEL_CHECK_SQUARINGS` squarings and returns `None` once it is set.
+    pub fn force_open_cancellable(
+        pp: &TimeParams<RsaP>,
+        comm: &Comm<RsaP>,
+        cancel: &AtomicBool,
+    ) -> Result<Option<(Option<Vec<u8>>, Opening<RsaP, H2P>)>, Error> {
+        let mut state = ForceOpenState::new(comm, CANCEL_CHECK_SQUARINGS);
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            if let Poll::Ready(result) = Self::force_open_resumable(pp, comm, &mut state) {
+                return result.map(Some);
+            }
+        }
+    }
+
+    /// Performs at most `state.max_squarings_per_poll` squarings per call, returning `Pending`
+    /// until all `t` squarings are done. The final result is identical to `force_open`.
+    pub fn force_open_resumable(
+        pp: &TimeParams<RsaP>,
+        comm: &Comm<RsaP>,
+        state: &mut ForceOpenState<RsaP>,
+    ) -> Poll<Result<(Option<Vec<u8>>, Opening<RsaP, H2P>), Error>> {
+        Self::force_open_poll(pp, comm, &[], &[], state)
+    }
+
+    fn force_open_poll(
+        pp: &TimeParams<RsaP>,
+        comm: &Comm<RsaP>,
+        ad: &[u8],
+        domain: &[u8],
+        state: &mut ForceOpenState<RsaP>,
+    ) -> Poll<Result<(Option<Vec<u8>>, Opening<RsaP, H2P>), Error>> {
+        if state.x != comm.x || state.squarings > pp.t {
+            return Poll::Ready(Err(Box::new(TCError::InvalidForceOpenState)));
+        }
+        let n_squarings = state.max_squarings_per_poll.min(pp.t - state.squarings);
+        let mut remaining = n_squarings;
+        while remaining > 0 {
+            let chunk = state.chunk.min(remaining);
+            state.y = if chunk == 1 {
+                state.y.op(&state.y)
+            } else {
+                state.y.power(&(BigInt::one() << chunk))
+            };
+            remaining -= chunk;
+        }
+        state.squarings += n_squarings;
+        if state.squarings < pp.t {
+            Poll::Pending
+        } else {
+            Poll::Ready(Self::force_open_from_square(
+                pp,
+                comm,
+                ad,
+                domain,
+                state.y.clone(),
+            ))
+        }
+    }
+
+    fn force_open_from_square(
+        pp: &TimeParams<RsaP>,
+        comm: &Comm<RsaP>,
+        ad: &[u8],
+        domain: &[u8],
+        y: Hog<RsaP>,
+    ) -> Result<(Option<Vec<u8>>, Opening<RsaP, H2P>), Error> {
         let proof = PoE::<PoEP, RsaP, H2P>::prove(&comm.x, &y, pp.t)?;
 
         // Derive key from repeated square
-        debug_assert_eq!(H::output_size(), 32);
-        let key = H::digest(&y.n.to_bytes_be().1).to_vec();
-        let ad = pp.t.to_be_bytes(); // Time parameter as associated data
-        let m = OneTimeKeyDeterministicAE::decrypt::<H>(&key, &comm.ct, &ad);
+        let key = Self::derive_key(&y, domain);
+        let m = OneTimeKeyDeterministicAE::decrypt::<H>(&key, &comm.ct, &Self::full_ad(pp, ad));
 
         let opening = Opening::FORCE(y, proof);
         match m {
@@ -135,8 +368,7 @@ impl<PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
         let proof = PoE::<PoEP, RsaP, H2P>::prove_cheating(&comm.x, &y, pp.t, order)?;
 
         // Derive key from repeated square
-        debug_assert_eq!(H::output_size(), 32);
-        let key = H::digest(&y.n.to_bytes_be().1).to_vec();
+        let key = Self::derive_key(&y, &[]);
         let ad = pp.t.to_be_bytes(); // Time parameter as associated data
         let m = OneTimeKeyDeterministicAE::decrypt::<H>(&key, &comm.ct, &ad);
 
@@ -153,13 +385,33 @@ impl<PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
         m: &Option<Vec<u8>>,
         opening: &Opening<RsaP, H2P>,
     ) -> Result<bool, Error> {
-        debug_assert_eq!(H::output_size(), 32);
+        Self::ver_open_with_ad(pp, comm, m, opening, &[])
+    }
+
+    pub fn ver_open_with_ad(
+        pp: &TimeParams<RsaP>,
+        comm: &Comm<RsaP>,
+        m: &Option<Vec<u8>>,
+        opening: &Opening<RsaP, H2P>,
+        ad: &[u8],
+    ) -> Result<bool, Error> {
+        Self::ver_open_with_domain(pp, comm, m, opening, ad, &[])
+    }
+
+    pub fn ver_open_with_domain(
+        pp: &TimeParams<RsaP>,
+        comm: &Comm<RsaP>,
+        m: &Option<Vec<u8>>,
+        opening: &Opening<RsaP, H2P>,
+        ad: &[u8],
+        domain: &[u8],
+    ) -> Result<bool, Error> {
+        let ad = Self::full_ad(pp, ad);
         match opening {
             Opening::SELF(r) => {
                 let x_valid = pp.x.power(r) == comm.x;
                 let y = pp.y.power(r);
-                let key = H::digest(&y.n.to_bytes_be().1).to_vec();
-                let ad = pp.t.to_be_bytes(); // Time parameter as associated data
+                let key = Self::derive_key(&y, domain);
                 let dec_m = OneTimeKeyDeterministicAE::decrypt::<H>(&key, &comm.ct, &ad);
                 match (m, dec_m) {
                     (Some(m), Ok(dec_m)) => Ok(x_valid && m == &dec_m),
@@ -169,8 +421,7 @@ impl<PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
             }
             Opening::FORCE(y, proof) => {
                 let proof_valid = PoE::<PoEP, RsaP, H2P>::verify(&comm.x, y, pp.t, proof)?;
-                let key = H::digest(&y.n.to_bytes_be().1).to_vec();
-                let ad = pp.t.to_be_bytes(); // Time parameter as associated data
+                let key = Self::derive_key(y, domain);
                 let dec_m = OneTimeKeyDeterministicAE::decrypt::<H>(&key, &comm.ct, &ad);
                 match (m, dec_m) {
                     (Some(m), Ok(dec_m)) => Ok(proof_valid && m == &dec_m),
@@ -180,6 +431,24 @@ impl<PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P: HashToPrime>
             }
         }
     }
+
+    // Hash of the repeated square, prefixed by the length-tagged domain if one is given. The empty
+    // domain keeps the original derivation, which the Solidity verifier also computes.
+    fn derive_key(y: &Hog<RsaP>, domain: &[u8]) -> Vec<u8> {
+        debug_assert_eq!(H::output_size(), 32);
+        let y_bytes = y.n.to_bytes_be().1;
+        if domain.is_empty() {
+            H::digest(&y_bytes).to_vec()
+        } else {
+            let len = (domain.len() as u64).to_be_bytes();
+            H::digest(&[&len[..], domain, &y_bytes].concat()).to_vec()
+        }
+    }
+
+    // Time parameter followed by the caller's associated data, all of which is MACed
+    fn full_ad(pp: &TimeParams<RsaP>, ad: &[u8]) -> Vec<u8> {
+        [&pp.t.to_be_bytes()[..], ad].concat()
+    }
 }
 
 pub struct OneTimeKeyDeterministicAE;
@@ -227,6 +496,56 @@ impl OneTimeKeyDeterministicAE {
     }
 }
 
+#[derive(Debug)]
+pub enum TCError {
+    InvalidForceOpenState,
+    MessageTooLong { len: usize, max_len: usize },
+}
+
+impl ErrorTrait for TCError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for TCError {
+    fn fmt(self: &Self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            TCError::InvalidForceOpenState => format!("force open state does not match commitment"),
+            TCError::MessageTooLong { len, max_len } => {
+                format!("message of {} bytes exceeds the fixed length {}", len, max_len)
+            }
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TimeParamsError {
+    PoEInvalid,
+    MalformedCertificate,
+    GroupElementOutOfRange,
+}
+
+impl ErrorTrait for TimeParamsError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for TimeParamsError {
+    fn fmt(self: &Self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            TimeParamsError::PoEInvalid => format!("proof of exponentiation does not verify"),
+            TimeParamsError::MalformedCertificate => format!("invalid hash to prime certificate"),
+            TimeParamsError::GroupElementOutOfRange => {
+                format!("group element is not a canonical group member")
+            }
+        };
+        write!(f, "{}", msg)
+    }
+}
+
 #[derive(Debug)]
 pub enum AEError {
     InvalidKeyFormat,
@@ -345,4 +664,259 @@ mod tests {
         assert!(TC::ver_open(&pp, &comm, &force_m, &force_opening).unwrap());
         assert_eq!(force_m, Some(m.to_vec()));
     }
+
+    #[test]
+    fn long_ad_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut m = [1u8; 32];
+        rng.fill(&mut m);
+        let mut ad = vec![0u8; 2048];
+        rng.fill(&mut ad[..]);
+        let mut ad_bad = ad.clone();
+        ad_bad[2040] ^= 1;
+        let some_m = Some(m.to_vec());
+
+        let (pp, _) = TC::gen_time_params(40).unwrap();
+        let (comm, self_opening) = TC::commit_with_ad(&mut rng, &pp, &m, &ad).unwrap();
+        assert!(TC::ver_open_with_ad(&pp, &comm, &some_m, &self_opening, &ad).unwrap());
+        assert!(!TC::ver_open_with_ad(&pp, &comm, &some_m, &self_opening, &ad_bad).unwrap());
+        assert!(!TC::ver_open_with_ad(&pp, &comm, &some_m, &self_opening, &ad[..2047]).unwrap());
+        assert!(!TC::ver_open(&pp, &comm, &some_m, &self_opening).unwrap());
+
+        let (force_m, force_opening) = TC::force_open_with_ad(&pp, &comm, &ad).unwrap();
+        assert_eq!(force_m, Some(m.to_vec()));
+        assert!(TC::ver_open_with_ad(&pp, &comm, &force_m, &force_opening, &ad).unwrap());
+        assert!(!TC::ver_open_with_ad(&pp, &comm, &force_m, &force_opening, &ad_bad).unwrap());
+
+        // Opening with the wrong ad fails decryption, so nothing is recovered
+        let (bad_m, bad_opening) = TC::force_open_with_ad(&pp, &comm, &ad_bad).unwrap();
+        assert_eq!(bad_m, None);
+        assert!(TC::ver_open_with_ad(&pp, &comm, &None, &bad_opening, &ad_bad).unwrap());
+    }
+
+    #[test]
+    fn domain_separation_test() {
+        let mut m = [1u8; 32];
+        StdRng::seed_from_u64(1u64).fill(&mut m);
+        let (pp, _) = TC::gen_time_params(40).unwrap();
+
+        // Same randomness, so both commitments share the repeated square
+        let (comm_a, opening_a) =
+            TC::commit_with_domain(&mut StdRng::seed_from_u64(0u64), &pp, &m, &[], b"auction 1")
+                .unwrap();
+        let (comm_b, opening_b) =
+            TC::commit_with_domain(&mut StdRng::seed_from_u64(0u64), &pp, &m, &[], b"auction 2")
+                .unwrap();
+        let (comm_legacy, _) = TC::commit(&mut StdRng::seed_from_u64(0u64), &pp, &m).unwrap();
+        assert_eq!(comm_a.x, comm_b.x);
+        assert_eq!(opening_a, opening_b);
+        assert!(comm_a.ct.iter().zip(comm_b.ct.iter()).any(|(a, b)| a != b));
+        assert_ne!(comm_a.ct, comm_legacy.ct);
+        assert_ne!(comm_b.ct, comm_legacy.ct);
+
+        let some_m = Some(m.to_vec());
+        assert!(
+            TC::ver_open_with_domain(&pp, &comm_a, &some_m, &opening_a, &[], b"auction 1").unwrap()
+        );
+        assert!(
+            !TC::ver_open_with_domain(&pp, &comm_a, &some_m, &opening_a, &[], b"auction 2")
+                .unwrap()
+        );
+        assert!(!TC::ver_open(&pp, &comm_a, &some_m, &opening_a).unwrap());
+
+        let (force_m, force_opening) =
+            TC::force_open_with_domain(&pp, &comm_b, &[], b"auction 2").unwrap();
+        assert_eq!(force_m, some_m);
+        assert!(TC::ver_open_with_domain(
+            &pp,
+            &comm_b,
+            &force_m,
+            &force_opening,
+            &[],
+            b"auction 2"
+        )
+        .unwrap());
+        let (wrong_m, _) = TC::force_open_with_domain(&pp, &comm_b, &[], b"auction 1").unwrap();
+        assert!(wrong_m.is_none());
+
+        // Commitments without a domain still open under the empty domain
+        let (legacy_m, _) = TC::force_open_with_domain(&pp, &comm_legacy, &[], &[]).unwrap();
+        assert_eq!(legacy_m, some_m);
+    }
+
+    #[test]
+    fn ver_time_params_detailed_test() {
+        let (pp, pp_proof) = TC::gen_time_params(40).unwrap();
+        assert_eq!(TC::ver_time_params_detailed(&pp, &pp_proof), Ok(()));
+
+        let mut cert_bad = pp_proof.clone();
+        cert_bad.cert.step_certificates[0].bu += 1;
+        assert_eq!(
+            TC::ver_time_params_detailed(&pp, &cert_bad),
+            Err(TimeParamsError::MalformedCertificate)
+        );
+        assert!(!TC::ver_time_params(&pp, &cert_bad).unwrap());
+
+        let mut q_bad = pp_proof.clone();
+        q_bad.q = q_bad.q.op(&pp.x);
+        assert_eq!(
+            TC::ver_time_params_detailed(&pp, &q_bad),
+            Err(TimeParamsError::PoEInvalid)
+        );
+
+        let mut q_out_of_range = pp_proof.clone();
+        q_out_of_range.q = Hog::<TestRsaParams>::from_nat(BigInt::from(2));
+        assert_eq!(
+            TC::ver_time_params_detailed(&pp, &q_out_of_range),
+            Err(TimeParamsError::GroupElementOutOfRange)
+        );
+    }
+
+    #[test]
+    fn ver_time_params_tampered_t_test() {
+        let (pp, pp_proof) = TC::gen_time_params(40).unwrap();
+
+        // Shorter t with the original y and proof fails the challenge
+        let mut t_bad = pp.clone();
+        t_bad.t = 20;
+        assert!(!TC::ver_time_params(&t_bad, &pp_proof).unwrap());
+        assert_eq!(
+            TC::ver_time_params_detailed(&t_bad, &pp_proof),
+            Err(TimeParamsError::MalformedCertificate)
+        );
+
+        // A fresh proof for the shorter t cannot relate x to the original y
+        let proof_bad = PoE::<
+            TestPoEParams,
+            TestRsaParams,
+            PocklingtonHash<TestPocklingtonParams, Keccak256>,
+        >::prove(&t_bad.x, &t_bad.y, 20)
+        .unwrap();
+        assert_eq!(
+            TC::ver_time_params_detailed(&t_bad, &proof_bad),
+            Err(TimeParamsError::PoEInvalid)
+        );
+    }
+
+    #[test]
+    fn ver_time_params_corruption_test() {
+        use rsa::poe::CorruptionKind;
+
+        let (pp, pp_proof) = TC::gen_time_params(40).unwrap();
+        assert!(TC::ver_time_params(&pp, &pp_proof).unwrap());
+        for (kind, err) in [
+            (CorruptionKind::WrongQ, TimeParamsError::PoEInvalid),
+            (
+                CorruptionKind::BadPocklingtonStep,
+                TimeParamsError::MalformedCertificate,
+            ),
+            (
+                CorruptionKind::OffByOneNonce,
+                TimeParamsError::MalformedCertificate,
+            ),
+        ] {
+            let proof_bad = pp_proof.corrupt(kind);
+            assert_ne!(proof_bad, pp_proof);
+            assert!(!TC::ver_time_params(&pp, &proof_bad).unwrap());
+            assert_eq!(TC::ver_time_params_detailed(&pp, &proof_bad), Err(err));
+        }
+    }
+
+    #[test]
+    fn security_summary_test() {
+        let (pp, _) = TC::gen_time_params(40).unwrap();
+        assert_eq!(pp.modulus_bits(), 2048);
+        assert_eq!(pp.squaring_count(), 40);
+        assert_eq!(
+            pp.security_summary(),
+            TimeParamsSecurity {
+                modulus_bits: 2048,
+                squaring_count: 40,
+            }
+        );
+    }
+
+    #[test]
+    fn force_open_resumable_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut m = [1u8; 32];
+        rng.fill(&mut m);
+
+        let (pp, _) = TC::gen_time_params(40).unwrap();
+        let (comm, _) = TC::commit(&mut rng, &pp, &m).unwrap();
+        let (force_m, force_opening) = TC::force_open(&pp, &comm).unwrap();
+
+        let mut state = ForceOpenState::new(&comm, 7);
+        let mut n_polls = 1;
+        let result = loop {
+            match TC::force_open_resumable(&pp, &comm, &mut state) {
+                Poll::Ready(result) => break result.unwrap(),
+                Poll::Pending => {
+                    // Resume from a persisted copy of the state
+                    state = state.clone();
+                    n_polls += 1;
+                }
+            }
+        };
+        assert_eq!(n_polls, 6);
+        assert_eq!(state.squarings, 40);
+        assert!(result == (force_m, force_opening));
+
+        // State from a different commitment is rejected
+        let (other_comm, _) = TC::commit(&mut rng, &pp, &m).unwrap();
+        let mut state = ForceOpenState::new(&other_comm, 7);
+        match TC::force_open_resumable(&pp, &comm, &mut state) {
+            Poll::Ready(result) => assert!(result.is_err()),
+            Poll::Pending => panic!("expected mismatched state to be rejected"),
+        }
+    }
+
+    #[test]
+    fn force_open_chunk_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut m = [1u8; 32];
+        rng.fill(&mut m);
+
+        // Not a multiple of the larger chunks, so the last chunk is partial
+        let (pp, _) = TC::gen_time_params(200).unwrap();
+        let (comm, _) = TC::commit(&mut rng, &pp, &m).unwrap();
+        let expected = TC::force_open(&pp, &comm).unwrap();
+        assert_eq!(expected.0, Some(m.to_vec()));
+        for chunk in [1, 8, 64] {
+            let opened =
+                TC::force_open_with_config(&pp, &comm, &ForceOpenConfig { chunk }).unwrap();
+            assert!(opened == expected);
+        }
+
+        // Chunks are cut short at the end of each poll
+        let mut state = ForceOpenState::with_config(&comm, 30, &ForceOpenConfig { chunk: 64 });
+        let result = loop {
+            if let Poll::Ready(result) = TC::force_open_resumable(&pp, &comm, &mut state) {
+                break result.unwrap();
+            }
+        };
+        assert!(result == expected);
+    }
+
+    #[test]
+    fn force_open_cancellable_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut m = [1u8; 32];
+        rng.fill(&mut m);
+
+        let (mut pp, _) = TC::gen_time_params(40).unwrap();
+        let (comm, _) = TC::commit(&mut rng, &pp, &m).unwrap();
+        let result = TC::force_open_cancellable(&pp, &comm, &AtomicBool::new(false))
+            .unwrap()
+            .unwrap();
+        assert!(result == TC::force_open(&pp, &comm).unwrap());
+        assert_eq!(result.0, Some(m.to_vec()));
+
+        // Would never finish if the flag were not checked before squaring
+        pp.t = u64::MAX;
+        let cancel = AtomicBool::new(true);
+        assert!(TC::force_open_cancellable(&pp, &comm, &cancel)
+            .unwrap()
+            .is_none());
+    }
 }
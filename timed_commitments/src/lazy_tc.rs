@@ -1,9 +1,11 @@
 use crate::{
-    basic_tc::{BasicTC, Comm as TCComm, Opening as TCOpening, TimeParams},
+    basic_tc::{
+        BasicTC, Comm as TCComm, Opening as TCOpening, TCError, TimeParams, TimeParamsError,
+    },
     Error, PedersenComm, PedersenParams,
 };
 use ark_ec::ProjectiveCurve;
-use ark_ff::{biginteger::BigInteger, PrimeField};
+use ark_ff::{biginteger::BigInteger, FpParameters, PrimeField, Zero};
 use digest::Digest;
 use num_bigint::Sign;
 use rand::{CryptoRng, Rng};
@@ -38,7 +40,40 @@ impl<G: ProjectiveCurve, P: RsaGroupParams> Hash for Comm<G, P> {
     }
 }
 
+impl<G: ProjectiveCurve, RsaP: RsaGroupParams> Comm<G, RsaP> {
+    pub fn pedersen_point(&self) -> &G {
+        &self.ped_comm
+    }
+
+    pub fn tc_commitment(&self) -> &TCComm<RsaP> {
+        &self.tc_comm
+    }
+}
+
 impl<G: ProjectiveCurve, RsaP: RsaGroupParams, H2P: HashToPrime> Opening<G, RsaP, H2P> {
+    // Committed message without the appended Pedersen opening, `None` if a force opening did not
+    // recover one
+    pub fn message_bytes(&self) -> Option<&[u8]> {
+        let f_bytes = <G::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8;
+        self.tc_m
+            .as_ref()
+            .filter(|tc_m| tc_m.len() >= f_bytes)
+            .map(|tc_m| &tc_m[..tc_m.len() - f_bytes])
+    }
+
+    pub fn is_self_opening(&self) -> bool {
+        matches!(self.tc_opening, TCOpening::SELF(_))
+    }
+
+    // Opening from its parts, e.g. when decoding a persisted opening
+    pub fn from_parts(tc_opening: TCOpening<RsaP, H2P>, tc_m: Option<Vec<u8>>) -> Self {
+        Self {
+            tc_opening,
+            tc_m,
+            _ped_g: PhantomData,
+        }
+    }
+
     // Parses Pedersen opening from opening and panics if invalid
     pub fn get_ped_opening(&self) -> G::ScalarField {
         let mut m = self.tc_m.as_ref().unwrap().to_vec();
@@ -85,16 +120,75 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         BasicTC::<PoEP, RsaP, H, H2P>::ver_time_params(pp, proof)
     }
 
+    pub fn ver_time_params_detailed(
+        pp: &TimeParams<RsaP>,
+        proof: &PoEProof<RsaP, H2P>,
+    ) -> Result<(), TimeParamsError> {
+        BasicTC::<PoEP, RsaP, H, H2P>::ver_time_params_detailed(pp, proof)
+    }
+
+    /// The Pedersen opening is appended to `m` inside the timed commitment. An empty `m` is
+    /// allowed, e.g. for a sentinel bid, and opens to an empty message rather than `None`.
     pub fn commit<R: CryptoRng + Rng>(
         rng: &mut R,
         time_pp: &TimeParams<RsaP>,
         ped_pp: &PedersenParams<G>,
         m: &[u8],
+    ) -> Result<(Comm<G, RsaP>, Opening<G, RsaP, H2P>), Error> {
+        Self::commit_with_domain(rng, time_pp, ped_pp, m, &[])
+    }
+
+    /// Derives the timed commitment key under `domain`, e.g. the auction id, see
+    /// `BasicTC::commit_with_domain`. The same `domain` must be passed to `force_open_with_domain`
+    /// and `ver_open_with_domain`.
+    pub fn commit_with_domain<R: CryptoRng + Rng>(
+        rng: &mut R,
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        m: &[u8],
+        domain: &[u8],
+    ) -> Result<(Comm<G, RsaP>, Opening<G, RsaP, H2P>), Error> {
+        Self::commit_encoded(rng, time_pp, ped_pp, m, m.to_vec(), domain)
+    }
+
+    /// Fixed-length mode: `m` is zero padded to `max_len` bytes and followed by its length as
+    /// 4 big-endian bytes, so commitments and force openings of messages up to `max_len` all
+    /// handle the same number of bytes. The Pedersen commitment reads the message little-endian,
+    /// so it commits to `m` as in `commit`. Open with `force_open_fixed_length` and
+    /// `ver_open_fixed_length`.
+    pub fn commit_fixed_length<R: CryptoRng + Rng>(
+        rng: &mut R,
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        m: &[u8],
+        max_len: usize,
+    ) -> Result<(Comm<G, RsaP>, Opening<G, RsaP, H2P>), Error> {
+        if m.len() > max_len || max_len > u32::MAX as usize {
+            return Err(Box::new(TCError::MessageTooLong {
+                len: m.len(),
+                max_len,
+            }));
+        }
+        let mut encoded = m.to_vec();
+        encoded.resize(max_len, 0u8);
+        encoded.extend_from_slice(&(m.len() as u32).to_be_bytes());
+        Self::commit_encoded(rng, time_pp, ped_pp, m, encoded, &[])
+    }
+
+    // Pedersen commits to `m`, the timed commitment to `encoded` followed by the Pedersen opening
+    fn commit_encoded<R: CryptoRng + Rng>(
+        rng: &mut R,
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        m: &[u8],
+        encoded: Vec<u8>,
+        domain: &[u8],
     ) -> Result<(Comm<G, RsaP>, Opening<G, RsaP, H2P>), Error> {
         let (ped_comm, ped_opening) = PedersenComm::<G>::commit(rng, ped_pp, m)?;
-        let mut tc_m = m.to_vec();
+        let mut tc_m = encoded;
         tc_m.append(&mut ped_opening.into_repr().to_bytes_be());
-        let (tc_comm, tc_opening) = BasicTC::<PoEP, RsaP, H, H2P>::commit(rng, time_pp, &tc_m)?;
+        let (tc_comm, tc_opening) =
+            BasicTC::<PoEP, RsaP, H, H2P>::commit_with_domain(rng, time_pp, &tc_m, &[], domain)?;
         Ok((
             Comm { ped_comm, tc_comm },
             Opening {
@@ -105,16 +199,87 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         ))
     }
 
+    /// Checks well-formedness without opening: the timed commitment must pass
+    /// `BasicTC::check_comm`, its ciphertext must fit the Pedersen opening, and the Pedersen
+    /// commitment must be a non-identity element of the prime order subgroup.
+    pub fn check_comm(time_pp: &TimeParams<RsaP>, comm: &Comm<G, RsaP>) -> Result<bool, Error> {
+        let f_bytes = <G::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8;
+        let ped_valid = !comm.ped_comm.is_zero()
+            && comm
+                .ped_comm
+                .mul(<<G::ScalarField as PrimeField>::Params as FpParameters>::MODULUS)
+                .is_zero();
+        Ok(ped_valid
+            && comm.tc_comm.ct.len() >= f_bytes + H::output_size()
+            && BasicTC::<PoEP, RsaP, H, H2P>::check_comm(time_pp, &comm.tc_comm)?)
+    }
+
+    /// Checks a claimed message against the Pedersen layer only, skipping the repeated squaring
+    /// of `force_open`. The Pedersen opening is hidden in the timed commitment, so it must be
+    /// supplied alongside the candidate, as in `selfOpenAuctionOptimized`.
+    pub fn verify_candidate(
+        ped_pp: &PedersenParams<G>,
+        comm: &Comm<G, RsaP>,
+        candidate: &[u8],
+        ped_opening: &G::ScalarField,
+    ) -> Result<bool, Error> {
+        PedersenComm::<G>::ver_open(ped_pp, &comm.ped_comm, candidate, ped_opening)
+    }
+
+    /// Refreshes the Pedersen blinding by adding `delta * h` to the commitment, keeping the
+    /// committed value. The timed layer is recommitted to the message and the refreshed opening,
+    /// so the new commitment can be self opened or force opened as usual. Panics if the opening
+    /// holds no message.
+    pub fn rerandomize_pedersen<R: CryptoRng + Rng>(
+        rng: &mut R,
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        comm: &Comm<G, RsaP>,
+        opening: &Opening<G, RsaP, H2P>,
+        delta: G::ScalarField,
+    ) -> Result<(Comm<G, RsaP>, Opening<G, RsaP, H2P>), Error> {
+        let ped_opening = opening.get_ped_opening() + delta;
+        let mut tc_m = opening.message_bytes().unwrap().to_vec();
+        tc_m.append(&mut ped_opening.into_repr().to_bytes_be());
+        let (tc_comm, tc_opening) =
+            BasicTC::<PoEP, RsaP, H, H2P>::commit_with_domain(rng, time_pp, &tc_m, &[], &[])?;
+        Ok((
+            Comm {
+                ped_comm: comm.ped_comm + &ped_pp.h.mul(&delta.into_repr()),
+                tc_comm,
+            },
+            Opening {
+                tc_opening,
+                tc_m: Some(tc_m),
+                _ped_g: PhantomData,
+            },
+        ))
+    }
+
     pub fn force_open(
         time_pp: &TimeParams<RsaP>,
         ped_pp: &PedersenParams<G>,
         comm: &Comm<G, RsaP>,
     ) -> Result<(Option<Vec<u8>>, Opening<G, RsaP, H2P>), Error> {
-        let (tc_m, tc_opening) = BasicTC::<PoEP, RsaP, H, H2P>::force_open(time_pp, &comm.tc_comm)?;
+        Self::force_open_with_domain(time_pp, ped_pp, comm, &[])
+    }
+
+    pub fn force_open_with_domain(
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        comm: &Comm<G, RsaP>,
+        domain: &[u8],
+    ) -> Result<(Option<Vec<u8>>, Opening<G, RsaP, H2P>), Error> {
+        let (tc_m, tc_opening) = BasicTC::<PoEP, RsaP, H, H2P>::force_open_with_domain(
+            time_pp,
+            &comm.tc_comm,
+            &[],
+            domain,
+        )?;
+        let f_bytes = <G::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8;
         match &tc_m {
-            Some(tc_m_inner) => {
+            Some(tc_m_inner) if tc_m_inner.len() >= f_bytes => {
                 let mut m = tc_m_inner.to_vec();
-                let f_bytes = <G::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8;
                 match nat_to_f(&BigInt::from_bytes_be(
                     Sign::Plus,
                     &m.split_off(m.len() - f_bytes),
@@ -152,7 +317,8 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
                     )),
                 }
             }
-            None => Ok((
+            // Missing or too short to carry a Pedersen opening
+            _ => Ok((
                 None,
                 Opening {
                     tc_opening,
@@ -163,6 +329,60 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         }
     }
 
+    pub fn force_open_fixed_length(
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        comm: &Comm<G, RsaP>,
+        max_len: usize,
+    ) -> Result<(Option<Vec<u8>>, Opening<G, RsaP, H2P>), Error> {
+        let (tc_m, tc_opening) = BasicTC::<PoEP, RsaP, H, H2P>::force_open_with_domain(
+            time_pp,
+            &comm.tc_comm,
+            &[],
+            &[],
+        )?;
+        let m = tc_m
+            .as_ref()
+            .and_then(|tc_m| Self::decode_fixed_length(ped_pp, comm, tc_m, max_len));
+        Ok((
+            m,
+            Opening {
+                tc_opening,
+                tc_m,
+                _ped_g: PhantomData,
+            },
+        ))
+    }
+
+    // Splits only at offsets fixed by `max_len`, and checks the Pedersen opening and the padding
+    // over the whole padded message, so the work done does not depend on the message length
+    fn decode_fixed_length(
+        ped_pp: &PedersenParams<G>,
+        comm: &Comm<G, RsaP>,
+        tc_m: &[u8],
+        max_len: usize,
+    ) -> Option<Vec<u8>> {
+        let f_bytes = <G::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8;
+        if tc_m.len() != max_len + 4 + f_bytes {
+            return None;
+        }
+        let (padded, rest) = tc_m.split_at(max_len);
+        let (len_bytes, ped_opening_bytes) = rest.split_at(4);
+        let len = u32::from_be_bytes(len_bytes.try_into().unwrap()) as usize;
+        let ped_opening = nat_to_f(&BigInt::from_bytes_be(Sign::Plus, ped_opening_bytes)).ok()?;
+        let ped_valid =
+            PedersenComm::<G>::ver_open(ped_pp, &comm.ped_comm, padded, &ped_opening).ok()?;
+        // Nonzero padding would make the Pedersen commitment open to a different message
+        let padding = padded.iter().enumerate().fold(0u8, |acc, (i, b)| {
+            acc | (b & ((i >= len) as u8).wrapping_neg())
+        });
+        if ped_valid && len <= max_len && padding == 0 {
+            Some(padded[..len].to_vec())
+        } else {
+            None
+        }
+    }
+
     pub fn force_open_cheating(
         time_pp: &TimeParams<RsaP>,
         ped_pp: &PedersenParams<G>,
@@ -229,16 +449,52 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
         m: &Option<Vec<u8>>,
         opening: &Opening<G, RsaP, H2P>,
     ) -> Result<bool, Error> {
-        let tc_valid = BasicTC::<PoEP, RsaP, H, H2P>::ver_open(
+        Self::ver_open_with_domain(time_pp, ped_pp, comm, m, opening, &[])
+    }
+
+    pub fn ver_open_fixed_length(
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        comm: &Comm<G, RsaP>,
+        m: &Option<Vec<u8>>,
+        opening: &Opening<G, RsaP, H2P>,
+        max_len: usize,
+    ) -> Result<bool, Error> {
+        let tc_valid = BasicTC::<PoEP, RsaP, H, H2P>::ver_open_with_domain(
+            time_pp,
+            &comm.tc_comm,
+            &opening.tc_m,
+            &opening.tc_opening,
+            &[],
+            &[],
+        )?;
+        let decoded = opening
+            .tc_m
+            .as_ref()
+            .and_then(|tc_m| Self::decode_fixed_length(ped_pp, comm, tc_m, max_len));
+        Ok(tc_valid && decoded == *m)
+    }
+
+    pub fn ver_open_with_domain(
+        time_pp: &TimeParams<RsaP>,
+        ped_pp: &PedersenParams<G>,
+        comm: &Comm<G, RsaP>,
+        m: &Option<Vec<u8>>,
+        opening: &Opening<G, RsaP, H2P>,
+        domain: &[u8],
+    ) -> Result<bool, Error> {
+        let tc_valid = BasicTC::<PoEP, RsaP, H, H2P>::ver_open_with_domain(
             time_pp,
             &comm.tc_comm,
             &opening.tc_m,
             &opening.tc_opening,
+            &[],
+            domain,
         )?;
+        let f_bytes = <G::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8;
         match &opening.tc_m {
-            Some(tc_m) => {
+            Some(tc_m) if tc_m.len() >= f_bytes => {
                 let mut m_computed = tc_m.to_vec();
-                let f_bytes = <G::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8;
                 let ped_opening = nat_to_f(&BigInt::from_bytes_be(
                     Sign::Plus,
                     &m_computed.split_off(m_computed.len() - f_bytes),
@@ -252,7 +508,8 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
                     None => Ok(tc_valid && !ped_valid),
                 }
             }
-            None => Ok(tc_valid && m.is_none()),
+            // Missing or too short to carry a Pedersen opening
+            _ => Ok(tc_valid && m.is_none()),
         }
     }
 }
@@ -261,9 +518,14 @@ impl<G: ProjectiveCurve, PoEP: PoEParams, RsaP: RsaGroupParams, H: Digest, H2P:
 mod tests {
     use super::*;
     use ark_bls12_381::G1Projective as G;
+    use ark_ff::UniformRand;
     use once_cell::sync::Lazy;
     use rand::{rngs::StdRng, SeedableRng};
-    use rsa::hash_to_prime::pocklington::{PocklingtonCertParams, PocklingtonHash};
+    use rsa::hash_to_prime::{
+        pocklington::{PocklingtonCertParams, PocklingtonHash},
+        MillerRabinRejectionSample,
+    };
+    use sha2::Sha256;
     use sha3::Keccak256;
     use std::str::FromStr;
 
@@ -310,6 +572,307 @@ mod tests {
         PocklingtonHash<TestPocklingtonParams, Keccak256>,
     >;
 
+    pub type MillerRabinTC =
+        LazyTC<G, TestPoEParams, TestRsaParams, Keccak256, MillerRabinRejectionSample<Keccak256>>;
+
+    pub type Sha256TC = LazyTC<
+        G,
+        TestPoEParams,
+        TestRsaParams,
+        Sha256,
+        PocklingtonHash<TestPocklingtonParams, Sha256>,
+    >;
+
+    #[test]
+    fn lazy_tc_miller_rabin_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut m = [1u8; 8];
+        rng.fill(&mut m);
+
+        let (time_pp, _) = MillerRabinTC::gen_time_params(40).unwrap();
+        let ped_pp = MillerRabinTC::gen_pedersen_params(&mut rng);
+
+        let (comm, self_opening) = MillerRabinTC::commit(&mut rng, &time_pp, &ped_pp, &m).unwrap();
+        assert!(MillerRabinTC::ver_open(
+            &time_pp,
+            &ped_pp,
+            &comm,
+            &Some(m.to_vec()),
+            &self_opening
+        )
+        .unwrap());
+
+        let (force_m, force_opening) = MillerRabinTC::force_open(&time_pp, &ped_pp, &comm).unwrap();
+        assert!(
+            MillerRabinTC::ver_open(&time_pp, &ped_pp, &comm, &force_m, &force_opening).unwrap()
+        );
+        assert_eq!(force_m, Some(m.to_vec()));
+    }
+
+    #[test]
+    fn lazy_tc_sha256_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut m = [1u8; 8];
+        rng.fill(&mut m);
+
+        let (time_pp, time_pp_proof) = Sha256TC::gen_time_params(40).unwrap();
+        assert!(Sha256TC::ver_time_params(&time_pp, &time_pp_proof).unwrap());
+        let ped_pp = Sha256TC::gen_pedersen_params(&mut rng);
+
+        let (comm, self_opening) = Sha256TC::commit(&mut rng, &time_pp, &ped_pp, &m).unwrap();
+        assert!(
+            Sha256TC::ver_open(&time_pp, &ped_pp, &comm, &Some(m.to_vec()), &self_opening).unwrap()
+        );
+
+        let (force_m, force_opening) = Sha256TC::force_open(&time_pp, &ped_pp, &comm).unwrap();
+        assert!(Sha256TC::ver_open(&time_pp, &ped_pp, &comm, &force_m, &force_opening).unwrap());
+        assert_eq!(force_m, Some(m.to_vec()));
+    }
+
+    #[test]
+    fn check_comm_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m = [1u8; 8];
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+        let (comm, _) = TC::commit(&mut rng, &time_pp, &ped_pp, &m).unwrap();
+        assert!(TC::check_comm(&time_pp, &comm).unwrap());
+
+        let mut tc_input_group_element_bad = comm.clone();
+        tc_input_group_element_bad.tc_comm.x = RsaHiddenOrderGroup::from_nat(BigInt::from(2));
+        assert!(!TC::check_comm(&time_pp, &tc_input_group_element_bad).unwrap());
+
+        let mut tc_input_identity = comm.clone();
+        tc_input_identity.tc_comm.x = RsaHiddenOrderGroup::identity();
+        assert!(!TC::check_comm(&time_pp, &tc_input_identity).unwrap());
+
+        let mut tc_ae_ct_short = comm.clone();
+        tc_ae_ct_short.tc_comm.ct.truncate(32);
+        assert!(!TC::check_comm(&time_pp, &tc_ae_ct_short).unwrap());
+
+        let mut ped_comm_identity = comm.clone();
+        ped_comm_identity.ped_comm = G::zero();
+        assert!(!TC::check_comm(&time_pp, &ped_comm_identity).unwrap());
+    }
+
+    #[test]
+    fn commit_with_domain_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m = [1u8; 8];
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+        let domain = 7u32.to_be_bytes();
+        let (comm, self_opening) =
+            TC::commit_with_domain(&mut rng, &time_pp, &ped_pp, &m, &domain).unwrap();
+        let some_m = Some(m.to_vec());
+        assert!(TC::ver_open_with_domain(
+            &time_pp,
+            &ped_pp,
+            &comm,
+            &some_m,
+            &self_opening,
+            &domain
+        )
+        .unwrap());
+        assert!(!TC::ver_open(&time_pp, &ped_pp, &comm, &some_m, &self_opening).unwrap());
+
+        let (force_m, force_opening) =
+            TC::force_open_with_domain(&time_pp, &ped_pp, &comm, &domain).unwrap();
+        assert_eq!(force_m, some_m);
+        assert!(TC::ver_open_with_domain(
+            &time_pp,
+            &ped_pp,
+            &comm,
+            &force_m,
+            &force_opening,
+            &domain
+        )
+        .unwrap());
+        let (undomained_m, _) = TC::force_open(&time_pp, &ped_pp, &comm).unwrap();
+        assert!(undomained_m.is_none());
+    }
+
+    #[test]
+    fn verify_candidate_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m = [1u8; 8];
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+        let (comm, self_opening) = TC::commit(&mut rng, &time_pp, &ped_pp, &m).unwrap();
+        let ped_opening = self_opening.get_ped_opening();
+
+        // No time parameters are involved, so no squaring can take place
+        assert!(TC::verify_candidate(&ped_pp, &comm, &m, &ped_opening).unwrap());
+        assert!(!TC::verify_candidate(&ped_pp, &comm, &[2u8; 8], &ped_opening).unwrap());
+        assert!(!TC::verify_candidate(&ped_pp, &comm, &m, &(ped_opening + ped_opening)).unwrap());
+    }
+
+    #[test]
+    fn rerandomize_pedersen_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m = [1u8; 8];
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+        let (comm, opening) = TC::commit(&mut rng, &time_pp, &ped_pp, &m).unwrap();
+
+        let delta = <G as ProjectiveCurve>::ScalarField::rand(&mut rng);
+        let (new_comm, new_opening) =
+            TC::rerandomize_pedersen(&mut rng, &time_pp, &ped_pp, &comm, &opening, delta).unwrap();
+        assert_ne!(new_comm.ped_comm, comm.ped_comm);
+        assert_ne!(new_comm.tc_comm, comm.tc_comm);
+        assert_eq!(
+            new_opening.get_ped_opening(),
+            opening.get_ped_opening() + delta
+        );
+        assert!(TC::check_comm(&time_pp, &new_comm).unwrap());
+
+        // Self opening
+        assert!(TC::ver_open(
+            &time_pp,
+            &ped_pp,
+            &new_comm,
+            &Some(m.to_vec()),
+            &new_opening
+        )
+        .unwrap());
+        // Old opening no longer verifies against the refreshed commitment
+        assert!(!TC::verify_candidate(&ped_pp, &new_comm, &m, &opening.get_ped_opening()).unwrap());
+
+        // Force opening recovers the bid with the refreshed blinding
+        let (force_m, force_opening) = TC::force_open(&time_pp, &ped_pp, &new_comm).unwrap();
+        assert_eq!(force_m, Some(m.to_vec()));
+        assert_eq!(
+            force_opening.get_ped_opening(),
+            new_opening.get_ped_opening()
+        );
+        assert!(TC::ver_open(&time_pp, &ped_pp, &new_comm, &force_m, &force_opening).unwrap());
+    }
+
+    #[test]
+    fn empty_message_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m: [u8; 0] = [];
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+        let (comm, self_opening) = TC::commit(&mut rng, &time_pp, &ped_pp, &m).unwrap();
+        assert!(TC::check_comm(&time_pp, &comm).unwrap());
+        let f_bytes = <<G as ProjectiveCurve>::ScalarField as PrimeField>::BigInt::NUM_LIMBS * 8;
+        assert_eq!(self_opening.tc_m.as_ref().unwrap().len(), f_bytes);
+        assert_eq!(self_opening.message_bytes(), Some(&m[..]));
+        assert!(TC::ver_open(&time_pp, &ped_pp, &comm, &Some(vec![]), &self_opening).unwrap());
+
+        let (force_m, force_opening) = TC::force_open(&time_pp, &ped_pp, &comm).unwrap();
+        assert_eq!(force_m, Some(vec![]));
+        assert_eq!(force_opening.message_bytes(), Some(&m[..]));
+        assert!(TC::ver_open(&time_pp, &ped_pp, &comm, &force_m, &force_opening).unwrap());
+
+        for opening in [&self_opening, &force_opening] {
+            assert!(!TC::ver_open(&time_pp, &ped_pp, &comm, &None, opening).unwrap());
+            // Commits to the same field element as the empty message
+            assert!(!TC::ver_open(&time_pp, &ped_pp, &comm, &Some(vec![0u8]), opening).unwrap());
+        }
+    }
+
+    #[test]
+    fn fixed_length_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let max_len = 16;
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+
+        let messages = [vec![1u8, 2, 3], vec![9u8; 10], vec![1u8, 0]];
+        let mut tc_m_lens = Vec::new();
+        for m in messages.iter() {
+            let (comm, self_opening) =
+                TC::commit_fixed_length(&mut rng, &time_pp, &ped_pp, m, max_len).unwrap();
+            tc_m_lens.push(self_opening.tc_m.as_ref().unwrap().len());
+            // Pedersen layer commits to the unpadded message
+            assert!(
+                TC::verify_candidate(&ped_pp, &comm, m, &self_opening.get_ped_opening()).unwrap()
+            );
+            let some_m = Some(m.clone());
+            assert!(TC::ver_open_fixed_length(
+                &time_pp,
+                &ped_pp,
+                &comm,
+                &some_m,
+                &self_opening,
+                max_len
+            )
+            .unwrap());
+
+            let (force_m, force_opening) =
+                TC::force_open_fixed_length(&time_pp, &ped_pp, &comm, max_len).unwrap();
+            assert_eq!(force_m, some_m);
+            assert_eq!(force_opening.tc_m, self_opening.tc_m);
+            assert!(TC::ver_open_fixed_length(
+                &time_pp,
+                &ped_pp,
+                &comm,
+                &force_m,
+                &force_opening,
+                max_len
+            )
+            .unwrap());
+
+            // Truncated message, for [1, 0] this is the same Pedersen value
+            let mut m_bad = m.clone();
+            m_bad.pop();
+            assert!(!TC::ver_open_fixed_length(
+                &time_pp,
+                &ped_pp,
+                &comm,
+                &Some(m_bad),
+                &force_opening,
+                max_len
+            )
+            .unwrap());
+            assert!(!TC::ver_open_fixed_length(
+                &time_pp,
+                &ped_pp,
+                &comm,
+                &None,
+                &force_opening,
+                max_len
+            )
+            .unwrap());
+        }
+        assert!(tc_m_lens.iter().all(|len| *len == tc_m_lens[0]));
+
+        assert!(TC::commit_fixed_length(&mut rng, &time_pp, &ped_pp, &[1u8; 17], max_len).is_err());
+    }
+
+    #[test]
+    fn accessors_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m = [3u8; 8];
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+        let (comm, self_opening) = TC::commit(&mut rng, &time_pp, &ped_pp, &m).unwrap();
+        let (_, force_opening) = TC::force_open(&time_pp, &ped_pp, &comm).unwrap();
+        assert!(self_opening.is_self_opening());
+        assert!(!force_opening.is_self_opening());
+
+        for opening in [&self_opening, &force_opening] {
+            let message = opening.message_bytes().unwrap();
+            assert_eq!(message, &m[..]);
+            assert!(PedersenComm::<G>::ver_open(
+                &ped_pp,
+                comm.pedersen_point(),
+                message,
+                &opening.get_ped_opening()
+            )
+            .unwrap());
+        }
+        assert!(BasicTC::<
+            TestPoEParams,
+            TestRsaParams,
+            Keccak256,
+            PocklingtonHash<TestPocklingtonParams, Keccak256>,
+        >::check_comm(&time_pp, comm.tc_commitment())
+        .unwrap());
+    }
+
     #[test]
     fn lazy_tc_test() {
         let mut rng = StdRng::seed_from_u64(0u64);
@@ -392,4 +955,169 @@ mod tests {
         )
         .unwrap());
     }
+
+    type TestBasicTC = BasicTC<
+        TestPoEParams,
+        TestRsaParams,
+        Keccak256,
+        PocklingtonHash<TestPocklingtonParams, Keccak256>,
+    >;
+
+    #[test]
+    fn short_plaintext_test() {
+        // Cheating committer whose timed commitment is shorter than a Pedersen opening
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (time_pp, _) = TC::gen_time_params(40).unwrap();
+        let ped_pp = TC::gen_pedersen_params(&mut rng);
+        let tc_m = vec![1u8; 8];
+        let (ped_comm, _) = PedersenComm::<G>::commit(&mut rng, &ped_pp, &[]).unwrap();
+        let (tc_comm, _) = TestBasicTC::commit(&mut rng, &time_pp, &tc_m).unwrap();
+        let comm = Comm { ped_comm, tc_comm };
+        assert!(!TC::check_comm(&time_pp, &comm).unwrap());
+
+        // Treated as an invalid Pedersen opening instead of underflowing
+        let (force_m, force_opening) = TC::force_open(&time_pp, &ped_pp, &comm).unwrap();
+        assert!(force_m.is_none());
+        assert!(force_opening.message_bytes().is_none());
+        assert!(TC::ver_open(&time_pp, &ped_pp, &comm, &None, &force_opening).unwrap());
+        assert!(!TC::ver_open(&time_pp, &ped_pp, &comm, &Some(tc_m), &force_opening).unwrap());
+    }
+
+    // Randomized round trips
+    mod proptests {
+        use super::*;
+        use proptest::{prelude::*, sample::Index};
+
+        proptest! {
+            // Each case generates time params and force opens, so keep the case count and `t` small
+            #![proptest_config(ProptestConfig::with_cases(16))]
+
+            #[test]
+            fn lazy_tc_round_trip_test(
+                seed in any::<u64>(),
+                // Pedersen commits to the message as a field element, so stay below 32 bytes
+                m in prop::collection::vec(any::<u8>(), 0..32),
+                domain in prop::collection::vec(any::<u8>(), 0..16),
+                t in 1u64..64,
+                idx in any::<Index>(),
+                flip in 1u8..=255,
+            ) {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let (time_pp, _) = TC::gen_time_params(t).unwrap();
+                let ped_pp = TC::gen_pedersen_params(&mut rng);
+                let (comm, self_opening) =
+                    TC::commit_with_domain(&mut rng, &time_pp, &ped_pp, &m, &domain).unwrap();
+                prop_assert!(TC::check_comm(&time_pp, &comm).unwrap());
+
+                let some_m = Some(m.clone());
+                prop_assert!(TC::ver_open_with_domain(
+                    &time_pp,
+                    &ped_pp,
+                    &comm,
+                    &some_m,
+                    &self_opening,
+                    &domain
+                )
+                .unwrap());
+
+                let (force_m, force_opening) =
+                    TC::force_open_with_domain(&time_pp, &ped_pp, &comm, &domain).unwrap();
+                prop_assert_eq!(&force_m, &some_m);
+                prop_assert!(TC::ver_open_with_domain(
+                    &time_pp,
+                    &ped_pp,
+                    &comm,
+                    &force_m,
+                    &force_opening,
+                    &domain
+                )
+                .unwrap());
+
+                for opening in [&self_opening, &force_opening] {
+                    if !m.is_empty() {
+                        let mut m_bad = m.clone();
+                        m_bad[idx.index(m.len())] ^= flip;
+                        prop_assert!(!TC::ver_open_with_domain(
+                            &time_pp,
+                            &ped_pp,
+                            &comm,
+                            &Some(m_bad),
+                            opening,
+                            &domain
+                        )
+                        .unwrap());
+                    }
+
+                    // Same Pedersen message, but the bytes differ
+                    let mut m_padded = m.clone();
+                    m_padded.push(0u8);
+                    prop_assert!(!TC::ver_open_with_domain(
+                        &time_pp,
+                        &ped_pp,
+                        &comm,
+                        &Some(m_padded),
+                        opening,
+                        &domain
+                    )
+                    .unwrap());
+
+                    if !domain.is_empty() {
+                        let mut domain_bad = domain.clone();
+                        domain_bad[idx.index(domain.len())] ^= flip;
+                        prop_assert!(!TC::ver_open_with_domain(
+                            &time_pp,
+                            &ped_pp,
+                            &comm,
+                            &some_m,
+                            opening,
+                            &domain_bad
+                        )
+                        .unwrap());
+                    }
+                }
+            }
+
+            #[test]
+            fn lazy_tc_short_plaintext_test(
+                seed in any::<u64>(),
+                tc_m in prop::collection::vec(any::<u8>(), 0..32),
+                domain in prop::collection::vec(any::<u8>(), 0..16),
+                t in 1u64..64,
+            ) {
+                // Cheating committer whose timed commitment cannot hold a Pedersen opening
+                let mut rng = StdRng::seed_from_u64(seed);
+                let (time_pp, _) = TC::gen_time_params(t).unwrap();
+                let ped_pp = TC::gen_pedersen_params(&mut rng);
+                let (ped_comm, _) = PedersenComm::<G>::commit(&mut rng, &ped_pp, &[]).unwrap();
+                let (tc_comm, _) =
+                    TestBasicTC::commit_with_domain(&mut rng, &time_pp, &tc_m, &[], &domain)
+                        .unwrap();
+                let comm = Comm { ped_comm, tc_comm };
+                prop_assert!(!TC::check_comm(&time_pp, &comm).unwrap());
+
+                let (force_m, force_opening) =
+                    TC::force_open_with_domain(&time_pp, &ped_pp, &comm, &domain).unwrap();
+                prop_assert!(force_m.is_none());
+                prop_assert!(force_opening.message_bytes().is_none());
+                prop_assert!(TC::ver_open_with_domain(
+                    &time_pp,
+                    &ped_pp,
+                    &comm,
+                    &None,
+                    &force_opening,
+                    &domain
+                )
+                .unwrap());
+                prop_assert!(!TC::ver_open_with_domain(
+                    &time_pp,
+                    &ped_pp,
+                    &comm,
+                    &Some(tc_m),
+                    &force_opening,
+                    &domain
+                )
+                .unwrap());
+            }
+        }
+    }
 }
@@ -1,13 +1,13 @@
 use ark_ec::ProjectiveCurve;
-use ark_ff::{PrimeField, UniformRand};
+use ark_ff::{FpParameters, PrimeField, UniformRand, Zero};
 use num_bigint::Sign;
-use rand::{CryptoRng, Rng};
+use rand::{CryptoRng, Rng, RngCore};
 use rsa::bigint::{nat_to_f, BigInt};
 
 use std::{
     hash::{Hash, Hasher}
 };
-use std::{error::Error as ErrorTrait, marker::PhantomData};
+use std::{error::Error as ErrorTrait, fmt, marker::PhantomData};
 
 pub mod basic_tc;
 pub mod lazy_tc;
@@ -21,6 +21,35 @@ pub struct PedersenParams<G: ProjectiveCurve> {
     pub h: G,
 }
 
+impl<G: ProjectiveCurve> PedersenParams<G> {
+    // Rejects generators that trivially break binding. Independence of g and h is not checkable,
+    // it relies on both being sampled at random.
+    pub fn validate(&self) -> Result<(), Error> {
+        let modulus = <<G::ScalarField as PrimeField>::Params as FpParameters>::MODULUS;
+        if self.g.is_zero() || self.h.is_zero() {
+            Err(Box::new(PedersenError::IdentityGenerator))
+        } else if self.g == self.h {
+            Err(Box::new(PedersenError::EqualGenerators))
+        } else if !self.g.mul(modulus).is_zero() || !self.h.mul(modulus).is_zero() {
+            Err(Box::new(PedersenError::GeneratorNotInSubgroup))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn sample<R: CryptoRng + Rng>(rng: &mut R) -> Self {
+        loop {
+            let pp = PedersenParams {
+                g: G::rand(rng).into(),
+                h: G::rand(rng).into(),
+            };
+            if pp.validate().is_ok() {
+                return pp;
+            }
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct PedersenComm<G: ProjectiveCurve> {
     _g: PhantomData<G>,
@@ -33,10 +62,33 @@ pub struct PedComm<G: ProjectiveCurve> {
 
 impl<G: ProjectiveCurve> PedersenComm<G> {
     pub fn gen_pedersen_params<R: CryptoRng + Rng>(rng: &mut R) -> PedersenParams<G> {
-        PedersenParams {
-            g: G::rand(rng).into(),
-            h: G::rand(rng).into(),
+        PedersenParams::sample(rng)
+    }
+
+    // Also returns the randomness consumed, from which the same params can be regenerated
+    // independently of the rng implementation
+    pub fn gen_pedersen_params_recorded<R: CryptoRng + Rng>(
+        rng: &mut R,
+    ) -> (PedersenParams<G>, ParamSeed) {
+        let mut recorder = RecordingRng {
+            rng,
+            bytes: Vec::new(),
+        };
+        let pp = PedersenParams::sample(&mut recorder);
+        (pp, ParamSeed(recorder.bytes))
+    }
+
+    pub fn gen_pedersen_params_from_seed(seed: &ParamSeed) -> Result<PedersenParams<G>, Error> {
+        let mut replay = ReplayRng {
+            bytes: &seed.0,
+            pos: 0,
+            exhausted: false,
+        };
+        let pp = PedersenParams::sample(&mut replay);
+        if replay.exhausted || replay.pos != seed.0.len() {
+            return Err(Box::new(PedersenError::SeedMismatch));
         }
+        Ok(pp)
     }
 
     pub fn commit<R: CryptoRng + Rng>(
@@ -84,10 +136,7 @@ impl<G: ProjectiveCurve> Hash for PedComm<G> {
 
 impl<G: ProjectiveCurve> PedComm<G> {
     pub fn gen_pedersen_params<R: CryptoRng + Rng>(rng: &mut R) -> PedersenParams<G> {
-        PedersenParams {
-            g: G::rand(rng).into(),
-            h: G::rand(rng).into(),
-        }
+        PedersenParams::sample(rng)
     }
 
     pub fn commit<R: CryptoRng + Rng>(
@@ -125,3 +174,156 @@ impl<G: ProjectiveCurve> PedComm<G> {
         }
     }
 }
+
+/// Randomness consumed while generating parameters, in the order it was drawn.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParamSeed(pub Vec<u8>);
+
+// Passes through the wrapped rng, recording every value drawn as little endian bytes
+struct RecordingRng<'a, R: CryptoRng + Rng> {
+    rng: &'a mut R,
+    bytes: Vec<u8>,
+}
+
+impl<'a, R: CryptoRng + Rng> RngCore for RecordingRng<'a, R> {
+    fn next_u32(&mut self) -> u32 {
+        let v = self.rng.next_u32();
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+        v
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let v = self.rng.next_u64();
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+        v
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+        self.bytes.extend_from_slice(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl<'a, R: CryptoRng + Rng> CryptoRng for RecordingRng<'a, R> {}
+
+// Replays recorded bytes, yielding zeros and flagging `exhausted` once they run out
+struct ReplayRng<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    exhausted: bool,
+}
+
+impl<'a> RngCore for ReplayRng<'a> {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u8; 4];
+        self.fill_bytes(&mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        self.fill_bytes(&mut buf);
+        u64::from_le_bytes(buf)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let n = dest.len().min(self.bytes.len() - self.pos);
+        dest[..n].copy_from_slice(&self.bytes[self.pos..self.pos + n]);
+        dest[n..].iter_mut().for_each(|b| *b = 0);
+        self.exhausted |= n < dest.len();
+        self.pos += n;
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl<'a> CryptoRng for ReplayRng<'a> {}
+
+#[derive(Debug)]
+pub enum PedersenError {
+    IdentityGenerator,
+    EqualGenerators,
+    GeneratorNotInSubgroup,
+    SeedMismatch,
+}
+
+impl ErrorTrait for PedersenError {
+    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for PedersenError {
+    fn fmt(self: &Self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            PedersenError::IdentityGenerator => format!("pedersen generator is the identity"),
+            PedersenError::EqualGenerators => format!("pedersen generators are equal"),
+            PedersenError::GeneratorNotInSubgroup => {
+                format!("pedersen generator is not in the prime order subgroup")
+            }
+            PedersenError::SeedMismatch => {
+                format!("param seed does not match the randomness consumed")
+            }
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::G1Projective as G;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn pedersen_params_validate_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let pp = PedersenComm::<G>::gen_pedersen_params(&mut rng);
+        assert!(pp.validate().is_ok());
+
+        let equal_pp = PedersenParams { g: pp.g, h: pp.g };
+        let err = equal_pp.validate().err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<PedersenError>(),
+            Some(PedersenError::EqualGenerators)
+        ));
+        let identity_pp = PedersenParams {
+            g: pp.g,
+            h: G::zero(),
+        };
+        let err = identity_pp.validate().err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<PedersenError>(),
+            Some(PedersenError::IdentityGenerator)
+        ));
+    }
+
+    #[test]
+    fn pedersen_params_seed_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (pp, seed) = PedersenComm::<G>::gen_pedersen_params_recorded(&mut rng);
+        assert_eq!(
+            PedersenComm::<G>::gen_pedersen_params_from_seed(&seed).unwrap(),
+            pp
+        );
+
+        // Same params as the unrecorded generator given the same rng
+        let mut rng = StdRng::seed_from_u64(0u64);
+        assert_eq!(PedersenComm::<G>::gen_pedersen_params(&mut rng), pp);
+
+        let mut short_seed = seed.clone();
+        short_seed.0.pop();
+        assert!(PedersenComm::<G>::gen_pedersen_params_from_seed(&short_seed).is_err());
+        let mut long_seed = seed.clone();
+        long_seed.0.push(0);
+        assert!(PedersenComm::<G>::gen_pedersen_params_from_seed(&long_seed).is_err());
+    }
+}